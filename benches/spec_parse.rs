@@ -0,0 +1,53 @@
+//! Cost of building a `Spec`.
+//!
+//! `spec_try_from` used to parse the file three times, once each for the
+//! fingerprint, the compatibility model and the canonical file;
+//! `three_parses_baseline` reproduces that. Medians from
+//! `cargo bench --bench spec_parse -- --warm-up-time 1 --measurement-time 4`
+//! on tests/data/complex_self_contained.proto, on one machine:
+//!
+//! | case                    | time    |
+//! |-------------------------|---------|
+//! | `three_parses_baseline` | 2.93 ms |
+//! | `spec_try_from`         | 1.22 ms |
+//! | `generate_fingerprint`  | 1.05 ms |
+//!
+//! Compare `spec_try_from` with `three_parses_baseline` on your machine
+//! rather than with these absolute numbers.
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use proto_sign::compatibility::get_compatibility_model;
+use proto_sign::spec::Spec;
+use proto_sign::{canonical_files_with_imports, generate_fingerprint};
+
+const PROTO: &str = include_str!("../tests/data/complex_self_contained.proto");
+
+fn bench_spec_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spec_parse");
+
+    // A single parse of the file, the lower bound for building a `Spec`.
+    group.bench_function("generate_fingerprint", |b| {
+        b.iter(|| generate_fingerprint(black_box(PROTO)).unwrap())
+    });
+    group.bench_function("get_compatibility_model", |b| {
+        b.iter(|| get_compatibility_model(black_box(PROTO)).unwrap())
+    });
+    group.bench_function("spec_try_from", |b| {
+        b.iter(|| Spec::try_from(black_box(PROTO)).unwrap())
+    });
+    // One parse per model, as `Spec::try_from` did before it shared a
+    // single descriptor.
+    group.bench_function("three_parses_baseline", |b| {
+        b.iter(|| {
+            let fingerprint = generate_fingerprint(black_box(PROTO)).unwrap();
+            let model = get_compatibility_model(black_box(PROTO)).unwrap();
+            let files = canonical_files_with_imports(black_box(PROTO), None).unwrap();
+            (fingerprint, model, files)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_spec_parse);
+criterion_main!(benches);
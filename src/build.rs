@@ -0,0 +1,276 @@
+//! Fingerprint constants for build scripts.
+//!
+//! Call this from `build.rs` next to `prost-build` or `tonic-build` to embed
+//! the fingerprint of every compiled `.proto` file, and the closure
+//! fingerprint of every service, into the crate being built:
+//!
+//! ```no_run
+//! // build.rs
+//! proto_sign::build::compile_protos(&["proto/acme/v1/order.proto"], &["proto"]).unwrap();
+//! ```
+//!
+//! ```ignore
+//! // src/lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/proto_sign_fingerprints.rs"));
+//! ```
+//!
+//! The generated file holds `pub const FINGERPRINT_<PKG>_<FILE>: &str` for each
+//! file, `pub const SERVICE_FINGERPRINT_<PKG>_<SERVICE>: &str` for each
+//! service, and the `FILE_FINGERPRINTS` and `SERVICE_FINGERPRINTS` tables.
+
+use crate::canonical::CanonicalFile;
+use crate::fingerprint::{FingerprintOptions, closure_fingerprint, fingerprint_with_options};
+use crate::source::FileSystemSources;
+use anyhow::Context;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Name of the generated file inside `OUT_DIR`.
+pub const GENERATED_FILE_NAME: &str = "proto_sign_fingerprints.rs";
+
+/// Configures fingerprint generation for a build script.
+#[derive(Debug, Clone)]
+pub struct Builder {
+    includes: Vec<PathBuf>,
+    options: FingerprintOptions,
+    out_file: Option<PathBuf>,
+    emit_rerun_if_changed: bool,
+}
+
+/// Returns a [`Builder`] with default settings.
+pub fn configure() -> Builder {
+    Builder {
+        includes: Vec::new(),
+        options: FingerprintOptions::default(),
+        out_file: None,
+        emit_rerun_if_changed: true,
+    }
+}
+
+/// Writes fingerprint constants for `protos` to
+/// `$OUT_DIR/proto_sign_fingerprints.rs`, resolving imports against
+/// `includes`. Returns the path of the generated file.
+pub fn compile_protos(
+    protos: &[impl AsRef<Path>],
+    includes: &[impl AsRef<Path>],
+) -> anyhow::Result<PathBuf> {
+    includes
+        .iter()
+        .fold(configure(), |builder, dir| builder.include(dir.as_ref()))
+        .compile(protos)
+}
+
+impl Builder {
+    /// Adds an import root. Import paths in the generated tables are also
+    /// made relative to the first root that contains the file.
+    pub fn include(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.includes.push(dir.into());
+        self
+    }
+
+    /// Sets the profile, hash and encoding of file fingerprints. Service
+    /// fingerprints are always `ps1-sha256` closure fingerprints.
+    pub fn options(mut self, options: FingerprintOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Writes the generated code to `path` instead of `$OUT_DIR`.
+    pub fn out_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.out_file = Some(path.into());
+        self
+    }
+
+    /// Whether to print `cargo:rerun-if-changed` for the protos and include
+    /// directories. On by default.
+    pub fn emit_rerun_if_changed(mut self, emit: bool) -> Self {
+        self.emit_rerun_if_changed = emit;
+        self
+    }
+
+    /// Generates the constants and writes them to the output file.
+    pub fn compile(&self, protos: &[impl AsRef<Path>]) -> anyhow::Result<PathBuf> {
+        let out_file = match &self.out_file {
+            Some(path) => path.clone(),
+            None => {
+                let out_dir = std::env::var_os("OUT_DIR")
+                    .context("OUT_DIR is not set; call this from build.rs or set out_file")?;
+                PathBuf::from(out_dir).join(GENERATED_FILE_NAME)
+            }
+        };
+        let code = self.generate(protos)?;
+        std::fs::write(&out_file, code)
+            .with_context(|| format!("Failed to write '{}'", out_file.display()))?;
+
+        if self.emit_rerun_if_changed {
+            for path in protos
+                .iter()
+                .map(AsRef::as_ref)
+                .chain(self.includes.iter().map(PathBuf::as_path))
+            {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+        Ok(out_file)
+    }
+
+    /// Returns the generated Rust source without writing it.
+    pub fn generate(&self, protos: &[impl AsRef<Path>]) -> anyhow::Result<String> {
+        let mut files = BTreeMap::new();
+        let mut services = BTreeMap::new();
+        let mut names = BTreeMap::new();
+
+        for path in protos.iter().map(AsRef::as_ref) {
+            let canonical_files = self.parse(path)?;
+            let file = &canonical_files[0];
+            let import_path = self.import_path(path);
+
+            let name = format!(
+                "FINGERPRINT_{}",
+                const_name(&[package_part(file), file_stem(&import_path)])
+            );
+            claim(&mut names, &name, &import_path)?;
+            let fingerprint = fingerprint_with_options(file, &self.options)
+                .with_context(|| format!("Failed to fingerprint '{}'", path.display()))?;
+            files.insert(import_path, (name, fingerprint));
+
+            for service in &file.services {
+                let full_name = match &file.package {
+                    Some(package) => format!("{package}.{}", service.name),
+                    None => service.name.clone(),
+                };
+                let name = format!(
+                    "SERVICE_FINGERPRINT_{}",
+                    const_name(&[package_part(file), screaming_snake(&service.name)])
+                );
+                claim(&mut names, &name, &full_name)?;
+                let closure = closure_fingerprint(&canonical_files, &full_name)?;
+                services.insert(full_name, (name, closure.fingerprint));
+            }
+        }
+
+        let mut out = String::new();
+        writeln!(out, "// @generated by proto-sign. Do not edit.")?;
+        for (path, (name, fingerprint)) in &files {
+            writeln!(out)?;
+            writeln!(out, "/// Fingerprint of `{path}`.")?;
+            writeln!(out, "pub const {name}: &str = {fingerprint:?};")?;
+        }
+        for (full_name, (name, fingerprint)) in &services {
+            writeln!(out)?;
+            writeln!(out, "/// Closure fingerprint of service `{full_name}`.")?;
+            writeln!(out, "pub const {name}: &str = {fingerprint:?};")?;
+        }
+        writeln!(out)?;
+        writeln!(out, "/// Every file fingerprint, keyed by import path.")?;
+        writeln!(out, "pub const FILE_FINGERPRINTS: &[(&str, &str)] = &[")?;
+        for (path, (name, _)) in &files {
+            writeln!(out, "    ({path:?}, {name}),")?;
+        }
+        writeln!(out, "];")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "/// Every service fingerprint, keyed by fully qualified service name."
+        )?;
+        writeln!(out, "pub const SERVICE_FINGERPRINTS: &[(&str, &str)] = &[")?;
+        for (full_name, (name, _)) in &services {
+            writeln!(out, "    ({full_name:?}, {name}),")?;
+        }
+        writeln!(out, "];")?;
+        Ok(out)
+    }
+
+    /// Parses `path` and its imports, the file itself first.
+    fn parse(&self, path: &Path) -> anyhow::Result<Vec<CanonicalFile>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read '{}'", path.display()))?;
+        let sources = FileSystemSources::with_includes(path, &self.includes);
+        let descriptors =
+            crate::parse::parse_all_with_sources(&content, &path.display().to_string(), &sources)?;
+        Ok(descriptors
+            .iter()
+            .map(crate::normalize::normalize_file)
+            .collect())
+    }
+
+    /// The path of `path` relative to the first include that contains it,
+    /// with `/` separators.
+    fn import_path(&self, path: &Path) -> String {
+        let relative = self
+            .includes
+            .iter()
+            .find_map(|dir| path.strip_prefix(dir).ok())
+            .unwrap_or(path);
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Records that `name` is generated for `owner`, failing on a collision.
+fn claim(names: &mut BTreeMap<String, String>, name: &str, owner: &str) -> anyhow::Result<()> {
+    if let Some(previous) = names.insert(name.to_string(), owner.to_string()) {
+        anyhow::bail!("'{previous}' and '{owner}' would both generate the constant {name}");
+    }
+    Ok(())
+}
+
+fn package_part(file: &CanonicalFile) -> String {
+    file.package
+        .as_deref()
+        .map(|package| package.replace('.', "_"))
+        .unwrap_or_default()
+}
+
+fn file_stem(import_path: &str) -> String {
+    let file_name = import_path.rsplit('/').next().unwrap_or(import_path);
+    file_name
+        .strip_suffix(".proto")
+        .unwrap_or(file_name)
+        .to_string()
+}
+
+/// Joins the non-empty parts with `_` and upper-cases the result, replacing
+/// anything that is not valid in an identifier with `_`.
+fn const_name(parts: &[String]) -> String {
+    parts
+        .iter()
+        .filter(|part| !part.is_empty())
+        .map(|part| part.as_str())
+        .collect::<Vec<_>>()
+        .join("_")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Converts a CamelCase name to SCREAMING_SNAKE_CASE, keeping acronyms
+/// together: `HTTPOrderService` becomes `HTTP_ORDER_SERVICE`.
+fn screaming_snake(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if i > 0 && c.is_ascii_uppercase() {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_is_lower)
+            {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_uppercase());
+    }
+    out
+}
@@ -1,4 +1,5 @@
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 // Note: Using BTreeSet for sorted, unique collections.
 // This requires `Ord` to be derived.
@@ -9,14 +10,14 @@ use std::collections::{BTreeMap, BTreeSet};
 //==============================================================================
 
 /// Represents a range of reserved numbers (for fields or enum values).
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ReservedRange {
     pub start: i32,
     pub end: i32,
 }
 
 /// Represents a reserved name.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ReservedName {
     pub name: String,
 }
@@ -26,7 +27,10 @@ pub struct ReservedName {
 //==============================================================================
 
 /// Represents the semantically significant content of a .proto file.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(default)]
 pub struct CanonicalFile {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub package: Option<String>,
@@ -101,7 +105,10 @@ pub struct CanonicalFile {
 }
 
 /// Represents a Protobuf message.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(default)]
 pub struct CanonicalMessage {
     pub name: String,
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
@@ -130,13 +137,17 @@ pub struct CanonicalMessage {
 
 /// Represents a field within a Protobuf message.
 /// The sort order is primarily by field number.
-#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(default)]
 pub struct CanonicalField {
     pub name: String,
     pub number: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>, // "optional", "required", "repeated"
     pub type_name: String,
+    /// Proto2 group: encoded with start/end-group tags rather than length-delimited.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub group: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub oneof_index: Option<i32>,
 
@@ -175,6 +186,72 @@ pub struct CanonicalField {
     pub options: BTreeMap<String, String>,
 }
 
+impl CanonicalFile {
+    /// Every message, nested ones included, keyed by its dotted path within
+    /// the package (e.g. `Order.Line`).
+    pub fn messages_by_path(&self) -> BTreeMap<String, &CanonicalMessage> {
+        fn collect<'a>(
+            messages: &'a BTreeSet<CanonicalMessage>,
+            prefix: &str,
+            out: &mut BTreeMap<String, &'a CanonicalMessage>,
+        ) {
+            for message in messages {
+                let path = qualify(prefix, &message.name);
+                collect(&message.nested_messages, &path, out);
+                out.insert(path, message);
+            }
+        }
+
+        let mut out = BTreeMap::new();
+        collect(&self.messages, "", &mut out);
+        out
+    }
+
+    /// Every enum, nested ones included, keyed by its dotted path within the
+    /// package (e.g. `Order.Status`).
+    pub fn enums_by_path(&self) -> BTreeMap<String, &CanonicalEnum> {
+        let mut out: BTreeMap<String, &CanonicalEnum> = self
+            .enums
+            .iter()
+            .map(|enum_def| (enum_def.name.clone(), enum_def))
+            .collect();
+        for (path, message) in self.messages_by_path() {
+            for enum_def in &message.nested_enums {
+                out.insert(qualify(&path, &enum_def.name), enum_def);
+            }
+        }
+        out
+    }
+
+    /// Every service, keyed by name.
+    pub fn services_by_path(&self) -> BTreeMap<String, &CanonicalService> {
+        self.services
+            .iter()
+            .map(|service| (service.name.clone(), service))
+            .collect()
+    }
+}
+
+/// Qualifies `name` with the dot-separated `scope`, which may be empty.
+pub(crate) fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+impl CanonicalField {
+    /// Human-readable type, distinguishing groups from message-typed fields.
+    pub fn display_type(&self) -> String {
+        if self.group {
+            format!("group {}", self.type_name)
+        } else {
+            self.type_name.clone()
+        }
+    }
+}
+
 // Custom implementation of Ord for CanonicalField to sort by `number` first.
 impl Ord for CanonicalField {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
@@ -191,7 +268,10 @@ impl PartialOrd for CanonicalField {
 }
 
 /// Represents a Protobuf enum.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(default)]
 pub struct CanonicalEnum {
     pub name: String,
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
@@ -216,20 +296,36 @@ pub struct CanonicalEnum {
 
 /// Represents a single value within a Protobuf enum.
 /// The sort order is primarily by number.
-#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(default)]
 pub struct CanonicalEnumValue {
     pub name: String,
     pub number: i32,
+
+    // Enum value options
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_redact: Option<bool>,
+
+    // Custom options keyed by extension field number
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub options: BTreeMap<String, String>,
 }
 
 /// Represents a protobuf extension field definition.
 /// Extensions are fields that extend existing messages.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(default)]
 pub struct CanonicalExtension {
     pub name: String,
     pub number: i32,
     pub extendee: String, // The message being extended (fully qualified)
     pub type_name: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub group: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>, // "optional", "required", "repeated"
 
@@ -256,7 +352,10 @@ impl PartialOrd for CanonicalEnumValue {
 }
 
 /// Represents a Protobuf service.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(default)]
 pub struct CanonicalService {
     pub name: String,
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
@@ -264,7 +363,10 @@ pub struct CanonicalService {
 }
 
 /// Represents a method within a service.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(default)]
 pub struct CanonicalMethod {
     pub name: String,
     pub input_type: String,
@@ -280,3 +382,10 @@ pub struct CanonicalMethod {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated: Option<bool>,
 }
+
+/// Returns the JSON Schema of the serialized [`CanonicalFile`] format.
+///
+/// The published copy lives in `schema/canonical-file.schema.json`.
+pub fn canonical_json_schema() -> schemars::Schema {
+    schemars::schema_for!(CanonicalFile)
+}
@@ -0,0 +1,496 @@
+//! Schema changelogs.
+//!
+//! A [`Changelog`] describes the API changes between consecutive versions of
+//! a file: the added, deprecated, removed and changed elements of the
+//! [`CanonicalDiff`] between them, each annotated with the
+//! [`BreakingEngine`](crate::compat::BreakingEngine) rules that report it as
+//! breaking. It renders as Markdown, newest version first. Versions can be
+//! given as files or read from a range of git history.
+
+use crate::canonical::qualify;
+use crate::compat::{BreakingChange, BreakingConfig, BreakingLocation};
+use crate::diff::{CanonicalDiff, ChangeClass, ChangeKind, DiffElement, DiffEntry};
+use crate::source::VirtualFileMap;
+use crate::spec::Spec;
+use serde::Serialize;
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The changes between consecutive versions, oldest first.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Changelog {
+    pub releases: Vec<Release>,
+}
+
+/// The changes from one version to the next.
+#[derive(Debug, Clone, Serialize)]
+pub struct Release {
+    pub version: String,
+    pub previous_version: String,
+    pub added: Vec<ReleaseEntry>,
+    /// Elements that became `deprecated`.
+    pub deprecated: Vec<ReleaseEntry>,
+    pub removed: Vec<ReleaseEntry>,
+    /// Other non-cosmetic changes to existing elements.
+    pub changed: Vec<ReleaseEntry>,
+    /// Breaking changes reported by the selected rules that match no listed
+    /// element, such as a required field set changing as a whole.
+    pub other_breaking: Vec<BreakingChange>,
+}
+
+/// A diff entry with the selected rules that report it as breaking.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReleaseEntry {
+    #[serde(flatten)]
+    pub entry: DiffEntry,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub breaking_rules: Vec<String>,
+}
+
+impl Changelog {
+    /// Builds a changelog from labelled versions, oldest first.
+    pub fn from_versions(versions: &[(String, Spec<'_>)], config: &BreakingConfig) -> Self {
+        let releases = versions
+            .windows(2)
+            .map(|pair| {
+                let (previous_version, old) = &pair[0];
+                let (version, new) = &pair[1];
+                Release::between(previous_version, old, version, new, config)
+            })
+            .collect();
+        Changelog { releases }
+    }
+
+    /// Whether any release has a breaking change.
+    pub fn has_breaking_changes(&self) -> bool {
+        self.releases.iter().any(Release::has_breaking_changes)
+    }
+}
+
+impl Release {
+    fn between(
+        previous_version: &str,
+        old: &Spec,
+        version: &str,
+        new: &Spec,
+        config: &BreakingConfig,
+    ) -> Self {
+        let mut release = Release {
+            version: version.to_string(),
+            previous_version: previous_version.to_string(),
+            added: Vec::new(),
+            deprecated: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+            other_breaking: Vec::new(),
+        };
+
+        let diff = CanonicalDiff::between(&old.canonical_file, &new.canonical_file);
+        for entry in diff.entries {
+            let section = match (entry.change, entry.attribute.as_deref()) {
+                (ChangeKind::Added, _) => &mut release.added,
+                (ChangeKind::Removed, _) => &mut release.removed,
+                (ChangeKind::Modified, Some("deprecated")) => {
+                    if entry.new.as_deref() != Some("true") {
+                        continue;
+                    }
+                    &mut release.deprecated
+                }
+                (ChangeKind::Modified, _) if entry.class == ChangeClass::Cosmetic => continue,
+                (ChangeKind::Modified, _) => &mut release.changed,
+            };
+            section.push(ReleaseEntry {
+                entry,
+                breaking_rules: Vec::new(),
+            });
+        }
+
+        let packages = [&old.canonical_file, &new.canonical_file]
+            .map(|file| file.package.as_deref().unwrap_or_default());
+        for change in old.check_breaking_changes_with_config(new, config).changes {
+            if !release.annotate(&change, &packages) {
+                release.other_breaking.push(change);
+            }
+        }
+        release
+    }
+
+    /// Adds the rule of `change` to the entries it reports, returning false
+    /// if it matches none.
+    fn annotate(&mut self, change: &BreakingChange, packages: &[&str]) -> bool {
+        let mut entries: Vec<&mut ReleaseEntry> = self
+            .added
+            .iter_mut()
+            .chain(&mut self.deprecated)
+            .chain(&mut self.removed)
+            .chain(&mut self.changed)
+            .filter(|entry| reports(change, &entry.entry, packages))
+            .collect();
+        // Among several attributes of one element, prefer the one the rule
+        // names, e.g. `name` rather than `json_name` for FIELD_SAME_NAME
+        if entries
+            .iter()
+            .any(|entry| names_attribute(change, &entry.entry))
+        {
+            entries.retain(|entry| names_attribute(change, &entry.entry));
+        }
+        for entry in &mut entries {
+            if !entry.breaking_rules.contains(&change.rule_id) {
+                entry.breaking_rules.push(change.rule_id.clone());
+            }
+        }
+        !entries.is_empty()
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &ReleaseEntry> {
+        self.added
+            .iter()
+            .chain(&self.deprecated)
+            .chain(&self.removed)
+            .chain(&self.changed)
+    }
+
+    /// Whether any selected rule reports a breaking change.
+    pub fn has_breaking_changes(&self) -> bool {
+        !self.other_breaking.is_empty()
+            || self.entries().any(|entry| !entry.breaking_rules.is_empty())
+    }
+
+    /// Whether the release changes nothing in the API.
+    pub fn is_empty(&self) -> bool {
+        self.other_breaking.is_empty() && self.entries().next().is_none()
+    }
+}
+
+/// Whether `change` reports `entry`: whether the element path the rule
+/// reports, qualified with the package of either version, is the entry's
+/// path. File-level changes have no element path and are matched by
+/// attribute.
+fn reports(change: &BreakingChange, entry: &DiffEntry, packages: &[&str]) -> bool {
+    let paths = reported_paths(change);
+    if paths.is_empty() {
+        return entry.element == DiffElement::File && names_attribute(change, entry);
+    }
+    paths.iter().any(|path| {
+        packages
+            .iter()
+            .any(|package| qualify(package, path) == entry.path)
+    })
+}
+
+/// The package-relative paths of the elements `change` reports. Rules
+/// locate a member either by its container and, for deletions, its previous
+/// name, e.g. `message` `Bar.Foo` and `field` `b`, or by the member's name
+/// alone with the container named in the message, e.g. `in message
+/// "Bar.Foo"`; the context file and package stand for no element.
+fn reported_paths(change: &BreakingChange) -> Vec<String> {
+    let locations: Vec<&BreakingLocation> = std::iter::once(&change.location)
+        .chain(&change.previous_location)
+        .filter(|location| {
+            !location.element_name.is_empty()
+                && !matches!(location.element_type.as_str(), "file" | "package")
+        })
+        .collect();
+    if let [container, member] = locations[..]
+        && container.element_type != member.element_type
+    {
+        return vec![qualify(&container.element_name, &member.element_name)];
+    }
+
+    let container = quoted_container(&change.message);
+    locations
+        .iter()
+        .map(|location| {
+            let name = &location.element_name;
+            match (location.element_type.as_str(), container) {
+                ("field" | "oneof" | "enum_value" | "rpc", Some(container))
+                    if !name.contains('.') =>
+                {
+                    qualify(container, name)
+                }
+                _ => name.clone(),
+            }
+        })
+        .collect()
+}
+
+/// The container a rule message names, e.g. `Bar.Foo` in `... in message
+/// "Bar.Foo".`
+fn quoted_container(message: &str) -> Option<&str> {
+    [" message \"", " enum \"", " service \""]
+        .iter()
+        .filter_map(|prefix| {
+            let start = message.rfind(prefix)? + prefix.len();
+            let end = message[start..].find('"')?;
+            Some((start, &message[start..start + end]))
+        })
+        .max_by_key(|(start, _)| *start)
+        .map(|(_, container)| container)
+}
+
+/// Whether the rule ID names the changed attribute, e.g. FIELD_SAME_TYPE
+/// and `type`.
+fn names_attribute(change: &BreakingChange, entry: &DiffEntry) -> bool {
+    entry
+        .attribute
+        .as_ref()
+        .is_some_and(|attribute| change.rule_id.contains(&attribute.to_uppercase()))
+}
+
+impl fmt::Display for Changelog {
+    /// Markdown, newest release first.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Changelog")?;
+        if self.releases.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "No earlier version to compare with.")?;
+        }
+        for release in self.releases.iter().rev() {
+            writeln!(f)?;
+            write!(f, "{release}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Release {
+    /// A Markdown section with one list per non-empty group.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "## {} (from {})", self.version, self.previous_version)?;
+        if self.is_empty() {
+            writeln!(f)?;
+            return writeln!(f, "No API changes.");
+        }
+        for (title, entries) in [
+            ("Added", &self.added),
+            ("Deprecated", &self.deprecated),
+            ("Removed", &self.removed),
+            ("Changed", &self.changed),
+        ] {
+            if entries.is_empty() {
+                continue;
+            }
+            writeln!(f)?;
+            writeln!(f, "### {title}")?;
+            writeln!(f)?;
+            for entry in entries {
+                writeln!(f, "- {}", markdown_entry(entry))?;
+            }
+        }
+        if !self.other_breaking.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "### Other breaking changes")?;
+            writeln!(f)?;
+            for change in &self.other_breaking {
+                writeln!(f, "- **{}**: {}", change.rule_id, change.message)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn markdown_entry(release_entry: &ReleaseEntry) -> String {
+    let entry = &release_entry.entry;
+    let mut line = entry.element.to_string();
+    if !entry.path.is_empty() {
+        line.push_str(&format!(" `{}`", entry.path));
+    }
+    if entry.change == ChangeKind::Modified
+        && let Some(attribute) = &entry.attribute
+        && attribute != "deprecated"
+    {
+        let show = |value: &Option<String>| match value {
+            Some(value) => format!("`{value}`"),
+            None => "unset".to_string(),
+        };
+        line.push_str(&format!(
+            ": {attribute} {} -> {}",
+            show(&entry.old),
+            show(&entry.new)
+        ));
+    }
+    if !release_entry.breaking_rules.is_empty() {
+        let rules: Vec<String> = release_entry
+            .breaking_rules
+            .iter()
+            .map(|rule| format!("**{rule}**"))
+            .collect();
+        line.push_str(&format!(" (breaking: {})", rules.join(", ")));
+    }
+    line
+}
+
+/// One version of a file read from git.
+#[derive(Debug, Clone)]
+pub struct GitVersion {
+    /// The abbreviated commit hash, or the start of the range as given.
+    pub revision: String,
+    /// The path of the file in `sources`.
+    pub path: String,
+    /// Every `.proto` file of the revision, so that imports resolve against
+    /// the same revision. Files are keyed by their path relative to the
+    /// file's directory, then by their path in the repository.
+    pub sources: VirtualFileMap,
+}
+
+impl GitVersion {
+    /// The content of the file at this revision.
+    pub fn content(&self) -> &str {
+        self.sources.get(&self.path).unwrap_or_default()
+    }
+
+    /// Parses the file with its imports from the same revision.
+    pub fn spec(&self) -> Result<Spec<'_>, crate::parse::ParseError> {
+        Spec::try_from_sources(&self.sources, &self.path)
+    }
+}
+
+/// Reads every version of `file` in a git revision range, oldest first.
+///
+/// `from..to` yields `from` followed by each commit after it up to `to` that
+/// touched the file; a single revision yields every commit up to it that
+/// touched the file. Commits are labelled with their abbreviated hash.
+pub fn git_versions(file: &Path, range: &str) -> anyhow::Result<Vec<GitVersion>> {
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = file
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a file", file.display()))?
+        .to_string_lossy()
+        .into_owned();
+
+    let mut revisions = Vec::new();
+    if let Some((from, _)) = range.split_once("..") {
+        revisions.push(from.to_string());
+    }
+    let commits = git(
+        dir,
+        &[
+            "rev-list",
+            "--reverse",
+            "--abbrev-commit",
+            range,
+            "--",
+            &name,
+        ],
+    )?;
+    revisions.extend(commits.lines().map(str::to_string));
+
+    let top = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim_end());
+    let prefix = git(dir, &["rev-parse", "--show-prefix"])?
+        .trim_end()
+        .to_string();
+    revisions
+        .into_iter()
+        .map(|revision| {
+            let sources = revision_sources(&top, &revision, &prefix)?;
+            if sources.get(&name).is_none() {
+                anyhow::bail!("'{}' does not exist at {revision}", file.display());
+            }
+            Ok(GitVersion {
+                revision,
+                path: name.clone(),
+                sources,
+            })
+        })
+        .collect()
+}
+
+/// Reads the `.proto` files of a revision, keyed by repository path and,
+/// overriding those, by path relative to `prefix`.
+fn revision_sources(top: &Path, revision: &str, prefix: &str) -> anyhow::Result<VirtualFileMap> {
+    let listing = git(top, &["ls-tree", "-r", "--name-only", revision])?;
+    let paths: Vec<&str> = listing
+        .lines()
+        .filter(|path| path.ends_with(".proto"))
+        .collect();
+    let objects: Vec<String> = paths
+        .iter()
+        .map(|path| format!("{revision}:{path}"))
+        .collect();
+    let contents = read_blobs(top, &objects)?;
+
+    let mut sources = VirtualFileMap::new();
+    let mut relative = Vec::new();
+    for (path, content) in paths.iter().zip(contents) {
+        if let Some(rest) = path.strip_prefix(prefix) {
+            relative.push((rest.to_string(), content.clone()));
+        }
+        sources.insert_bytes(*path, content)?;
+    }
+    for (path, content) in relative {
+        sources.insert_bytes(path, content)?;
+    }
+    Ok(sources)
+}
+
+/// Reads blobs with a single `git cat-file --batch`, in the order given.
+fn read_blobs(top: &Path, objects: &[String]) -> anyhow::Result<Vec<Vec<u8>>> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(top)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run git: {e}"))?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input: String = objects.iter().map(|object| format!("{object}\n")).collect();
+    // Written from another thread so that a full stdout pipe cannot block us
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let mut output = Vec::new();
+    child
+        .stdout
+        .take()
+        .expect("stdout is piped")
+        .read_to_end(&mut output)?;
+    writer
+        .join()
+        .map_err(|_| anyhow::anyhow!("Failed to write to git cat-file"))??;
+    if !child.wait()?.success() {
+        anyhow::bail!("git cat-file --batch failed");
+    }
+
+    // Each blob is "<hash> blob <size>\n<content>\n"
+    let mut blobs = Vec::with_capacity(objects.len());
+    let mut rest = output.as_slice();
+    for object in objects {
+        let header_end = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| anyhow::anyhow!("Truncated git cat-file output"))?;
+        let header = String::from_utf8_lossy(&rest[..header_end]).into_owned();
+        let size: usize = match header.split(' ').collect::<Vec<_>>().as_slice() {
+            [_, "blob", size] => size.parse()?,
+            _ => anyhow::bail!("Cannot read {object} from git: {header}"),
+        };
+        let content = rest
+            .get(header_end + 1..header_end + 1 + size)
+            .ok_or_else(|| anyhow::anyhow!("Truncated git cat-file output"))?;
+        blobs.push(content.to_vec());
+        rest = rest.get(header_end + size + 2..).unwrap_or_default();
+    }
+    Ok(blobs)
+}
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
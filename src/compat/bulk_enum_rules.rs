@@ -2,10 +2,10 @@
 //!
 //! These rules handle enum definitions, values, and reserved ranges.
 
-use crate::canonical::{CanonicalEnum, CanonicalEnumValue, CanonicalFile};
-use crate::compat::handlers::{create_breaking_change, create_location};
+use crate::canonical::{CanonicalEnumValue, CanonicalFile};
+use crate::compat::handlers::{create_breaking_change, create_info_change, create_location};
 use crate::compat::types::{RuleContext, RuleResult};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::HashMap;
 
 // ========================================
 // ENUM Rules
@@ -19,8 +19,8 @@ pub fn check_enum_value_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -63,8 +63,8 @@ pub fn check_enum_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for enum_path in prev_enums.keys() {
         if !curr_enums.contains_key(enum_path) {
@@ -93,8 +93,8 @@ pub fn check_enum_first_value_same(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -103,9 +103,10 @@ pub fn check_enum_first_value_same(
             let curr_first = curr_enum.values.iter().min_by_key(|v| v.number);
 
             match (prev_first, curr_first) {
-                (Some(prev), Some(curr)) => {
-                    if prev.number != curr.number || prev.name != curr.name {
-                        changes.push(create_breaking_change(
+                (Some(prev), Some(curr))
+                    if (prev.number != curr.number || prev.name != curr.name) =>
+                {
+                    changes.push(create_breaking_change(
                             "ENUM_FIRST_VALUE_SAME",
                             format!(
                                 "First enum value changed from \"{}\" ({}) to \"{}\" ({}) in enum \"{}\".",
@@ -119,7 +120,6 @@ pub fn check_enum_first_value_same(
                             )),
                             vec!["ENUM_VALUE".to_string()],
                         ));
-                    }
                 }
                 (Some(prev), None) => {
                     changes.push(create_breaking_change(
@@ -153,8 +153,8 @@ pub fn check_enum_value_same_number(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -172,23 +172,23 @@ pub fn check_enum_value_same_number(
 
             // Find values with changed numbers
             for (name, prev_value) in &prev_values {
-                if let Some(curr_value) = curr_values.get(name) {
-                    if prev_value.number != curr_value.number {
-                        changes.push(create_breaking_change(
-                            "ENUM_VALUE_SAME_NUMBER",
-                            format!(
-                                "Enum value \"{}\" number changed from {} to {} in enum \"{}\".",
-                                name, prev_value.number, curr_value.number, enum_path
-                            ),
-                            create_location(&context.current_file, "enum_value", name),
-                            Some(create_location(
-                                context.previous_file.as_deref().unwrap_or(""),
-                                "enum_value",
-                                name,
-                            )),
-                            vec!["ENUM_VALUE".to_string()],
-                        ));
-                    }
+                if let Some(curr_value) = curr_values.get(name)
+                    && prev_value.number != curr_value.number
+                {
+                    changes.push(create_breaking_change(
+                        "ENUM_VALUE_SAME_NUMBER",
+                        format!(
+                            "Enum value \"{}\" number changed from {} to {} in enum \"{}\".",
+                            name, prev_value.number, curr_value.number, enum_path
+                        ),
+                        create_location(&context.current_file, "enum_value", name),
+                        Some(create_location(
+                            context.previous_file.as_deref().unwrap_or(""),
+                            "enum_value",
+                            name,
+                        )),
+                        vec!["ENUM_VALUE".to_string()],
+                    ));
                 }
             }
         }
@@ -205,8 +205,8 @@ pub fn check_enum_zero_value_same(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -215,23 +215,21 @@ pub fn check_enum_zero_value_same(
             let curr_zero = curr_enum.values.iter().find(|v| v.number == 0);
 
             match (prev_zero, curr_zero) {
-                (Some(prev), Some(curr)) => {
-                    if prev.name != curr.name {
-                        changes.push(create_breaking_change(
-                            "ENUM_ZERO_VALUE_SAME",
-                            format!(
-                                "Enum zero value name changed from \"{}\" to \"{}\" in enum \"{}\".",
-                                prev.name, curr.name, enum_path
-                            ),
-                            create_location(&context.current_file, "enum_value", &curr.name),
-                            Some(create_location(
-                                context.previous_file.as_deref().unwrap_or(""),
-                                "enum_value",
-                                &prev.name
-                            )),
-                            vec!["ENUM_VALUE".to_string()],
-                        ));
-                    }
+                (Some(prev), Some(curr)) if prev.name != curr.name => {
+                    changes.push(create_breaking_change(
+                        "ENUM_ZERO_VALUE_SAME",
+                        format!(
+                            "Enum zero value name changed from \"{}\" to \"{}\" in enum \"{}\".",
+                            prev.name, curr.name, enum_path
+                        ),
+                        create_location(&context.current_file, "enum_value", &curr.name),
+                        Some(create_location(
+                            context.previous_file.as_deref().unwrap_or(""),
+                            "enum_value",
+                            &prev.name,
+                        )),
+                        vec!["ENUM_VALUE".to_string()],
+                    ));
                 }
                 (Some(prev), None) => {
                     changes.push(create_breaking_change(
@@ -270,41 +268,80 @@ pub fn check_enum_zero_value_same(
 }
 
 // ========================================
-// Helper Functions
+// Informational ENUM Rules
 // ========================================
 
-fn collect_all_enums(file: &CanonicalFile) -> HashMap<String, &CanonicalEnum> {
-    let mut all_enums = HashMap::new();
+/// ENUM_VALUE_DEPRECATED - reports enum values that became deprecated
+pub fn check_enum_value_deprecated(
+    current: &CanonicalFile,
+    previous: &CanonicalFile,
+    context: &RuleContext,
+) -> RuleResult {
+    check_enum_value_deprecation_change(current, previous, context, true)
+}
 
-    // Top-level enums
-    for enum_def in &file.enums {
-        all_enums.insert(enum_def.name.clone(), enum_def);
-    }
+/// ENUM_VALUE_UNDEPRECATED - reports enum values that are no longer deprecated
+pub fn check_enum_value_undeprecated(
+    current: &CanonicalFile,
+    previous: &CanonicalFile,
+    context: &RuleContext,
+) -> RuleResult {
+    check_enum_value_deprecation_change(current, previous, context, false)
+}
 
-    // Nested enums in messages
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<crate::canonical::CanonicalMessage>,
-        prefix: &str,
-        all_enums: &mut HashMap<String, &'a CanonicalEnum>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            for enum_def in &message.nested_enums {
-                let enum_key = format!("{}.{}", message_name, enum_def.name);
-                all_enums.insert(enum_key, enum_def);
-            }
+fn check_enum_value_deprecation_change(
+    current: &CanonicalFile,
+    previous: &CanonicalFile,
+    context: &RuleContext,
+    now_deprecated: bool,
+) -> RuleResult {
+    let (rule_id, verb) = if now_deprecated {
+        ("ENUM_VALUE_DEPRECATED", "was deprecated")
+    } else {
+        ("ENUM_VALUE_UNDEPRECATED", "is no longer deprecated")
+    };
+    let mut changes = Vec::new();
+
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
-            collect_from_messages(&message.nested_messages, &message_name, all_enums);
+    for (enum_path, prev_enum) in &prev_enums {
+        if let Some(curr_enum) = curr_enums.get(enum_path) {
+            // Keyed by name: under allow_alias several values share a number
+            let prev_values: HashMap<&str, &CanonicalEnumValue> = prev_enum
+                .values
+                .iter()
+                .map(|v| (v.name.as_str(), v))
+                .collect();
+
+            for curr_value in &curr_enum.values {
+                let Some(prev_value) = prev_values.get(curr_value.name.as_str()) else {
+                    continue;
+                };
+                let was_deprecated = prev_value.deprecated.unwrap_or(false);
+                let is_deprecated = curr_value.deprecated.unwrap_or(false);
+                if was_deprecated != is_deprecated && is_deprecated == now_deprecated {
+                    let value_path = format!("{}.{}", enum_path, curr_value.name);
+                    changes.push(create_info_change(
+                        rule_id,
+                        format!(
+                            "Enum value \"{}\" with number {} {} in enum \"{}\".",
+                            curr_value.name, curr_value.number, verb, enum_path
+                        ),
+                        create_location(&context.current_file, "enum_value", &value_path),
+                        Some(create_location(
+                            context.previous_file.as_deref().unwrap_or(""),
+                            "enum_value",
+                            &format!("{}.{}", enum_path, prev_value.name),
+                        )),
+                        vec!["ENUM_VALUE".to_string()],
+                    ));
+                }
+            }
         }
     }
 
-    collect_from_messages(&file.messages, "", &mut all_enums);
-    all_enums
+    RuleResult::with_changes(changes)
 }
 
 // ========================================
@@ -318,3 +355,9 @@ pub const ENUM_RULES: &[crate::compat::types::RuleEntry] = &[
     ("ENUM_VALUE_SAME_NUMBER", check_enum_value_same_number),
     ("ENUM_ZERO_VALUE_SAME", check_enum_zero_value_same),
 ];
+
+/// Informational rules: reported alongside, but never counted as, breaking changes
+pub const ENUM_INFO_RULES: &[crate::compat::types::RuleEntry] = &[
+    ("ENUM_VALUE_DEPRECATED", check_enum_value_deprecated),
+    ("ENUM_VALUE_UNDEPRECATED", check_enum_value_undeprecated),
+];
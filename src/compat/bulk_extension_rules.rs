@@ -2,10 +2,10 @@
 //!
 //! These rules handle protobuf extensions which extend existing messages.
 
-use crate::canonical::{CanonicalExtension, CanonicalFile, CanonicalMessage};
+use crate::canonical::{CanonicalExtension, CanonicalFile};
 use crate::compat::handlers::{create_breaking_change, create_location};
 use crate::compat::types::{RuleContext, RuleResult};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::HashMap;
 
 // ========================================
 // EXTENSION Rules (Placeholder Implementation)
@@ -62,8 +62,8 @@ pub fn check_extension_message_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -96,34 +96,6 @@ pub fn check_extension_message_no_delete(
     RuleResult::with_changes(changes)
 }
 
-// ========================================
-// Helper Functions
-// ========================================
-
-fn collect_all_messages(file: &CanonicalFile) -> HashMap<String, &CanonicalMessage> {
-    let mut all_messages = HashMap::new();
-
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_messages: &mut HashMap<String, &'a CanonicalMessage>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            all_messages.insert(message_name.clone(), message);
-            collect_from_messages(&message.nested_messages, &message_name, all_messages);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_messages);
-    all_messages
-}
-
 // ========================================
 // Rule Export Table
 // ========================================
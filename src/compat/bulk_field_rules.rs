@@ -39,6 +39,37 @@ fn collect_all_fields(file: &CanonicalFile) -> HashMap<String, &CanonicalField>
     all_fields
 }
 
+/// Collects fields keyed by message path and field number.
+///
+/// Wire compatibility is about what is on the wire, which is identified by
+/// number rather than name, so renamed fields must still be compared.
+fn collect_all_fields_by_number(file: &CanonicalFile) -> HashMap<(String, i32), &CanonicalField> {
+    let mut all_fields = HashMap::new();
+
+    fn collect_from_messages<'a>(
+        messages: &'a BTreeSet<CanonicalMessage>,
+        prefix: &str,
+        all_fields: &mut HashMap<(String, i32), &'a CanonicalField>,
+    ) {
+        for message in messages {
+            let message_name = if prefix.is_empty() {
+                message.name.clone()
+            } else {
+                format!("{}.{}", prefix, message.name)
+            };
+
+            for field in &message.fields {
+                all_fields.insert((message_name.clone(), field.number), field);
+            }
+
+            collect_from_messages(&message.nested_messages, &message_name, all_fields);
+        }
+    }
+
+    collect_from_messages(&file.messages, "", &mut all_fields);
+    all_fields
+}
+
 // ========================================
 // MACRO MAGIC for Field Rules
 // ========================================
@@ -239,36 +270,12 @@ pub fn check_field_no_delete_unless_number_reserved(
 // Helper Functions for Reserved Checking
 // ========================================
 
-fn collect_all_messages(file: &CanonicalFile) -> HashMap<String, &CanonicalMessage> {
-    let mut all_messages = HashMap::new();
-
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_messages: &mut HashMap<String, &'a CanonicalMessage>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            all_messages.insert(message_name.clone(), message);
-            collect_from_messages(&message.nested_messages, &message_name, all_messages);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_messages);
-    all_messages
-}
-
 fn check_field_name_reserved_in_message(
     file: &CanonicalFile,
     message_path: &str,
     field_name: &str,
 ) -> bool {
-    let messages = collect_all_messages(file);
+    let messages = file.messages_by_path();
     if let Some(message) = messages.get(message_path) {
         message
             .reserved_names
@@ -284,7 +291,7 @@ fn check_field_number_reserved_in_message(
     message_path: &str,
     field_number: i32,
 ) -> bool {
-    let messages = collect_all_messages(file);
+    let messages = file.messages_by_path();
     if let Some(message) = messages.get(message_path) {
         message
             .reserved_ranges
@@ -307,27 +314,30 @@ pub fn check_field_wire_compatible_type(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let previous_fields = collect_all_fields(previous);
-    let current_fields = collect_all_fields(current);
-
-    for (field_path, prev_field) in &previous_fields {
-        if let Some(curr_field) = current_fields.get(field_path) {
-            if !are_types_wire_compatible(&prev_field.type_name, &curr_field.type_name) {
-                changes.push(create_breaking_change(
-                    "FIELD_WIRE_COMPATIBLE_TYPE",
-                    format!(
-                        "Field \"{}\" type changed from \"{}\" to \"{}\" which are not wire-compatible.",
-                        field_path, prev_field.type_name, curr_field.type_name
-                    ),
-                    create_location(&context.current_file, "field", field_path),
-                    Some(create_location(
-                        context.previous_file.as_deref().unwrap_or(""),
-                        "field",
-                        field_path
-                    )),
-                    vec!["WIRE".to_string()],
-                ));
-            }
+    let previous_fields = collect_all_fields_by_number(previous);
+    let current_fields = collect_all_fields_by_number(current);
+
+    for ((message_path, number), prev_field) in &previous_fields {
+        if let Some(curr_field) = current_fields.get(&(message_path.clone(), *number))
+            && !are_types_wire_compatible(prev_field, curr_field)
+        {
+            let field_path = format!("{}.{}", message_path, curr_field.name);
+            changes.push(create_breaking_change(
+                "FIELD_WIRE_COMPATIBLE_TYPE",
+                format!(
+                    "Field \"{}\" type changed from \"{}\" to \"{}\" which are not wire-compatible.",
+                    field_path,
+                    prev_field.display_type(),
+                    curr_field.display_type()
+                ),
+                create_location(&context.current_file, "field", &field_path),
+                Some(create_location(
+                    context.previous_file.as_deref().unwrap_or(""),
+                    "field",
+                    &format!("{}.{}", message_path, prev_field.name),
+                )),
+                vec!["WIRE".to_string()],
+            ));
         }
     }
 
@@ -375,7 +385,14 @@ pub fn check_field_wire_compatible_cardinality(
 // Compatibility Check Functions
 // ========================================
 
-fn are_types_wire_compatible(prev_type: &str, curr_type: &str) -> bool {
+fn are_types_wire_compatible(prev_field: &CanonicalField, curr_field: &CanonicalField) -> bool {
+    // Groups use start/end-group tags, so converting to or from a group is
+    // never wire-compatible even when the underlying message is unchanged.
+    if prev_field.group != curr_field.group {
+        return false;
+    }
+
+    let (prev_type, curr_type) = (prev_field.type_name.as_str(), curr_field.type_name.as_str());
     if prev_type == curr_type {
         return true;
     }
@@ -422,27 +439,30 @@ pub fn check_field_wire_json_compatible_type(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let previous_fields = collect_all_fields(previous);
-    let current_fields = collect_all_fields(current);
-
-    for (field_path, prev_field) in &previous_fields {
-        if let Some(curr_field) = current_fields.get(field_path) {
-            if !are_types_wire_json_compatible(&prev_field.type_name, &curr_field.type_name) {
-                changes.push(create_breaking_change(
-                    "FIELD_WIRE_JSON_COMPATIBLE_TYPE",
-                    format!(
-                        "Field \"{}\" type changed from \"{}\" to \"{}\" which are not wire+JSON compatible.",
-                        field_path, prev_field.type_name, curr_field.type_name
-                    ),
-                    create_location(&context.current_file, "field", field_path),
-                    Some(create_location(
-                        context.previous_file.as_deref().unwrap_or(""),
-                        "field",
-                        field_path
-                    )),
-                    vec!["WIRE_JSON".to_string(), "WIRE".to_string()],
-                ));
-            }
+    let previous_fields = collect_all_fields_by_number(previous);
+    let current_fields = collect_all_fields_by_number(current);
+
+    for ((message_path, number), prev_field) in &previous_fields {
+        if let Some(curr_field) = current_fields.get(&(message_path.clone(), *number))
+            && !are_types_wire_json_compatible(prev_field, curr_field)
+        {
+            let field_path = format!("{}.{}", message_path, curr_field.name);
+            changes.push(create_breaking_change(
+                "FIELD_WIRE_JSON_COMPATIBLE_TYPE",
+                format!(
+                    "Field \"{}\" type changed from \"{}\" to \"{}\" which are not wire+JSON compatible.",
+                    field_path,
+                    prev_field.display_type(),
+                    curr_field.display_type()
+                ),
+                create_location(&context.current_file, "field", &field_path),
+                Some(create_location(
+                    context.previous_file.as_deref().unwrap_or(""),
+                    "field",
+                    &format!("{}.{}", message_path, prev_field.name),
+                )),
+                vec!["WIRE_JSON".to_string(), "WIRE".to_string()],
+            ));
         }
     }
 
@@ -490,7 +510,15 @@ pub fn check_field_wire_json_compatible_cardinality(
 // Enhanced Compatibility Functions
 // ========================================
 
-fn are_types_wire_json_compatible(prev_type: &str, curr_type: &str) -> bool {
+fn are_types_wire_json_compatible(
+    prev_field: &CanonicalField,
+    curr_field: &CanonicalField,
+) -> bool {
+    if prev_field.group != curr_field.group {
+        return false;
+    }
+
+    let (prev_type, curr_type) = (prev_field.type_name.as_str(), curr_field.type_name.as_str());
     if prev_type == curr_type {
         return true;
     }
@@ -527,25 +555,25 @@ pub fn check_field_same_default(
     let current_fields = collect_all_fields(current);
 
     for (field_path, prev_field) in &previous_fields {
-        if let Some(curr_field) = current_fields.get(field_path) {
-            if prev_field.default != curr_field.default {
-                changes.push(create_breaking_change(
-                    "FIELD_SAME_DEFAULT",
-                    format!(
-                        "Field \"{}\" default value changed from \"{}\" to \"{}\".",
-                        field_path,
-                        prev_field.default.as_deref().unwrap_or(""),
-                        curr_field.default.as_deref().unwrap_or("")
-                    ),
-                    create_location(&context.current_file, "field", field_path),
-                    Some(create_location(
-                        context.previous_file.as_deref().unwrap_or(""),
-                        "field",
-                        field_path,
-                    )),
-                    vec!["WIRE_JSON".to_string()],
-                ));
-            }
+        if let Some(curr_field) = current_fields.get(field_path)
+            && prev_field.default != curr_field.default
+        {
+            changes.push(create_breaking_change(
+                "FIELD_SAME_DEFAULT",
+                format!(
+                    "Field \"{}\" default value changed from \"{}\" to \"{}\".",
+                    field_path,
+                    prev_field.default.as_deref().unwrap_or(""),
+                    curr_field.default.as_deref().unwrap_or("")
+                ),
+                create_location(&context.current_file, "field", field_path),
+                Some(create_location(
+                    context.previous_file.as_deref().unwrap_or(""),
+                    "field",
+                    field_path,
+                )),
+                vec!["WIRE_JSON".to_string()],
+            ));
         }
     }
 
@@ -564,25 +592,25 @@ pub fn check_field_same_json_name(
     let current_fields = collect_all_fields(current);
 
     for (field_path, prev_field) in &previous_fields {
-        if let Some(curr_field) = current_fields.get(field_path) {
-            if prev_field.json_name != curr_field.json_name {
-                changes.push(create_breaking_change(
-                    "FIELD_SAME_JSON_NAME",
-                    format!(
-                        "Field \"{}\" JSON name changed from \"{}\" to \"{}\".",
-                        field_path,
-                        prev_field.json_name.as_deref().unwrap_or(""),
-                        curr_field.json_name.as_deref().unwrap_or("")
-                    ),
-                    create_location(&context.current_file, "field", field_path),
-                    Some(create_location(
-                        context.previous_file.as_deref().unwrap_or(""),
-                        "field",
-                        field_path,
-                    )),
-                    vec!["WIRE_JSON".to_string()],
-                ));
-            }
+        if let Some(curr_field) = current_fields.get(field_path)
+            && prev_field.json_name != curr_field.json_name
+        {
+            changes.push(create_breaking_change(
+                "FIELD_SAME_JSON_NAME",
+                format!(
+                    "Field \"{}\" JSON name changed from \"{}\" to \"{}\".",
+                    field_path,
+                    prev_field.json_name.as_deref().unwrap_or(""),
+                    curr_field.json_name.as_deref().unwrap_or("")
+                ),
+                create_location(&context.current_file, "field", field_path),
+                Some(create_location(
+                    context.previous_file.as_deref().unwrap_or(""),
+                    "field",
+                    field_path,
+                )),
+                vec!["WIRE_JSON".to_string()],
+            ));
         }
     }
 
@@ -601,25 +629,25 @@ pub fn check_field_same_jstype(
     let current_fields = collect_all_fields(current);
 
     for (field_path, prev_field) in &previous_fields {
-        if let Some(curr_field) = current_fields.get(field_path) {
-            if prev_field.jstype != curr_field.jstype {
-                changes.push(create_breaking_change(
-                    "FIELD_SAME_JSTYPE",
-                    format!(
-                        "Field \"{}\" JSType changed from \"{}\" to \"{}\".",
-                        field_path,
-                        prev_field.jstype.as_deref().unwrap_or("JS_NORMAL"),
-                        curr_field.jstype.as_deref().unwrap_or("JS_NORMAL")
-                    ),
-                    create_location(&context.current_file, "field", field_path),
-                    Some(create_location(
-                        context.previous_file.as_deref().unwrap_or(""),
-                        "field",
-                        field_path,
-                    )),
-                    vec!["WIRE_JSON".to_string()],
-                ));
-            }
+        if let Some(curr_field) = current_fields.get(field_path)
+            && prev_field.jstype != curr_field.jstype
+        {
+            changes.push(create_breaking_change(
+                "FIELD_SAME_JSTYPE",
+                format!(
+                    "Field \"{}\" JSType changed from \"{}\" to \"{}\".",
+                    field_path,
+                    prev_field.jstype.as_deref().unwrap_or("JS_NORMAL"),
+                    curr_field.jstype.as_deref().unwrap_or("JS_NORMAL")
+                ),
+                create_location(&context.current_file, "field", field_path),
+                Some(create_location(
+                    context.previous_file.as_deref().unwrap_or(""),
+                    "field",
+                    field_path,
+                )),
+                vec!["WIRE_JSON".to_string()],
+            ));
         }
     }
 
@@ -638,25 +666,25 @@ pub fn check_field_same_ctype(
     let current_fields = collect_all_fields(current);
 
     for (field_path, prev_field) in &previous_fields {
-        if let Some(curr_field) = current_fields.get(field_path) {
-            if prev_field.ctype != curr_field.ctype {
-                changes.push(create_breaking_change(
-                    "FIELD_SAME_CTYPE",
-                    format!(
-                        "Field \"{}\" CType changed from \"{}\" to \"{}\".",
-                        field_path,
-                        prev_field.ctype.as_deref().unwrap_or("STRING"),
-                        curr_field.ctype.as_deref().unwrap_or("STRING")
-                    ),
-                    create_location(&context.current_file, "field", field_path),
-                    Some(create_location(
-                        context.previous_file.as_deref().unwrap_or(""),
-                        "field",
-                        field_path,
-                    )),
-                    vec!["WIRE_JSON".to_string()],
-                ));
-            }
+        if let Some(curr_field) = current_fields.get(field_path)
+            && prev_field.ctype != curr_field.ctype
+        {
+            changes.push(create_breaking_change(
+                "FIELD_SAME_CTYPE",
+                format!(
+                    "Field \"{}\" CType changed from \"{}\" to \"{}\".",
+                    field_path,
+                    prev_field.ctype.as_deref().unwrap_or("STRING"),
+                    curr_field.ctype.as_deref().unwrap_or("STRING")
+                ),
+                create_location(&context.current_file, "field", field_path),
+                Some(create_location(
+                    context.previous_file.as_deref().unwrap_or(""),
+                    "field",
+                    field_path,
+                )),
+                vec!["WIRE_JSON".to_string()],
+            ));
         }
     }
 
@@ -675,25 +703,25 @@ pub fn check_field_same_cpp_string_type(
     let current_fields = collect_all_fields(current);
 
     for (field_path, prev_field) in &previous_fields {
-        if let Some(curr_field) = current_fields.get(field_path) {
-            if prev_field.cpp_string_type != curr_field.cpp_string_type {
-                changes.push(create_breaking_change(
-                    "FIELD_SAME_CPP_STRING_TYPE",
-                    format!(
-                        "Field \"{}\" C++ string type changed from \"{}\" to \"{}\".",
-                        field_path,
-                        prev_field.cpp_string_type.as_deref().unwrap_or(""),
-                        curr_field.cpp_string_type.as_deref().unwrap_or("")
-                    ),
-                    create_location(&context.current_file, "field", field_path),
-                    Some(create_location(
-                        context.previous_file.as_deref().unwrap_or(""),
-                        "field",
-                        field_path,
-                    )),
-                    vec!["WIRE_JSON".to_string()],
-                ));
-            }
+        if let Some(curr_field) = current_fields.get(field_path)
+            && prev_field.cpp_string_type != curr_field.cpp_string_type
+        {
+            changes.push(create_breaking_change(
+                "FIELD_SAME_CPP_STRING_TYPE",
+                format!(
+                    "Field \"{}\" C++ string type changed from \"{}\" to \"{}\".",
+                    field_path,
+                    prev_field.cpp_string_type.as_deref().unwrap_or(""),
+                    curr_field.cpp_string_type.as_deref().unwrap_or("")
+                ),
+                create_location(&context.current_file, "field", field_path),
+                Some(create_location(
+                    context.previous_file.as_deref().unwrap_or(""),
+                    "field",
+                    field_path,
+                )),
+                vec!["WIRE_JSON".to_string()],
+            ));
         }
     }
 
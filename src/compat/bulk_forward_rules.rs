@@ -0,0 +1,135 @@
+//! Forward-compatibility rules
+//!
+//! The Buf rules check that new readers accept data written with the previous
+//! schema. These rules check the opposite direction: that readers still on the
+//! previous schema accept data written with the current one. They only run
+//! with `Direction::Forward` or `Direction::Full`.
+
+use crate::canonical::{CanonicalEnum, CanonicalFile, CanonicalMessage};
+use crate::compat::handlers::{create_breaking_change, create_location};
+use crate::compat::types::{RuleContext, RuleResult};
+use std::collections::HashSet;
+
+// ========================================
+// Forward Rules
+// ========================================
+
+/// ENUM_VALUE_NO_ADD_TO_CLOSED - checks no values are added to closed enums.
+/// Old readers treat an unknown value of a closed enum as an unknown field,
+/// so the field reads as unset.
+pub fn check_enum_value_no_add_to_closed(
+    current: &CanonicalFile,
+    previous: &CanonicalFile,
+    context: &RuleContext,
+) -> RuleResult {
+    let mut changes = Vec::new();
+
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
+
+    for (enum_path, curr_enum) in &curr_enums {
+        let Some(prev_enum) = prev_enums.get(enum_path) else {
+            continue;
+        };
+        if !is_closed(prev_enum, previous) {
+            continue;
+        }
+        let prev_numbers: HashSet<i32> = prev_enum.values.iter().map(|v| v.number).collect();
+
+        for curr_value in &curr_enum.values {
+            if !prev_numbers.contains(&curr_value.number) {
+                changes.push(create_breaking_change(
+                    "ENUM_VALUE_NO_ADD_TO_CLOSED",
+                    format!(
+                        "Enum value \"{}\" with number {} was added to closed enum \"{}\"; previous readers treat it as unknown.",
+                        curr_value.name, curr_value.number, enum_path
+                    ),
+                    create_location(&context.current_file, "enum_value", &curr_value.name),
+                    Some(create_location(
+                        context.previous_file.as_deref().unwrap_or(""),
+                        "enum",
+                        enum_path,
+                    )),
+                    vec!["ENUM_VALUE".to_string()],
+                ));
+            }
+        }
+    }
+
+    RuleResult::with_changes(changes)
+}
+
+/// ONEOF_NO_ADD_FIELD - checks no new fields are added to existing oneofs.
+/// Old readers see a oneof set to an unknown member as not set at all.
+pub fn check_oneof_no_add_field(
+    current: &CanonicalFile,
+    previous: &CanonicalFile,
+    context: &RuleContext,
+) -> RuleResult {
+    let mut changes = Vec::new();
+
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
+
+    for (message_path, curr_message) in &curr_messages {
+        let Some(prev_message) = prev_messages.get(message_path) else {
+            continue;
+        };
+        let prev_numbers: HashSet<i32> = prev_message.fields.iter().map(|f| f.number).collect();
+
+        for curr_field in &curr_message.fields {
+            if prev_numbers.contains(&curr_field.number) {
+                continue;
+            }
+            let Some(oneof) = oneof_name(curr_message, curr_field.oneof_index) else {
+                continue;
+            };
+            if prev_message.oneofs.iter().any(|name| name == oneof) {
+                changes.push(create_breaking_change(
+                    "ONEOF_NO_ADD_FIELD",
+                    format!(
+                        "Field \"{}\" with number {} was added to oneof \"{}\" in message \"{}\"; previous readers see the oneof as unset.",
+                        curr_field.name, curr_field.number, oneof, message_path
+                    ),
+                    create_location(&context.current_file, "field", &curr_field.name),
+                    Some(create_location(
+                        context.previous_file.as_deref().unwrap_or(""),
+                        "oneof",
+                        oneof,
+                    )),
+                    vec!["ONEOF".to_string()],
+                ));
+            }
+        }
+    }
+
+    RuleResult::with_changes(changes)
+}
+
+// ========================================
+// Helper Functions
+// ========================================
+
+/// Proto2 enums are closed; proto3 enums are open unless marked otherwise.
+fn is_closed(enum_def: &CanonicalEnum, file: &CanonicalFile) -> bool {
+    enum_def.closed_enum.unwrap_or(file.syntax == "proto2")
+}
+
+/// Synthetic oneofs of proto3 `optional` fields are not reported: they
+/// never gain members.
+fn oneof_name(message: &CanonicalMessage, index: Option<i32>) -> Option<&str> {
+    let name = message.oneofs.get(usize::try_from(index?).ok()?)?;
+    (!name.starts_with('_')).then_some(name.as_str())
+}
+
+// ========================================
+// Rule Export Table
+// ========================================
+
+pub const FORWARD_RULES: &[crate::compat::types::RuleEntry] = &[
+    (
+        "ENUM_VALUE_NO_ADD_TO_CLOSED",
+        check_enum_value_no_add_to_closed,
+    ),
+    ("ONEOF_NO_ADD_FIELD", check_oneof_no_add_field),
+];
@@ -2,10 +2,10 @@
 //!
 //! These rules handle message definitions, fields, oneofs, and reserved ranges.
 
-use crate::canonical::{CanonicalField, CanonicalFile, CanonicalMessage};
+use crate::canonical::{CanonicalField, CanonicalFile};
 use crate::compat::handlers::{create_breaking_change, create_location};
 use crate::compat::types::{RuleContext, RuleResult};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::HashMap;
 
 // ========================================
 // MESSAGE Rules
@@ -19,8 +19,8 @@ pub fn check_message_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for message_path in prev_messages.keys() {
         if !curr_messages.contains_key(message_path) {
@@ -49,8 +49,8 @@ pub fn check_message_no_remove_standard_descriptor_accessor(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -90,29 +90,29 @@ pub fn check_message_same_message_set_wire_format(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
-        if let Some(curr_message) = curr_messages.get(message_path) {
-            if prev_message.message_set_wire_format != curr_message.message_set_wire_format {
-                changes.push(create_breaking_change(
-                    "MESSAGE_SAME_MESSAGE_SET_WIRE_FORMAT",
-                    format!(
-                        "Message \"{}\" MessageSet wire format changed from {:?} to {:?}.",
-                        message_path,
-                        prev_message.message_set_wire_format,
-                        curr_message.message_set_wire_format
-                    ),
-                    create_location(&context.current_file, "message", message_path),
-                    Some(create_location(
-                        context.previous_file.as_deref().unwrap_or(""),
-                        "message",
-                        message_path,
-                    )),
-                    vec!["FILE".to_string()],
-                ));
-            }
+        if let Some(curr_message) = curr_messages.get(message_path)
+            && prev_message.message_set_wire_format != curr_message.message_set_wire_format
+        {
+            changes.push(create_breaking_change(
+                "MESSAGE_SAME_MESSAGE_SET_WIRE_FORMAT",
+                format!(
+                    "Message \"{}\" MessageSet wire format changed from {:?} to {:?}.",
+                    message_path,
+                    prev_message.message_set_wire_format,
+                    curr_message.message_set_wire_format
+                ),
+                create_location(&context.current_file, "message", message_path),
+                Some(create_location(
+                    context.previous_file.as_deref().unwrap_or(""),
+                    "message",
+                    message_path,
+                )),
+                vec!["FILE".to_string()],
+            ));
         }
     }
 
@@ -127,8 +127,8 @@ pub fn check_oneof_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -166,8 +166,8 @@ pub fn check_field_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -210,8 +210,8 @@ pub fn check_field_same_name(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -223,23 +223,23 @@ pub fn check_field_same_name(
 
             // Find fields with changed names
             for (number, prev_field) in &prev_fields {
-                if let Some(curr_field) = curr_fields.get(number) {
-                    if prev_field.name != curr_field.name {
-                        changes.push(create_breaking_change(
-                            "FIELD_SAME_NAME",
-                            format!(
-                                "Field {} name changed from \"{}\" to \"{}\" in message \"{}\".",
-                                number, prev_field.name, curr_field.name, message_path
-                            ),
-                            create_location(&context.current_file, "field", &curr_field.name),
-                            Some(create_location(
-                                context.previous_file.as_deref().unwrap_or(""),
-                                "field",
-                                &prev_field.name,
-                            )),
-                            vec!["FIELD".to_string()],
-                        ));
-                    }
+                if let Some(curr_field) = curr_fields.get(number)
+                    && prev_field.name != curr_field.name
+                {
+                    changes.push(create_breaking_change(
+                        "FIELD_SAME_NAME",
+                        format!(
+                            "Field {} name changed from \"{}\" to \"{}\" in message \"{}\".",
+                            number, prev_field.name, curr_field.name, message_path
+                        ),
+                        create_location(&context.current_file, "field", &curr_field.name),
+                        Some(create_location(
+                            context.previous_file.as_deref().unwrap_or(""),
+                            "field",
+                            &prev_field.name,
+                        )),
+                        vec!["FIELD".to_string()],
+                    ));
                 }
             }
         }
@@ -256,8 +256,8 @@ pub fn check_field_same_type(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -269,23 +269,27 @@ pub fn check_field_same_type(
 
             // Find fields with changed types
             for (number, prev_field) in &prev_fields {
-                if let Some(curr_field) = curr_fields.get(number) {
-                    if prev_field.type_name != curr_field.type_name {
-                        changes.push(create_breaking_change(
-                            "FIELD_SAME_TYPE",
-                            format!(
-                                "Field \"{}\" type changed from \"{}\" to \"{}\" in message \"{}\".",
-                                prev_field.name, prev_field.type_name, curr_field.type_name, message_path
-                            ),
-                            create_location(&context.current_file, "field", &curr_field.name),
-                            Some(create_location(
-                                context.previous_file.as_deref().unwrap_or(""),
-                                "field",
-                                &prev_field.name
-                            )),
-                            vec!["FIELD".to_string()],
-                        ));
-                    }
+                if let Some(curr_field) = curr_fields.get(number)
+                    && (prev_field.type_name != curr_field.type_name
+                        || prev_field.group != curr_field.group)
+                {
+                    changes.push(create_breaking_change(
+                        "FIELD_SAME_TYPE",
+                        format!(
+                            "Field \"{}\" type changed from \"{}\" to \"{}\" in message \"{}\".",
+                            prev_field.name,
+                            prev_field.display_type(),
+                            curr_field.display_type(),
+                            message_path
+                        ),
+                        create_location(&context.current_file, "field", &curr_field.name),
+                        Some(create_location(
+                            context.previous_file.as_deref().unwrap_or(""),
+                            "field",
+                            &prev_field.name,
+                        )),
+                        vec!["FIELD".to_string()],
+                    ));
                 }
             }
         }
@@ -294,34 +298,6 @@ pub fn check_field_same_type(
     RuleResult::with_changes(changes)
 }
 
-// ========================================
-// Helper Functions
-// ========================================
-
-fn collect_all_messages(file: &CanonicalFile) -> HashMap<String, &CanonicalMessage> {
-    let mut all_messages = HashMap::new();
-
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_messages: &mut HashMap<String, &'a CanonicalMessage>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            all_messages.insert(message_name.clone(), message);
-            collect_from_messages(&message.nested_messages, &message_name, all_messages);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_messages);
-    all_messages
-}
-
 // ========================================
 // Rule Export Table
 // ========================================
@@ -2,9 +2,7 @@
 //!
 //! This module implements all remaining non-field rules in one go.
 
-use crate::canonical::{
-    CanonicalEnum, CanonicalEnumValue, CanonicalFile, CanonicalMessage, CanonicalService,
-};
+use crate::canonical::{CanonicalEnumValue, CanonicalFile, CanonicalMessage};
 use crate::compat::handlers::{create_breaking_change, create_location};
 use crate::compat::types::{RuleContext, RuleResult};
 use std::collections::{BTreeSet, HashMap};
@@ -21,8 +19,8 @@ pub fn check_message_same_json_format(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -60,8 +58,8 @@ pub fn check_message_same_required_fields(
     let mut changes = Vec::new();
 
     // Collect all messages with their required fields
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -119,8 +117,8 @@ pub fn check_message_no_remove_standard_descriptor_accessor(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -165,8 +163,8 @@ pub fn check_enum_same_type(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -205,8 +203,8 @@ pub fn check_enum_same_json_format(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -271,8 +269,8 @@ pub fn check_enum_value_same_name(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -321,8 +319,8 @@ pub fn check_rpc_same_idempotency_level(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_services = collect_all_services(previous);
-    let curr_services = collect_all_services(current);
+    let prev_services = previous.services_by_path();
+    let curr_services = current.services_by_path();
 
     for (service_path, prev_service) in &prev_services {
         if let Some(curr_service) = curr_services.get(service_path) {
@@ -382,8 +380,8 @@ pub fn check_oneof_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -415,72 +413,6 @@ pub fn check_oneof_no_delete(
 // Helper Functions
 // ========================================
 
-fn collect_all_messages(file: &CanonicalFile) -> HashMap<String, &CanonicalMessage> {
-    let mut all_messages = HashMap::new();
-
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_messages: &mut HashMap<String, &'a CanonicalMessage>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            all_messages.insert(message_name.clone(), message);
-            collect_from_messages(&message.nested_messages, &message_name, all_messages);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_messages);
-    all_messages
-}
-
-fn collect_all_enums(file: &CanonicalFile) -> HashMap<String, &CanonicalEnum> {
-    let mut all_enums = HashMap::new();
-
-    // Top-level enums
-    for enum_def in &file.enums {
-        all_enums.insert(enum_def.name.clone(), enum_def);
-    }
-
-    // Nested enums in messages
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_enums: &mut HashMap<String, &'a CanonicalEnum>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            for enum_def in &message.nested_enums {
-                let enum_key = format!("{}.{}", message_name, enum_def.name);
-                all_enums.insert(enum_key, enum_def);
-            }
-
-            collect_from_messages(&message.nested_messages, &message_name, all_enums);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_enums);
-    all_enums
-}
-
-fn collect_all_services(file: &CanonicalFile) -> HashMap<String, &CanonicalService> {
-    let mut all_services = HashMap::new();
-    for service in &file.services {
-        all_services.insert(service.name.clone(), service);
-    }
-    all_services
-}
-
 fn get_required_fields(message: &CanonicalMessage) -> BTreeSet<String> {
     message
         .fields
@@ -3,10 +3,10 @@
 //! These rules check for deletions at the package level across file sets.
 //! Note: These rules require file-set analysis, not single-file comparison.
 
-use crate::canonical::{CanonicalEnum, CanonicalFile, CanonicalMessage, CanonicalService};
+use crate::canonical::CanonicalFile;
 use crate::compat::handlers::{create_breaking_change, create_location};
 use crate::compat::types::{RuleContext, RuleResult};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::HashMap;
 
 // ========================================
 // PACKAGE_* Rules - File-Set Level Analysis
@@ -64,8 +64,8 @@ pub fn check_package_enum_no_delete(
 
     // Only check if same package
     if prev_package == curr_package && !prev_package.is_empty() {
-        let prev_enums = collect_all_enums(previous);
-        let curr_enums = collect_all_enums(current);
+        let prev_enums = previous.enums_by_path();
+        let curr_enums = current.enums_by_path();
 
         // Find deleted enums
         for enum_path in prev_enums.keys() {
@@ -100,8 +100,8 @@ pub fn check_package_message_no_delete(
     let curr_package = current.package.as_deref().unwrap_or("");
 
     if prev_package == curr_package && !prev_package.is_empty() {
-        let prev_messages = collect_all_messages(previous);
-        let curr_messages = collect_all_messages(current);
+        let prev_messages = previous.messages_by_path();
+        let curr_messages = current.messages_by_path();
 
         for message_path in prev_messages.keys() {
             if !curr_messages.contains_key(message_path) {
@@ -137,8 +137,8 @@ pub fn check_package_service_no_delete(
     let curr_package = current.package.as_deref().unwrap_or("");
 
     if prev_package == curr_package && !prev_package.is_empty() {
-        let prev_services = collect_all_services(previous);
-        let curr_services = collect_all_services(current);
+        let prev_services = previous.services_by_path();
+        let curr_services = current.services_by_path();
 
         for service_name in prev_services.keys() {
             if !curr_services.contains_key(service_name) {
@@ -212,76 +212,6 @@ pub fn check_package_extension_no_delete(
     RuleResult::with_changes(changes)
 }
 
-// ========================================
-// Helper Functions
-// ========================================
-
-fn collect_all_messages(file: &CanonicalFile) -> HashMap<String, &CanonicalMessage> {
-    let mut all_messages = HashMap::new();
-
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_messages: &mut HashMap<String, &'a CanonicalMessage>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            all_messages.insert(message_name.clone(), message);
-            collect_from_messages(&message.nested_messages, &message_name, all_messages);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_messages);
-    all_messages
-}
-
-fn collect_all_enums(file: &CanonicalFile) -> HashMap<String, &CanonicalEnum> {
-    let mut all_enums = HashMap::new();
-
-    // Top-level enums
-    for enum_def in &file.enums {
-        all_enums.insert(enum_def.name.clone(), enum_def);
-    }
-
-    // Nested enums in messages
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_enums: &mut HashMap<String, &'a CanonicalEnum>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            for enum_def in &message.nested_enums {
-                let enum_key = format!("{}.{}", message_name, enum_def.name);
-                all_enums.insert(enum_key, enum_def);
-            }
-
-            collect_from_messages(&message.nested_messages, &message_name, all_enums);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_enums);
-    all_enums
-}
-
-fn collect_all_services(file: &CanonicalFile) -> HashMap<String, &CanonicalService> {
-    let mut all_services = HashMap::new();
-    for service in &file.services {
-        all_services.insert(service.name.clone(), service);
-    }
-    all_services
-}
-
 // ========================================
 // Rule Export Table
 // ========================================
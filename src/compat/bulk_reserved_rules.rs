@@ -2,10 +2,10 @@
 //!
 //! These rules ensure that reserved fields, ranges, and names cannot be violated.
 
-use crate::canonical::{CanonicalEnum, CanonicalFile, CanonicalMessage};
+use crate::canonical::CanonicalFile;
 use crate::compat::handlers::{create_breaking_change, create_location};
 use crate::compat::types::{RuleContext, RuleResult};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::HashMap;
 
 // ========================================
 // RESERVED Rules
@@ -19,8 +19,8 @@ pub fn check_reserved_enum_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -77,8 +77,8 @@ pub fn check_reserved_message_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -135,8 +135,8 @@ pub fn check_field_no_delete_unless_name_reserved(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -183,8 +183,8 @@ pub fn check_field_no_delete_unless_number_reserved(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -233,8 +233,8 @@ pub fn check_enum_value_no_delete_unless_name_reserved(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -281,8 +281,8 @@ pub fn check_enum_value_no_delete_unless_number_reserved(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
         if let Some(curr_enum) = curr_enums.get(enum_path) {
@@ -323,68 +323,6 @@ pub fn check_enum_value_no_delete_unless_number_reserved(
     RuleResult::with_changes(changes)
 }
 
-// ========================================
-// Helper Functions
-// ========================================
-
-fn collect_all_messages(file: &CanonicalFile) -> HashMap<String, &CanonicalMessage> {
-    let mut all_messages = HashMap::new();
-
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_messages: &mut HashMap<String, &'a CanonicalMessage>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            all_messages.insert(message_name.clone(), message);
-            collect_from_messages(&message.nested_messages, &message_name, all_messages);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_messages);
-    all_messages
-}
-
-fn collect_all_enums(file: &CanonicalFile) -> HashMap<String, &CanonicalEnum> {
-    let mut all_enums = HashMap::new();
-
-    // Top-level enums
-    for enum_def in &file.enums {
-        all_enums.insert(enum_def.name.clone(), enum_def);
-    }
-
-    // Nested enums in messages
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_enums: &mut HashMap<String, &'a CanonicalEnum>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            for enum_def in &message.nested_enums {
-                let enum_key = format!("{}.{}", message_name, enum_def.name);
-                all_enums.insert(enum_key, enum_def);
-            }
-
-            collect_from_messages(&message.nested_messages, &message_name, all_enums);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_enums);
-    all_enums
-}
-
 // ========================================
 // Rule Export Table
 // ========================================
@@ -7,6 +7,7 @@ use crate::compat::bulk_enum_rules;
 use crate::compat::bulk_extension_rules;
 use crate::compat::bulk_field_rules;
 use crate::compat::bulk_file_rules;
+use crate::compat::bulk_forward_rules;
 use crate::compat::bulk_message_rules;
 use crate::compat::bulk_other_rules;
 use crate::compat::bulk_package_rules;
@@ -274,6 +275,45 @@ const BULK_RULES: &[crate::compat::types::RuleEntry] = &[
     ),
 ];
 
+/// Informational rules that report notable, non-breaking changes
+pub fn get_info_rule_mapping() -> &'static [crate::compat::types::RuleEntry] {
+    bulk_enum_rules::ENUM_INFO_RULES
+}
+
+/// Rules that only run when checking forward compatibility
+pub fn get_forward_rule_mapping() -> &'static [crate::compat::types::RuleEntry] {
+    bulk_forward_rules::FORWARD_RULES
+}
+
+/// Buf rules that only guard backward compatibility, skipped when checking
+/// forward only. They report deleted elements, which readers on the previous
+/// schema simply never see. Deleted services and RPCs are not listed, since
+/// old clients still call them; every rule not listed runs in both directions.
+const BACKWARD_ONLY_RULES: &[&str] = &[
+    "ENUM_VALUE_NO_DELETE",
+    "ENUM_VALUE_NO_DELETE_UNLESS_NAME_RESERVED",
+    "ENUM_VALUE_NO_DELETE_UNLESS_NUMBER_RESERVED",
+    "EXTENSION_MESSAGE_NO_DELETE",
+    "EXTENSION_NO_DELETE",
+    "FIELD_NO_DELETE",
+    "FIELD_NO_DELETE_UNLESS_NAME_RESERVED",
+    "FIELD_NO_DELETE_UNLESS_NUMBER_RESERVED",
+    "FILE_NO_DELETE",
+    "MESSAGE_NO_DELETE",
+    "ONEOF_NO_DELETE",
+    "PACKAGE_ENUM_NO_DELETE",
+    "PACKAGE_EXTENSION_NO_DELETE",
+    "PACKAGE_MESSAGE_NO_DELETE",
+    "PACKAGE_NO_DELETE",
+    "RESERVED_ENUM_NO_DELETE",
+    "RESERVED_MESSAGE_NO_DELETE",
+];
+
+/// Whether a Buf rule only guards backward compatibility
+pub fn is_backward_only_rule(rule_id: &str) -> bool {
+    BACKWARD_ONLY_RULES.contains(&rule_id)
+}
+
 /// Get count of all bulk-generated rules
 pub const fn get_bulk_rule_count() -> usize {
     BULK_RULES.len()
@@ -289,6 +329,13 @@ pub fn verify_bulk_rules() -> Result<(), String> {
         }
     }
 
+    // Verify the direction table only names registered rules
+    for rule_id in BACKWARD_ONLY_RULES {
+        if !seen.contains(rule_id) {
+            return Err(format!("Unknown backward-only rule: {rule_id}"));
+        }
+    }
+
     // Verify expected count exactly matches Buf
     let expected_count = 69; // Exactly matching Buf's breaking rule count
     let actual_count = BULK_RULES.len();
@@ -2,7 +2,7 @@
 //!
 //! These rules handle service definitions, RPC methods, and their attributes.
 
-use crate::canonical::{CanonicalFile, CanonicalMethod, CanonicalService};
+use crate::canonical::{CanonicalFile, CanonicalMethod};
 use crate::compat::handlers::{create_breaking_change, create_location};
 use crate::compat::types::{RuleContext, RuleResult};
 use std::collections::HashMap;
@@ -19,8 +19,8 @@ pub fn check_service_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_services = collect_all_services(previous);
-    let curr_services = collect_all_services(current);
+    let prev_services = previous.services_by_path();
+    let curr_services = current.services_by_path();
 
     for service_name in prev_services.keys() {
         if !curr_services.contains_key(service_name) {
@@ -49,8 +49,8 @@ pub fn check_rpc_no_delete(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_services = collect_all_services(previous);
-    let curr_services = collect_all_services(current);
+    let prev_services = previous.services_by_path();
+    let curr_services = current.services_by_path();
 
     for (service_name, prev_service) in &prev_services {
         if let Some(curr_service) = curr_services.get(service_name) {
@@ -98,8 +98,8 @@ pub fn check_rpc_same_request_type(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_services = collect_all_services(previous);
-    let curr_services = collect_all_services(current);
+    let prev_services = previous.services_by_path();
+    let curr_services = current.services_by_path();
 
     for (service_name, prev_service) in &prev_services {
         if let Some(curr_service) = curr_services.get(service_name) {
@@ -115,9 +115,10 @@ pub fn check_rpc_same_request_type(
                 .collect();
 
             for (method_name, prev_method) in &prev_methods {
-                if let Some(curr_method) = curr_methods.get(method_name) {
-                    if prev_method.input_type != curr_method.input_type {
-                        changes.push(create_breaking_change(
+                if let Some(curr_method) = curr_methods.get(method_name)
+                    && prev_method.input_type != curr_method.input_type
+                {
+                    changes.push(create_breaking_change(
                             "RPC_SAME_REQUEST_TYPE",
                             format!(
                                 "RPC \"{}\" request type changed from \"{}\" to \"{}\" in service \"{}\".",
@@ -131,7 +132,6 @@ pub fn check_rpc_same_request_type(
                             )),
                             vec!["RPC".to_string()],
                         ));
-                    }
                 }
             }
         }
@@ -148,8 +148,8 @@ pub fn check_rpc_same_response_type(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_services = collect_all_services(previous);
-    let curr_services = collect_all_services(current);
+    let prev_services = previous.services_by_path();
+    let curr_services = current.services_by_path();
 
     for (service_name, prev_service) in &prev_services {
         if let Some(curr_service) = curr_services.get(service_name) {
@@ -165,9 +165,10 @@ pub fn check_rpc_same_response_type(
                 .collect();
 
             for (method_name, prev_method) in &prev_methods {
-                if let Some(curr_method) = curr_methods.get(method_name) {
-                    if prev_method.output_type != curr_method.output_type {
-                        changes.push(create_breaking_change(
+                if let Some(curr_method) = curr_methods.get(method_name)
+                    && prev_method.output_type != curr_method.output_type
+                {
+                    changes.push(create_breaking_change(
                             "RPC_SAME_RESPONSE_TYPE",
                             format!(
                                 "RPC \"{}\" response type changed from \"{}\" to \"{}\" in service \"{}\".",
@@ -181,7 +182,6 @@ pub fn check_rpc_same_response_type(
                             )),
                             vec!["RPC".to_string()],
                         ));
-                    }
                 }
             }
         }
@@ -198,8 +198,8 @@ pub fn check_rpc_same_client_streaming(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_services = collect_all_services(previous);
-    let curr_services = collect_all_services(current);
+    let prev_services = previous.services_by_path();
+    let curr_services = current.services_by_path();
 
     for (service_name, prev_service) in &prev_services {
         if let Some(curr_service) = curr_services.get(service_name) {
@@ -215,23 +215,26 @@ pub fn check_rpc_same_client_streaming(
                 .collect();
 
             for (method_name, prev_method) in &prev_methods {
-                if let Some(curr_method) = curr_methods.get(method_name) {
-                    if prev_method.client_streaming != curr_method.client_streaming {
-                        changes.push(create_breaking_change(
-                            "RPC_SAME_CLIENT_STREAMING",
-                            format!(
-                                "RPC \"{}\" client streaming changed from {} to {} in service \"{}\".",
-                                method_name, prev_method.client_streaming, curr_method.client_streaming, service_name
-                            ),
-                            create_location(&context.current_file, "rpc", method_name),
-                            Some(create_location(
-                                context.previous_file.as_deref().unwrap_or(""),
-                                "rpc",
-                                method_name
-                            )),
-                            vec!["RPC".to_string()],
-                        ));
-                    }
+                if let Some(curr_method) = curr_methods.get(method_name)
+                    && prev_method.client_streaming != curr_method.client_streaming
+                {
+                    changes.push(create_breaking_change(
+                        "RPC_SAME_CLIENT_STREAMING",
+                        format!(
+                            "RPC \"{}\" client streaming changed from {} to {} in service \"{}\".",
+                            method_name,
+                            prev_method.client_streaming,
+                            curr_method.client_streaming,
+                            service_name
+                        ),
+                        create_location(&context.current_file, "rpc", method_name),
+                        Some(create_location(
+                            context.previous_file.as_deref().unwrap_or(""),
+                            "rpc",
+                            method_name,
+                        )),
+                        vec!["RPC".to_string()],
+                    ));
                 }
             }
         }
@@ -248,8 +251,8 @@ pub fn check_rpc_same_server_streaming(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_services = collect_all_services(previous);
-    let curr_services = collect_all_services(current);
+    let prev_services = previous.services_by_path();
+    let curr_services = current.services_by_path();
 
     for (service_name, prev_service) in &prev_services {
         if let Some(curr_service) = curr_services.get(service_name) {
@@ -265,23 +268,26 @@ pub fn check_rpc_same_server_streaming(
                 .collect();
 
             for (method_name, prev_method) in &prev_methods {
-                if let Some(curr_method) = curr_methods.get(method_name) {
-                    if prev_method.server_streaming != curr_method.server_streaming {
-                        changes.push(create_breaking_change(
-                            "RPC_SAME_SERVER_STREAMING",
-                            format!(
-                                "RPC \"{}\" server streaming changed from {} to {} in service \"{}\".",
-                                method_name, prev_method.server_streaming, curr_method.server_streaming, service_name
-                            ),
-                            create_location(&context.current_file, "rpc", method_name),
-                            Some(create_location(
-                                context.previous_file.as_deref().unwrap_or(""),
-                                "rpc",
-                                method_name
-                            )),
-                            vec!["RPC".to_string()],
-                        ));
-                    }
+                if let Some(curr_method) = curr_methods.get(method_name)
+                    && prev_method.server_streaming != curr_method.server_streaming
+                {
+                    changes.push(create_breaking_change(
+                        "RPC_SAME_SERVER_STREAMING",
+                        format!(
+                            "RPC \"{}\" server streaming changed from {} to {} in service \"{}\".",
+                            method_name,
+                            prev_method.server_streaming,
+                            curr_method.server_streaming,
+                            service_name
+                        ),
+                        create_location(&context.current_file, "rpc", method_name),
+                        Some(create_location(
+                            context.previous_file.as_deref().unwrap_or(""),
+                            "rpc",
+                            method_name,
+                        )),
+                        vec!["RPC".to_string()],
+                    ));
                 }
             }
         }
@@ -290,18 +296,6 @@ pub fn check_rpc_same_server_streaming(
     RuleResult::with_changes(changes)
 }
 
-// ========================================
-// Helper Functions
-// ========================================
-
-fn collect_all_services(file: &CanonicalFile) -> HashMap<String, &CanonicalService> {
-    let mut all_services = HashMap::new();
-    for service in &file.services {
-        all_services.insert(service.name.clone(), service);
-    }
-    all_services
-}
-
 // ========================================
 // Rule Export Table
 // ========================================
@@ -2,10 +2,10 @@
 //!
 //! These rules handle special cases and advanced scenarios not covered by other rule categories.
 
-use crate::canonical::{CanonicalEnum, CanonicalFile, CanonicalMessage};
+use crate::canonical::CanonicalFile;
 use crate::compat::handlers::{create_breaking_change, create_location};
 use crate::compat::types::{RuleContext, RuleResult};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::HashMap;
 
 // ========================================
 // SPECIAL Rules
@@ -75,8 +75,8 @@ pub fn check_field_name_same_case(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_messages = collect_all_messages(previous);
-    let curr_messages = collect_all_messages(current);
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
 
     for (message_path, prev_message) in &prev_messages {
         if let Some(curr_message) = curr_messages.get(message_path) {
@@ -125,27 +125,27 @@ pub fn check_enum_allow_alias_same(
 ) -> RuleResult {
     let mut changes = Vec::new();
 
-    let prev_enums = collect_all_enums(previous);
-    let curr_enums = collect_all_enums(current);
+    let prev_enums = previous.enums_by_path();
+    let curr_enums = current.enums_by_path();
 
     for (enum_path, prev_enum) in &prev_enums {
-        if let Some(curr_enum) = curr_enums.get(enum_path) {
-            if prev_enum.allow_alias != curr_enum.allow_alias {
-                changes.push(create_breaking_change(
-                    "ENUM_ALLOW_ALIAS_SAME",
-                    format!(
-                        "Enum \"{}\" allow_alias setting changed from {:?} to {:?}.",
-                        enum_path, prev_enum.allow_alias, curr_enum.allow_alias
-                    ),
-                    create_location(&context.current_file, "enum", enum_path),
-                    Some(create_location(
-                        context.previous_file.as_deref().unwrap_or(""),
-                        "enum",
-                        enum_path,
-                    )),
-                    vec!["ENUM".to_string()],
-                ));
-            }
+        if let Some(curr_enum) = curr_enums.get(enum_path)
+            && prev_enum.allow_alias != curr_enum.allow_alias
+        {
+            changes.push(create_breaking_change(
+                "ENUM_ALLOW_ALIAS_SAME",
+                format!(
+                    "Enum \"{}\" allow_alias setting changed from {:?} to {:?}.",
+                    enum_path, prev_enum.allow_alias, curr_enum.allow_alias
+                ),
+                create_location(&context.current_file, "enum", enum_path),
+                Some(create_location(
+                    context.previous_file.as_deref().unwrap_or(""),
+                    "enum",
+                    enum_path,
+                )),
+                vec!["ENUM".to_string()],
+            ));
         }
     }
 
@@ -156,64 +156,6 @@ pub fn check_enum_allow_alias_same(
 // Helper Functions
 // ========================================
 
-fn collect_all_messages(file: &CanonicalFile) -> HashMap<String, &CanonicalMessage> {
-    let mut all_messages = HashMap::new();
-
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_messages: &mut HashMap<String, &'a CanonicalMessage>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            all_messages.insert(message_name.clone(), message);
-            collect_from_messages(&message.nested_messages, &message_name, all_messages);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_messages);
-    all_messages
-}
-
-fn collect_all_enums(file: &CanonicalFile) -> HashMap<String, &CanonicalEnum> {
-    let mut all_enums = HashMap::new();
-
-    // Top-level enums
-    for enum_def in &file.enums {
-        all_enums.insert(enum_def.name.clone(), enum_def);
-    }
-
-    // Nested enums in messages
-    fn collect_from_messages<'a>(
-        messages: &'a BTreeSet<CanonicalMessage>,
-        prefix: &str,
-        all_enums: &mut HashMap<String, &'a CanonicalEnum>,
-    ) {
-        for message in messages {
-            let message_name = if prefix.is_empty() {
-                message.name.clone()
-            } else {
-                format!("{}.{}", prefix, message.name)
-            };
-
-            for enum_def in &message.nested_enums {
-                let enum_key = format!("{}.{}", message_name, enum_def.name);
-                all_enums.insert(enum_key, enum_def);
-            }
-
-            collect_from_messages(&message.nested_messages, &message_name, all_enums);
-        }
-    }
-
-    collect_from_messages(&file.messages, "", &mut all_enums);
-    all_enums
-}
-
 fn is_snake_case(name: &str) -> bool {
     name.chars()
         .all(|c| c.is_lowercase() || c.is_numeric() || c == '_')
@@ -4,10 +4,65 @@
 //! two Protocol Buffer files, using the simplified bulk rule registry system.
 
 use crate::canonical::CanonicalFile;
-use crate::compat::bulk_rule_registry;
 use crate::compat::types::{BreakingChange, RuleContext};
+use crate::compat::{bulk_rule_registry, renames};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Which readers must accept data written with the other schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// New readers accept data written with the previous schema (Buf's rules)
+    #[default]
+    Backward,
+    /// Previous readers accept data written with the new schema
+    Forward,
+    /// Both of the above
+    Full,
+}
+
+impl Direction {
+    /// The name used in configuration files and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::Backward => "backward",
+            Direction::Forward => "forward",
+            Direction::Full => "full",
+        }
+    }
+
+    fn checks_backward(self) -> bool {
+        self != Direction::Forward
+    }
+
+    fn checks_forward(self) -> bool {
+        self != Direction::Backward
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "backward" => Ok(Direction::Backward),
+            "forward" => Ok(Direction::Forward),
+            "full" => Ok(Direction::Full),
+            other => {
+                anyhow::bail!("unknown direction '{other}' (expected backward, forward or full)")
+            }
+        }
+    }
+}
 
 /// Configuration for breaking change detection
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +94,12 @@ pub struct BreakingConfig {
     /// Enum name suffixes that cannot be changed
     #[serde(default)]
     pub enum_no_change_suffixes: Vec<String>,
+    /// Compatibility direction to check
+    #[serde(default)]
+    pub direction: Direction,
+    /// Pair removed and added elements to report probable renames
+    #[serde(default)]
+    pub detect_renames: bool,
 }
 
 impl BreakingConfig {
@@ -72,6 +133,8 @@ impl Default for BreakingConfig {
             service_no_change_suffixes: Vec::new(),
             message_no_change_suffixes: Vec::new(),
             enum_no_change_suffixes: Vec::new(),
+            direction: Direction::default(),
+            detect_renames: false,
         }
     }
 }
@@ -89,6 +152,9 @@ pub struct BreakingResult {
     pub executed_rules: Vec<String>,
     /// Rules that failed to execute
     pub failed_rules: Vec<String>,
+    /// Non-breaking changes worth surfacing (e.g. newly deprecated enum values)
+    #[serde(default)]
+    pub informational: Vec<BreakingChange>,
 }
 
 impl BreakingResult {
@@ -100,6 +166,7 @@ impl BreakingResult {
             summary: HashMap::new(),
             executed_rules: Vec::new(),
             failed_rules: Vec::new(),
+            informational: Vec::new(),
         }
     }
 
@@ -118,6 +185,11 @@ impl BreakingResult {
         self.changes.extend(new_changes);
     }
 
+    /// Add informational changes to the result; these never mark it as breaking
+    pub fn add_informational(&mut self, changes: Vec<BreakingChange>) {
+        self.informational.extend(changes);
+    }
+
     /// Mark a rule as executed successfully
     pub fn mark_rule_executed(&mut self, rule_id: String) {
         self.executed_rules.push(rule_id);
@@ -162,11 +234,22 @@ impl BreakingEngine {
             metadata: HashMap::new(),
         };
 
-        // Get all rules from bulk registry
-        let all_rules = bulk_rule_registry::get_bulk_rule_mapping();
+        // Buf rules check backward compatibility; in forward-only mode the
+        // deletion rules are dropped and the forward rules added
+        let direction = config.direction;
+        let bulk_rules =
+            bulk_rule_registry::get_bulk_rule_mapping()
+                .iter()
+                .filter(|(rule_id, _)| {
+                    direction.checks_backward()
+                        || !bulk_rule_registry::is_backward_only_rule(rule_id)
+                });
+        let forward_rules = bulk_rule_registry::get_forward_rule_mapping()
+            .iter()
+            .filter(|_| direction.checks_forward());
 
         // Execute selected rules based on configuration
-        for (rule_id, rule_fn) in all_rules.iter() {
+        for (rule_id, rule_fn) in bulk_rules.chain(forward_rules) {
             // Skip rules that are explicitly excluded
             if config.except_rules.contains(&rule_id.to_string()) {
                 continue;
@@ -202,6 +285,36 @@ impl BreakingEngine {
             }
         }
 
+        // Informational rules are independent of category selection
+        for (rule_id, rule_fn) in bulk_rule_registry::get_info_rule_mapping() {
+            if config.except_rules.contains(&rule_id.to_string()) {
+                continue;
+            }
+
+            let rule_result = rule_fn(current, previous, &context);
+
+            if rule_result.success {
+                result.add_informational(rule_result.changes);
+            } else {
+                result.mark_rule_failed(rule_id.to_string());
+            }
+        }
+
+        if config.detect_renames {
+            let renames = renames::detect_renames(current, previous);
+            for change in &mut result.changes {
+                if let Some(rename) = renames.iter().find(|rename| rename.explains(change)) {
+                    change.hint = Some(rename.hint());
+                }
+            }
+            result.add_informational(
+                renames
+                    .iter()
+                    .map(|rename| rename.to_info_change(&context))
+                    .collect(),
+            );
+        }
+
         result
     }
 
@@ -279,6 +392,17 @@ fn get_rule_categories(rule_id: &str) -> Vec<String> {
             vec!["WIRE_JSON".to_string()]
         }
 
+        // Required fields break the wire format in both directions, and the
+        // forward rules affect it too, so they run under every category
+        "MESSAGE_SAME_REQUIRED_FIELDS" | "ENUM_VALUE_NO_ADD_TO_CLOSED" | "ONEOF_NO_ADD_FIELD" => {
+            vec![
+                "FILE".to_string(),
+                "PACKAGE".to_string(),
+                "WIRE".to_string(),
+                "WIRE_JSON".to_string(),
+            ]
+        }
+
         // Default to FILE category for unknown rules
         _ => vec!["FILE".to_string()],
     }
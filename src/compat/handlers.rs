@@ -100,6 +100,21 @@ pub fn create_breaking_change(
         previous_location,
         severity: BreakingSeverity::Error,
         categories,
+        hint: None,
+    }
+}
+
+/// Helper function to create an informational (non-breaking) change
+pub fn create_info_change(
+    rule_id: &str,
+    message: String,
+    location: BreakingLocation,
+    previous_location: Option<BreakingLocation>,
+    categories: Vec<String>,
+) -> BreakingChange {
+    BreakingChange {
+        severity: BreakingSeverity::Info,
+        ..create_breaking_change(rule_id, message, location, previous_location, categories)
     }
 }
 
@@ -403,38 +418,36 @@ pub fn check_enum_value_no_delete(
     for full_name in previous_enums.keys() {
         if let Some(previous_enum) =
             find_enum_by_name(&previous.messages, &previous.enums, full_name)
-        {
-            if let Some(current_enum) =
+            && let Some(current_enum) =
                 find_enum_by_name(&current.messages, &current.enums, full_name)
-            {
-                let current_values: HashSet<i32> =
-                    current_enum.values.iter().map(|v| v.number).collect();
+        {
+            let current_values: HashSet<i32> =
+                current_enum.values.iter().map(|v| v.number).collect();
 
-                for previous_value in &previous_enum.values {
-                    if !current_values.contains(&previous_value.number) {
-                        let change = create_breaking_change(
-                            "ENUM_VALUE_NO_DELETE",
-                            format!(
-                                "Enum value \"{}\" with number {} was deleted from enum \"{}\".",
-                                previous_value.name, previous_value.number, full_name
-                            ),
-                            create_location(
-                                &context.current_file,
-                                "enum_value",
-                                &format!("{}.{}", full_name, previous_value.name),
-                            ),
-                            Some(create_location(
-                                context
-                                    .previous_file
-                                    .as_ref()
-                                    .unwrap_or(&"previous".to_string()),
-                                "enum_value",
-                                &format!("{}.{}", full_name, previous_value.name),
-                            )),
-                            vec!["FILE".to_string(), "PACKAGE".to_string()],
-                        );
-                        changes.push(change);
-                    }
+            for previous_value in &previous_enum.values {
+                if !current_values.contains(&previous_value.number) {
+                    let change = create_breaking_change(
+                        "ENUM_VALUE_NO_DELETE",
+                        format!(
+                            "Enum value \"{}\" with number {} was deleted from enum \"{}\".",
+                            previous_value.name, previous_value.number, full_name
+                        ),
+                        create_location(
+                            &context.current_file,
+                            "enum_value",
+                            &format!("{}.{}", full_name, previous_value.name),
+                        ),
+                        Some(create_location(
+                            context
+                                .previous_file
+                                .as_ref()
+                                .unwrap_or(&"previous".to_string()),
+                            "enum_value",
+                            &format!("{}.{}", full_name, previous_value.name),
+                        )),
+                        vec!["FILE".to_string(), "PACKAGE".to_string()],
+                    );
+                    changes.push(change);
                 }
             }
         }
@@ -569,38 +582,38 @@ pub fn check_field_same_name(
                 .collect();
 
             for previous_field in &previous_message.fields {
-                if let Some(current_field) = current_fields.get(&previous_field.number) {
-                    if current_field.name != previous_field.name {
-                        let change = create_breaking_change(
-                            "FIELD_SAME_NAME",
-                            format!(
-                                "Field {} on message \"{}\" changed name from \"{}\" to \"{}\".",
-                                previous_field.number,
-                                previous_message.name,
-                                previous_field.name,
-                                current_field.name
-                            ),
-                            create_location(
-                                &context.current_file,
-                                "field",
-                                &format!("{}.{}", previous_message.name, current_field.name),
-                            ),
-                            Some(create_location(
-                                context
-                                    .previous_file
-                                    .as_ref()
-                                    .unwrap_or(&"previous".to_string()),
-                                "field",
-                                &format!("{}.{}", previous_message.name, previous_field.name),
-                            )),
-                            vec![
-                                "FILE".to_string(),
-                                "PACKAGE".to_string(),
-                                "WIRE_JSON".to_string(),
-                            ],
-                        );
-                        changes.push(change);
-                    }
+                if let Some(current_field) = current_fields.get(&previous_field.number)
+                    && current_field.name != previous_field.name
+                {
+                    let change = create_breaking_change(
+                        "FIELD_SAME_NAME",
+                        format!(
+                            "Field {} on message \"{}\" changed name from \"{}\" to \"{}\".",
+                            previous_field.number,
+                            previous_message.name,
+                            previous_field.name,
+                            current_field.name
+                        ),
+                        create_location(
+                            &context.current_file,
+                            "field",
+                            &format!("{}.{}", previous_message.name, current_field.name),
+                        ),
+                        Some(create_location(
+                            context
+                                .previous_file
+                                .as_ref()
+                                .unwrap_or(&"previous".to_string()),
+                            "field",
+                            &format!("{}.{}", previous_message.name, previous_field.name),
+                        )),
+                        vec![
+                            "FILE".to_string(),
+                            "PACKAGE".to_string(),
+                            "WIRE_JSON".to_string(),
+                        ],
+                    );
+                    changes.push(change);
                 }
             }
         }
@@ -886,47 +899,45 @@ pub fn check_enum_value_same_name(
     for full_name in previous_enums.keys() {
         if let Some(previous_enum) =
             find_enum_by_name(&previous.messages, &previous.enums, full_name)
-        {
-            if let Some(current_enum) =
+            && let Some(current_enum) =
                 find_enum_by_name(&current.messages, &current.enums, full_name)
-            {
-                let current_values: HashMap<i32, &crate::canonical::CanonicalEnumValue> =
-                    current_enum.values.iter().map(|v| (v.number, v)).collect();
-
-                for previous_value in &previous_enum.values {
-                    if let Some(current_value) = current_values.get(&previous_value.number) {
-                        if current_value.name != previous_value.name {
-                            let change = create_breaking_change(
-                                "ENUM_VALUE_SAME_NAME",
-                                format!(
-                                    "Enum value {} on enum \"{}\" changed name from \"{}\" to \"{}\".",
-                                    previous_value.number,
-                                    full_name,
-                                    previous_value.name,
-                                    current_value.name
-                                ),
-                                create_location(
-                                    &context.current_file,
-                                    "enum_value",
-                                    &format!("{}.{}", full_name, current_value.name),
-                                ),
-                                Some(create_location(
-                                    context
-                                        .previous_file
-                                        .as_ref()
-                                        .unwrap_or(&"previous".to_string()),
-                                    "enum_value",
-                                    &format!("{}.{}", full_name, previous_value.name),
-                                )),
-                                vec![
-                                    "FILE".to_string(),
-                                    "PACKAGE".to_string(),
-                                    "WIRE_JSON".to_string(),
-                                ],
-                            );
-                            changes.push(change);
-                        }
-                    }
+        {
+            let current_values: HashMap<i32, &crate::canonical::CanonicalEnumValue> =
+                current_enum.values.iter().map(|v| (v.number, v)).collect();
+
+            for previous_value in &previous_enum.values {
+                if let Some(current_value) = current_values.get(&previous_value.number)
+                    && current_value.name != previous_value.name
+                {
+                    let change = create_breaking_change(
+                        "ENUM_VALUE_SAME_NAME",
+                        format!(
+                            "Enum value {} on enum \"{}\" changed name from \"{}\" to \"{}\".",
+                            previous_value.number,
+                            full_name,
+                            previous_value.name,
+                            current_value.name
+                        ),
+                        create_location(
+                            &context.current_file,
+                            "enum_value",
+                            &format!("{}.{}", full_name, current_value.name),
+                        ),
+                        Some(create_location(
+                            context
+                                .previous_file
+                                .as_ref()
+                                .unwrap_or(&"previous".to_string()),
+                            "enum_value",
+                            &format!("{}.{}", full_name, previous_value.name),
+                        )),
+                        vec![
+                            "FILE".to_string(),
+                            "PACKAGE".to_string(),
+                            "WIRE_JSON".to_string(),
+                        ],
+                    );
+                    changes.push(change);
                 }
             }
         }
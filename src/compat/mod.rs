@@ -7,6 +7,7 @@ pub mod bulk_enum_rules;
 pub mod bulk_extension_rules;
 pub mod bulk_field_rules;
 pub mod bulk_file_rules;
+pub mod bulk_forward_rules;
 pub mod bulk_message_rules;
 pub mod bulk_other_rules;
 pub mod bulk_package_rules;
@@ -17,8 +18,9 @@ pub mod bulk_special_rules;
 pub mod categories;
 pub mod engine;
 pub mod handlers;
+pub mod renames;
 pub mod types;
 
 pub use categories::BreakingCategory;
-pub use engine::{BreakingConfig, BreakingEngine, BreakingResult};
+pub use engine::{BreakingConfig, BreakingEngine, BreakingResult, Direction};
 pub use types::{BreakingChange, BreakingLocation, BreakingSeverity};
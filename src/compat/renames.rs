@@ -0,0 +1,245 @@
+//! Rename detection heuristics
+//!
+//! A renamed element looks like a deletion to the Buf rules: a renamed
+//! message is reported by MESSAGE_NO_DELETE, a renamed field by
+//! FIELD_SAME_NAME. When `BreakingConfig::detect_renames` is set, the engine
+//! pairs removed and added elements by structure, attaches a "probably
+//! renamed" hint to the violations they explain and reports each pair as an
+//! informational change.
+//!
+//! - Messages pair when their fields have the same numbers, labels and types.
+//! - Fields pair when the same number keeps its label and type under a new
+//!   name, in the same or a renamed message.
+//! - RPCs pair within a service when they have the same request, response
+//!   and streaming.
+//!
+//! Field and RPC types are compared after applying the message renames.
+//!
+//! Candidates that match more than one element are not paired.
+
+use crate::canonical::{CanonicalFile, CanonicalMessage, CanonicalMethod};
+use crate::compat::handlers::{create_info_change, create_location};
+use crate::compat::types::{BreakingChange, RuleContext};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A removed element paired with the added element it was probably renamed to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    /// `message`, `field` or `rpc`, as in rule locations.
+    pub element_type: &'static str,
+    /// Message of a field or service of an RPC, as named in the previous file.
+    pub parent: Option<String>,
+    pub from: String,
+    pub to: String,
+}
+
+impl Rename {
+    /// The hint attached to violations this rename explains.
+    pub fn hint(&self) -> String {
+        match &self.parent {
+            Some(parent) => format!(
+                "probably renamed from {parent}.{} to {parent}.{}",
+                self.from, self.to
+            ),
+            None => format!("probably renamed from {} to {}", self.from, self.to),
+        }
+    }
+
+    /// Whether a violation reports the previous element of this rename.
+    pub fn explains(&self, change: &BreakingChange) -> bool {
+        let Some(previous) = &change.previous_location else {
+            return false;
+        };
+        previous.element_type == self.element_type
+            && previous.element_name == self.from
+            && self
+                .parent
+                .as_ref()
+                .is_none_or(|parent| change.message.contains(&format!("\"{parent}\"")))
+    }
+
+    /// The rename as an informational change.
+    pub fn to_info_change(&self, context: &RuleContext) -> BreakingChange {
+        let (rule_id, label, category) = match self.element_type {
+            "message" => ("MESSAGE_PROBABLY_RENAMED", "Message", "MESSAGE"),
+            "field" => ("FIELD_PROBABLY_RENAMED", "Field", "FIELD"),
+            _ => ("RPC_PROBABLY_RENAMED", "RPC", "RPC"),
+        };
+        let scope = match (&self.parent, self.element_type) {
+            (Some(parent), "field") => format!(" in message \"{parent}\""),
+            (Some(parent), _) => format!(" in service \"{parent}\""),
+            (None, _) => String::new(),
+        };
+        create_info_change(
+            rule_id,
+            format!(
+                "{label} \"{}\" was probably renamed to \"{}\"{scope}.",
+                self.from, self.to
+            ),
+            create_location(&context.current_file, self.element_type, &self.to),
+            Some(create_location(
+                context.previous_file.as_deref().unwrap_or(""),
+                self.element_type,
+                &self.from,
+            )),
+            vec![category.to_string()],
+        )
+    }
+}
+
+/// Pairs removed and added messages, fields and RPCs by structure.
+pub fn detect_renames(current: &CanonicalFile, previous: &CanonicalFile) -> Vec<Rename> {
+    let prev_messages = previous.messages_by_path();
+    let curr_messages = current.messages_by_path();
+
+    let message_renames = unique_pairs(
+        prev_messages
+            .iter()
+            .filter(|(path, message)| {
+                !curr_messages.contains_key(*path) && !message.fields.is_empty()
+            })
+            .map(|(path, message)| (message_signature(message), path.clone())),
+        curr_messages
+            .iter()
+            .filter(|(path, message)| {
+                !prev_messages.contains_key(*path) && !message.fields.is_empty()
+            })
+            .map(|(path, message)| (message_signature(message), path.clone())),
+    );
+
+    let mut renames: Vec<Rename> = message_renames
+        .iter()
+        .map(|(from, to)| Rename {
+            element_type: "message",
+            parent: None,
+            from: from.clone(),
+            to: to.clone(),
+        })
+        .collect();
+
+    // Types in the previous file, as named after the message renames
+    let qualify = |path: &str| match &current.package {
+        Some(package) if !package.is_empty() => format!(".{package}.{path}"),
+        _ => format!(".{path}"),
+    };
+    let renamed_types: HashMap<String, String> = message_renames
+        .iter()
+        .map(|(from, to)| (qualify(from), qualify(to)))
+        .collect();
+    let resolve = |type_name: &str| -> String {
+        renamed_types
+            .get(type_name)
+            .cloned()
+            .unwrap_or_else(|| type_name.to_string())
+    };
+
+    // Fields of messages that kept their name or were renamed
+    let message_pairs = prev_messages.iter().filter_map(|(path, prev_message)| {
+        let curr_path = message_renames.get(path).unwrap_or(path);
+        let curr_message = curr_messages.get(curr_path)?;
+        Some((path, *prev_message, *curr_message))
+    });
+    for (path, prev_message, curr_message) in message_pairs {
+        let curr_fields: HashMap<i32, _> =
+            curr_message.fields.iter().map(|f| (f.number, f)).collect();
+        for prev_field in &prev_message.fields {
+            let Some(curr_field) = curr_fields.get(&prev_field.number) else {
+                continue;
+            };
+            if prev_field.name != curr_field.name
+                && prev_field.label == curr_field.label
+                && resolve(&prev_field.type_name) == curr_field.type_name
+            {
+                renames.push(Rename {
+                    element_type: "field",
+                    parent: Some(path.clone()),
+                    from: prev_field.name.clone(),
+                    to: curr_field.name.clone(),
+                });
+            }
+        }
+    }
+
+    let curr_services = current.services_by_path();
+    for (path, prev_service) in previous.services_by_path() {
+        let Some(curr_service) = curr_services.get(&path) else {
+            continue;
+        };
+        let removed = prev_service
+            .methods
+            .iter()
+            .filter(|m| !curr_service.methods.iter().any(|c| c.name == m.name));
+        let added = curr_service
+            .methods
+            .iter()
+            .filter(|m| !prev_service.methods.iter().any(|p| p.name == m.name));
+        let method_renames = unique_pairs(
+            removed.map(|m| (method_signature(m, &resolve), m.name.clone())),
+            added.map(|m| {
+                (
+                    method_signature(m, &|t: &str| t.to_string()),
+                    m.name.clone(),
+                )
+            }),
+        );
+        renames.extend(method_renames.into_iter().map(|(from, to)| Rename {
+            element_type: "rpc",
+            parent: Some(path.clone()),
+            from,
+            to,
+        }));
+    }
+
+    renames
+}
+
+/// Pairs removed and added names whose signature is shared by exactly one
+/// element on each side.
+fn unique_pairs<S: Eq + Hash>(
+    removed: impl Iterator<Item = (S, String)>,
+    added: impl Iterator<Item = (S, String)>,
+) -> BTreeMap<String, String> {
+    let removed = group_by_signature(removed);
+    let mut added = group_by_signature(added);
+
+    removed
+        .into_iter()
+        .filter_map(|(signature, mut from)| {
+            let to = added.remove(&signature)?;
+            (from.len() == 1 && to.len() == 1).then(|| (from.remove(0), to[0].clone()))
+        })
+        .collect()
+}
+
+fn group_by_signature<S: Eq + Hash>(
+    items: impl Iterator<Item = (S, String)>,
+) -> HashMap<S, Vec<String>> {
+    let mut groups: HashMap<S, Vec<String>> = HashMap::new();
+    for (signature, name) in items {
+        groups.entry(signature).or_default().push(name);
+    }
+    groups
+}
+
+fn message_signature(message: &CanonicalMessage) -> Vec<(i32, Option<&str>, &str)> {
+    let mut signature: Vec<_> = message
+        .fields
+        .iter()
+        .map(|f| (f.number, f.label.as_deref(), f.type_name.as_str()))
+        .collect();
+    signature.sort();
+    signature
+}
+
+fn method_signature(
+    method: &CanonicalMethod,
+    resolve: &dyn Fn(&str) -> String,
+) -> (String, String, bool, bool) {
+    (
+        resolve(&method.input_type),
+        resolve(&method.output_type),
+        method.client_streaming,
+        method.server_streaming,
+    )
+}
@@ -19,6 +19,9 @@ pub struct BreakingChange {
     pub severity: BreakingSeverity,
     /// Categories this rule belongs to
     pub categories: Vec<String>,
+    /// Likely explanation of the change, e.g. a probable rename
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
 }
 
 /// Location information for a breaking change
@@ -43,6 +46,8 @@ pub enum BreakingSeverity {
     Error,
     /// Warning about potential compatibility issues
     Warning,
+    /// Informational change that does not break clients
+    Info,
 }
 
 /// Context for rule execution
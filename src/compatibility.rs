@@ -1,6 +1,6 @@
 //! Provides structures and functions for checking backward-compatibility of Protobuf files.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeSet;
 
 //==============================================================================
@@ -8,7 +8,8 @@ use std::collections::BTreeSet;
 //==============================================================================
 
 /// Represents the backward-compatibility-relevant content of a .proto file.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(default)]
 pub struct CompatibilityModel {
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
     pub messages: BTreeSet<CompatibilityMessage>,
@@ -17,7 +18,8 @@ pub struct CompatibilityModel {
 }
 
 /// Represents a message for compatibility purposes.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(default)]
 pub struct CompatibilityMessage {
     pub name: String,
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
@@ -26,10 +28,12 @@ pub struct CompatibilityMessage {
 
 /// Represents a field for compatibility purposes.
 /// Note the absence of `name` and `label`.
-#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CompatibilityField {
     pub number: i32,
     pub type_name: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub group: bool,
 }
 
 // Custom implementation of Ord for CompatibilityField to sort by `number` first.
@@ -38,6 +42,7 @@ impl Ord for CompatibilityField {
         self.number
             .cmp(&other.number)
             .then_with(|| self.type_name.cmp(&other.type_name))
+            .then_with(|| self.group.cmp(&other.group))
     }
 }
 
@@ -48,7 +53,8 @@ impl PartialOrd for CompatibilityField {
 }
 
 /// Represents a service for compatibility purposes.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(default)]
 pub struct CompatibilityService {
     pub name: String,
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
@@ -56,7 +62,7 @@ pub struct CompatibilityService {
 }
 
 /// Represents a service method for compatibility purposes.
-#[derive(Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CompatibilityMethod {
     pub name: String,
     pub input_type: String,
@@ -68,49 +74,10 @@ pub struct CompatibilityMethod {
 //==============================================================================
 
 use crate::normalize;
-use anyhow::Context;
-use protobuf_parse::Parser;
 
 /// Parses a `.proto` file content and returns its compatibility model.
 pub fn get_compatibility_model(proto_content: &str) -> anyhow::Result<CompatibilityModel> {
-    let temp_dir = tempfile::tempdir().context("Failed to create temp directory")?;
-    let file_name = "input.proto";
-    let temp_path = temp_dir.path().join(file_name);
-    std::fs::write(&temp_path, proto_content).context("Failed to write to temp file")?;
-
-    for line in proto_content.lines() {
-        if line.trim().starts_with("import ") {
-            let path_str = line
-                .trim()
-                .trim_start_matches("import ")
-                .trim_start_matches("public ")
-                .trim_matches(|c| c == '"' || c == ';');
-
-            if !path_str.starts_with("google/protobuf/") {
-                let import_path = temp_dir.path().join(path_str);
-                if let Some(parent) = import_path.parent() {
-                    std::fs::create_dir_all(parent).context(format!(
-                        "Failed to create parent dirs for import: {path_str}"
-                    ))?;
-                }
-                std::fs::write(&import_path, "syntax = \"proto3\";")
-                    .context(format!("Failed to create dummy import file: {path_str}"))?;
-            }
-        }
-    }
-
-    let parsed = Parser::new()
-        .pure()
-        .include(temp_dir.path())
-        .input(&temp_path)
-        .file_descriptor_set()
-        .context("Protobuf parsing failed")?;
-
-    let file_descriptor = parsed
-        .file
-        .into_iter()
-        .find(|d| d.name() == file_name)
-        .context("Could not find the parsed file descriptor for the input file")?;
+    let file_descriptor = crate::parse::parse_file_descriptor(proto_content, None)?;
 
     Ok(normalize::normalize_compatibility_file(&file_descriptor))
 }
@@ -0,0 +1,782 @@
+//! Structured semantic diffs.
+//!
+//! [`CanonicalDiff::between`] walks two versions of a [`CanonicalFile`] and
+//! lists every added, removed or modified message, field, oneof, enum, enum
+//! value, service, method, extension and option. Each entry is classified:
+//!
+//! - `breaking`: removals; added `required` fields; changes to names,
+//!   types, labels, oneof membership, streaming, JSON names, defaults, the
+//!   package, the syntax and file-level language options; removed
+//!   reservations.
+//! - `non-breaking`: other additions, new reservations, `packed` and
+//!   `idempotency_level`.
+//! - `cosmetic`: `deprecated`, `debug_redact`, imports and custom options,
+//!   which change neither the encoding nor the generated API.
+//!
+//! The classification is intrinsic to each change and does not depend on a
+//! rule selection; use [`crate::compat::BreakingEngine`] for that.
+
+use crate::canonical::{
+    CanonicalEnum, CanonicalEnumValue, CanonicalExtension, CanonicalField, CanonicalFile,
+    CanonicalMessage, CanonicalMethod, CanonicalService, ReservedName, ReservedRange,
+};
+use ChangeClass::{Breaking, Cosmetic, NonBreaking};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// How a change affects existing readers and generated code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeClass {
+    Breaking,
+    NonBreaking,
+    Cosmetic,
+}
+
+impl ChangeClass {
+    /// Every class, most severe first.
+    pub const ALL: [ChangeClass; 3] = [
+        ChangeClass::Breaking,
+        ChangeClass::NonBreaking,
+        ChangeClass::Cosmetic,
+    ];
+}
+
+impl fmt::Display for ChangeClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChangeClass::Breaking => "breaking",
+            ChangeClass::NonBreaking => "non-breaking",
+            ChangeClass::Cosmetic => "cosmetic",
+        })
+    }
+}
+
+/// Whether an element was added, removed or modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl ChangeKind {
+    fn symbol(self) -> char {
+        match self {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Modified => '~',
+        }
+    }
+}
+
+/// The kind of element a [`DiffEntry`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffElement {
+    File,
+    Message,
+    Field,
+    Oneof,
+    Enum,
+    EnumValue,
+    Service,
+    Method,
+    Extension,
+}
+
+impl fmt::Display for DiffElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DiffElement::File => "file",
+            DiffElement::Message => "message",
+            DiffElement::Field => "field",
+            DiffElement::Oneof => "oneof",
+            DiffElement::Enum => "enum",
+            DiffElement::EnumValue => "enum value",
+            DiffElement::Service => "service",
+            DiffElement::Method => "method",
+            DiffElement::Extension => "extension",
+        })
+    }
+}
+
+/// A single change between two versions of a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffEntry {
+    pub element: DiffElement,
+    /// Fully-qualified name without a leading dot; the package for file-level
+    /// changes. Modified and added elements use their new name.
+    pub path: String,
+    pub change: ChangeKind,
+    pub class: ChangeClass,
+    /// The attribute that changed, for modified elements.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribute: Option<String>,
+    /// The previous value of the attribute, if it was set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<String>,
+    /// The new value of the attribute, if it is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<String>,
+}
+
+impl fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.change.symbol(), self.element)?;
+        if !self.path.is_empty() {
+            write!(f, " {}", self.path)?;
+        }
+        if let Some(attribute) = &self.attribute {
+            let show = |value: &Option<String>| value.clone().unwrap_or_else(|| "(unset)".into());
+            write!(
+                f,
+                ": {attribute} {} -> {}",
+                show(&self.old),
+                show(&self.new)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Every change between two versions of a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CanonicalDiff {
+    pub entries: Vec<DiffEntry>,
+}
+
+impl CanonicalDiff {
+    /// Lists the changes from `old` to `new`.
+    pub fn between(old: &CanonicalFile, new: &CanonicalFile) -> Self {
+        let mut differ = Differ {
+            package: new.package.clone().unwrap_or_default(),
+            entries: Vec::new(),
+        };
+        differ.file(old, new);
+
+        let old_messages = old.messages_by_path();
+        let new_messages = new.messages_by_path();
+        differ.keyed(
+            DiffElement::Message,
+            &old_messages,
+            &new_messages,
+            |d, path, old, new| d.message(path, old, new),
+        );
+
+        let old_enums = old.enums_by_path();
+        let new_enums = new.enums_by_path();
+        differ.keyed(
+            DiffElement::Enum,
+            &old_enums,
+            &new_enums,
+            |d, path, old, new| d.enumeration(path, old, new),
+        );
+
+        let old_services = old.services_by_path();
+        let new_services = new.services_by_path();
+        differ.keyed(
+            DiffElement::Service,
+            &old_services,
+            &new_services,
+            |d, path, old, new| {
+                d.keyed(
+                    DiffElement::Method,
+                    &methods_by_path(path, old),
+                    &methods_by_path(path, new),
+                    |d, path, old, new| d.method(path, old, new),
+                );
+            },
+        );
+
+        let (old_extensions, new_extensions) = (extensions_by_key(old), extensions_by_key(new));
+        for (key, old_extension) in &old_extensions {
+            if !new_extensions.contains_key(key) {
+                let path = differ.qualify(&old_extension.name);
+                differ.removed(DiffElement::Extension, &path);
+            }
+        }
+        for (key, new_extension) in &new_extensions {
+            let path = differ.qualify(&new_extension.name);
+            match old_extensions.get(key) {
+                Some(old_extension) => differ.extension(&path, old_extension, new_extension),
+                None => differ.added(
+                    DiffElement::Extension,
+                    &path,
+                    new_extension.label.as_deref() == Some("required"),
+                ),
+            }
+        }
+
+        CanonicalDiff {
+            entries: differ.entries,
+        }
+    }
+
+    /// True if there are no changes at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// True if any change is classified as breaking.
+    pub fn has_breaking_changes(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.class == ChangeClass::Breaking)
+    }
+
+    /// The changes of one class, in diff order.
+    pub fn entries_of(&self, class: ChangeClass) -> impl Iterator<Item = &DiffEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.class == class)
+    }
+}
+
+impl fmt::Display for CanonicalDiff {
+    /// One section per non-empty class, most severe first.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No semantic changes.");
+        }
+        for class in ChangeClass::ALL {
+            let entries: Vec<_> = self.entries_of(class).collect();
+            if entries.is_empty() {
+                continue;
+            }
+            let title = match class {
+                ChangeClass::Breaking => "Breaking",
+                ChangeClass::NonBreaking => "Non-breaking",
+                ChangeClass::Cosmetic => "Cosmetic",
+            };
+            writeln!(f, "{title} ({}):", entries.len())?;
+            for entry in entries {
+                writeln!(f, "  {entry}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An attribute compared between two versions of an element.
+type Attribute<T> = (&'static str, ChangeClass, fn(&T) -> Option<String>);
+
+const FILE_ATTRIBUTES: &[Attribute<CanonicalFile>] = &[
+    ("package", Breaking, |f| f.package.clone()),
+    ("syntax", Breaking, |f| Some(f.syntax.clone())),
+    ("imports", Cosmetic, |f| join(f.imports.iter().cloned())),
+    ("go_package", Breaking, |f| opt(&f.go_package)),
+    ("java_package", Breaking, |f| opt(&f.java_package)),
+    ("csharp_namespace", Breaking, |f| opt(&f.csharp_namespace)),
+    ("ruby_package", Breaking, |f| opt(&f.ruby_package)),
+    ("java_multiple_files", Breaking, |f| {
+        opt(&f.java_multiple_files)
+    }),
+    ("java_outer_classname", Breaking, |f| {
+        opt(&f.java_outer_classname)
+    }),
+    ("java_string_check_utf8", Breaking, |f| {
+        opt(&f.java_string_check_utf8)
+    }),
+    ("java_generic_services", Breaking, |f| {
+        opt(&f.java_generic_services)
+    }),
+    ("objc_class_prefix", Breaking, |f| opt(&f.objc_class_prefix)),
+    ("php_class_prefix", Breaking, |f| opt(&f.php_class_prefix)),
+    ("php_namespace", Breaking, |f| opt(&f.php_namespace)),
+    ("php_metadata_namespace", Breaking, |f| {
+        opt(&f.php_metadata_namespace)
+    }),
+    ("php_generic_services", Breaking, |f| {
+        opt(&f.php_generic_services)
+    }),
+    ("swift_prefix", Breaking, |f| opt(&f.swift_prefix)),
+    ("cc_generic_services", Breaking, |f| {
+        opt(&f.cc_generic_services)
+    }),
+    ("cc_enable_arenas", Breaking, |f| opt(&f.cc_enable_arenas)),
+    ("py_generic_services", Breaking, |f| {
+        opt(&f.py_generic_services)
+    }),
+    ("optimize_for", Breaking, |f| opt(&f.optimize_for)),
+];
+
+const MESSAGE_ATTRIBUTES: &[Attribute<CanonicalMessage>] = &[
+    ("message_set_wire_format", Breaking, |m| {
+        opt(&m.message_set_wire_format)
+    }),
+    ("no_standard_descriptor_accessor", Breaking, |m| {
+        opt(&m.no_standard_descriptor_accessor)
+    }),
+    ("deprecated", Cosmetic, |m| opt(&m.deprecated)),
+    ("reserved_ranges", Breaking, |m| {
+        ranges(&m.reserved_ranges, true)
+    }),
+    ("reserved_names", Breaking, |m| names(&m.reserved_names)),
+    ("extension_ranges", Breaking, |m| {
+        ranges(&m.extension_ranges, true)
+    }),
+];
+
+const FIELD_ATTRIBUTES: &[Attribute<CanonicalField>] = &[
+    ("name", Breaking, |f| Some(f.name.clone())),
+    ("type", Breaking, |f| {
+        Some(display_type(&f.type_name, f.group))
+    }),
+    ("label", Breaking, |f| Some(label(&f.label))),
+    ("json_name", Breaking, |f| opt(&f.json_name)),
+    ("default", Breaking, |f| opt(&f.default)),
+    ("jstype", Breaking, |f| opt(&f.jstype)),
+    ("ctype", Breaking, |f| opt(&f.ctype)),
+    ("cpp_string_type", Breaking, |f| opt(&f.cpp_string_type)),
+    ("utf8_validation", Breaking, |f| opt(&f.utf8_validation)),
+    ("java_utf8_validation", Breaking, |f| {
+        opt(&f.java_utf8_validation)
+    }),
+    ("weak", Breaking, |f| opt(&f.weak)),
+    ("deprecated", Cosmetic, |f| opt(&f.deprecated)),
+];
+
+const ENUM_ATTRIBUTES: &[Attribute<CanonicalEnum>] = &[
+    ("allow_alias", Breaking, |e| opt(&e.allow_alias)),
+    ("closed_enum", Breaking, |e| opt(&e.closed_enum)),
+    ("deprecated", Cosmetic, |e| opt(&e.deprecated)),
+    ("reserved_ranges", Breaking, |e| {
+        ranges(&e.reserved_ranges, false)
+    }),
+    ("reserved_names", Breaking, |e| names(&e.reserved_names)),
+];
+
+const ENUM_VALUE_ATTRIBUTES: &[Attribute<CanonicalEnumValue>] = &[
+    ("number", Breaking, |v| Some(v.number.to_string())),
+    ("deprecated", Cosmetic, |v| opt(&v.deprecated)),
+    ("debug_redact", Cosmetic, |v| opt(&v.debug_redact)),
+];
+
+const METHOD_ATTRIBUTES: &[Attribute<CanonicalMethod>] = &[
+    ("input_type", Breaking, |m| Some(type_name(&m.input_type))),
+    ("output_type", Breaking, |m| Some(type_name(&m.output_type))),
+    ("client_streaming", Breaking, |m| {
+        Some(m.client_streaming.to_string())
+    }),
+    ("server_streaming", Breaking, |m| {
+        Some(m.server_streaming.to_string())
+    }),
+    ("idempotency_level", NonBreaking, |m| {
+        opt(&m.idempotency_level)
+    }),
+    ("deprecated", Cosmetic, |m| opt(&m.deprecated)),
+];
+
+const EXTENSION_ATTRIBUTES: &[Attribute<CanonicalExtension>] = &[
+    ("name", Breaking, |e| Some(e.name.clone())),
+    ("type", Breaking, |e| {
+        Some(display_type(&e.type_name, e.group))
+    }),
+    ("label", Breaking, |e| Some(label(&e.label))),
+    ("default", Breaking, |e| opt(&e.default)),
+    ("deprecated", Cosmetic, |e| opt(&e.deprecated)),
+];
+
+/// Attributes listing reserved numbers or names.
+const RESERVATIONS: &[&str] = &["reserved_ranges", "reserved_names", "extension_ranges"];
+
+/// Field options mirrored by typed members of [`CanonicalField`].
+const MIRRORED_FIELD_OPTIONS: &[&str] = &[
+    "ctype",
+    "jstype",
+    "deprecated",
+    "weak",
+    "default",
+    "json_name",
+];
+
+/// Enum options mirrored by typed members of [`CanonicalEnum`].
+const MIRRORED_ENUM_OPTIONS: &[&str] = &["allow_alias", "deprecated"];
+
+struct Differ {
+    package: String,
+    entries: Vec<DiffEntry>,
+}
+
+impl Differ {
+    fn qualify(&self, path: &str) -> String {
+        if self.package.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}.{path}", self.package)
+        }
+    }
+
+    fn push(&mut self, element: DiffElement, path: &str, change: ChangeKind, class: ChangeClass) {
+        self.entries.push(DiffEntry {
+            element,
+            path: path.to_string(),
+            change,
+            class,
+            attribute: None,
+            old: None,
+            new: None,
+        });
+    }
+
+    fn added(&mut self, element: DiffElement, path: &str, breaking: bool) {
+        let class = if breaking { Breaking } else { NonBreaking };
+        self.push(element, path, ChangeKind::Added, class);
+    }
+
+    fn removed(&mut self, element: DiffElement, path: &str) {
+        self.push(element, path, ChangeKind::Removed, Breaking);
+    }
+
+    /// Records a modified attribute if its value differs.
+    fn attribute(
+        &mut self,
+        element: DiffElement,
+        path: &str,
+        attribute: &str,
+        old: Option<String>,
+        new: Option<String>,
+        class: ChangeClass,
+    ) {
+        if old != new {
+            self.entries.push(DiffEntry {
+                element,
+                path: path.to_string(),
+                change: ChangeKind::Modified,
+                class,
+                attribute: Some(attribute.to_string()),
+                old,
+                new,
+            });
+        }
+    }
+
+    /// Compares every attribute in `table`. Reservations are only breaking
+    /// when one is lifted, since the numbers or names become free for reuse.
+    fn attributes<T>(
+        &mut self,
+        element: DiffElement,
+        path: &str,
+        old: &T,
+        new: &T,
+        table: &[Attribute<T>],
+    ) {
+        for (attribute, class, get) in table {
+            let (old, new) = (get(old), get(new));
+            let class = if RESERVATIONS.contains(attribute) {
+                reservation_class(attribute, &old, &new)
+            } else {
+                *class
+            };
+            self.attribute(element, path, attribute, old, new, class);
+        }
+    }
+
+    /// Diffs option maps key by key, skipping keys mirrored by typed members.
+    fn options(
+        &mut self,
+        element: DiffElement,
+        path: &str,
+        old: &BTreeMap<String, String>,
+        new: &BTreeMap<String, String>,
+        mirrored: &[&str],
+        class: impl Fn(&str) -> ChangeClass,
+    ) {
+        let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+        for key in keys {
+            if !mirrored.contains(&key.as_str()) {
+                let (old, new) = (old.get(key).cloned(), new.get(key).cloned());
+                self.attribute(
+                    element,
+                    path,
+                    &format!("option {key}"),
+                    old,
+                    new,
+                    class(key),
+                );
+            }
+        }
+    }
+
+    /// Diffs two maps of elements keyed by path within the package: removed
+    /// keys, then added or modified keys via `modified`.
+    fn keyed<T>(
+        &mut self,
+        element: DiffElement,
+        old: &BTreeMap<String, T>,
+        new: &BTreeMap<String, T>,
+        mut modified: impl FnMut(&mut Self, &str, &T, &T),
+    ) {
+        for key in old.keys() {
+            if !new.contains_key(key) {
+                let path = self.qualify(key);
+                self.removed(element, &path);
+            }
+        }
+        for (key, new_value) in new {
+            match old.get(key) {
+                Some(old_value) => modified(self, key, old_value, new_value),
+                None => {
+                    let path = self.qualify(key);
+                    self.added(element, &path, false);
+                }
+            }
+        }
+    }
+
+    fn file(&mut self, old: &CanonicalFile, new: &CanonicalFile) {
+        let path = self.package.clone();
+        self.attributes(DiffElement::File, &path, old, new, FILE_ATTRIBUTES);
+    }
+
+    fn message(&mut self, key: &str, old: &CanonicalMessage, new: &CanonicalMessage) {
+        let path = self.qualify(key);
+        self.attributes(DiffElement::Message, &path, old, new, MESSAGE_ATTRIBUTES);
+
+        let oneofs = |message: &CanonicalMessage| -> BTreeMap<String, ()> {
+            message
+                .oneofs
+                .iter()
+                .map(|name| (format!("{key}.{name}"), ()))
+                .collect()
+        };
+        self.keyed(
+            DiffElement::Oneof,
+            &oneofs(old),
+            &oneofs(new),
+            |_, _, _, _| {},
+        );
+
+        let (old_fields, new_fields) = (fields_by_number(old), fields_by_number(new));
+        for (number, old_field) in &old_fields {
+            if !new_fields.contains_key(number) {
+                self.removed(DiffElement::Field, &format!("{path}.{}", old_field.name));
+            }
+        }
+        for (number, new_field) in &new_fields {
+            let field_path = format!("{path}.{}", new_field.name);
+            let Some(old_field) = old_fields.get(number) else {
+                let required = new_field.label.as_deref() == Some("required");
+                self.added(DiffElement::Field, &field_path, required);
+                continue;
+            };
+            self.field(&field_path, (old, old_field), (new, new_field));
+        }
+    }
+
+    fn field(
+        &mut self,
+        path: &str,
+        (old_message, old): (&CanonicalMessage, &CanonicalField),
+        (new_message, new): (&CanonicalMessage, &CanonicalField),
+    ) {
+        let element = DiffElement::Field;
+        self.attributes(element, path, old, new, FIELD_ATTRIBUTES);
+        let (old_oneof, new_oneof) = (oneof(old_message, old), oneof(new_message, new));
+        self.attribute(element, path, "oneof", old_oneof, new_oneof, Breaking);
+        self.options(
+            element,
+            path,
+            &old.options,
+            &new.options,
+            MIRRORED_FIELD_OPTIONS,
+            |key| {
+                if key == "packed" {
+                    NonBreaking
+                } else {
+                    Cosmetic
+                }
+            },
+        );
+    }
+
+    fn enumeration(&mut self, key: &str, old: &CanonicalEnum, new: &CanonicalEnum) {
+        let path = self.qualify(key);
+        self.attributes(DiffElement::Enum, &path, old, new, ENUM_ATTRIBUTES);
+        self.options(
+            DiffElement::Enum,
+            &path,
+            &old.options,
+            &new.options,
+            MIRRORED_ENUM_OPTIONS,
+            |key| {
+                if key == "json_format" {
+                    Breaking
+                } else {
+                    Cosmetic
+                }
+            },
+        );
+
+        let element = DiffElement::EnumValue;
+        // Keyed by name: under allow_alias several values share a number
+        let (old_values, new_values) = (values_by_name(old), values_by_name(new));
+        for name in old_values.keys() {
+            if !new_values.contains_key(name) {
+                self.removed(element, &format!("{path}.{name}"));
+            }
+        }
+        for (name, new_value) in &new_values {
+            let value_path = format!("{path}.{name}");
+            let Some(old_value) = old_values.get(name) else {
+                self.added(element, &value_path, false);
+                continue;
+            };
+            self.attributes(
+                element,
+                &value_path,
+                *old_value,
+                *new_value,
+                ENUM_VALUE_ATTRIBUTES,
+            );
+            self.options(
+                element,
+                &value_path,
+                &old_value.options,
+                &new_value.options,
+                &[],
+                |_| Cosmetic,
+            );
+        }
+    }
+
+    fn method(&mut self, key: &str, old: &CanonicalMethod, new: &CanonicalMethod) {
+        let path = self.qualify(key);
+        self.attributes(DiffElement::Method, &path, old, new, METHOD_ATTRIBUTES);
+    }
+
+    fn extension(&mut self, path: &str, old: &CanonicalExtension, new: &CanonicalExtension) {
+        self.attributes(DiffElement::Extension, path, old, new, EXTENSION_ATTRIBUTES);
+    }
+}
+
+fn methods_by_path<'a>(
+    service_path: &str,
+    service: &'a CanonicalService,
+) -> BTreeMap<String, &'a CanonicalMethod> {
+    service
+        .methods
+        .iter()
+        .map(|method| (format!("{service_path}.{}", method.name), method))
+        .collect()
+}
+
+fn extensions_by_key(file: &CanonicalFile) -> BTreeMap<(String, i32), &CanonicalExtension> {
+    file.extensions
+        .iter()
+        .map(|extension| ((extension.extendee.clone(), extension.number), extension))
+        .collect()
+}
+
+fn fields_by_number(message: &CanonicalMessage) -> BTreeMap<i32, &CanonicalField> {
+    message
+        .fields
+        .iter()
+        .map(|field| (field.number, field))
+        .collect()
+}
+
+fn values_by_name(enumeration: &CanonicalEnum) -> BTreeMap<&str, &CanonicalEnumValue> {
+    enumeration
+        .values
+        .iter()
+        .map(|value| (value.name.as_str(), value))
+        .collect()
+}
+
+fn oneof(message: &CanonicalMessage, field: &CanonicalField) -> Option<String> {
+    let index = usize::try_from(field.oneof_index?).ok()?;
+    message.oneofs.get(index).cloned()
+}
+
+/// Lifting a reservation is breaking; adding or widening one is not.
+fn reservation_class(attribute: &str, old: &Option<String>, new: &Option<String>) -> ChangeClass {
+    let items = |list: &Option<String>| -> Vec<String> {
+        list.iter()
+            .flat_map(|list| list.split(", ").map(str::to_string))
+            .collect()
+    };
+    let kept = if attribute == "reserved_names" {
+        let new: BTreeSet<String> = items(new).into_iter().collect();
+        items(old).iter().all(|name| new.contains(name))
+    } else {
+        let new = merged(items(new).iter().filter_map(|item| parse_range(item)));
+        items(old)
+            .iter()
+            .filter_map(|item| parse_range(item))
+            .all(|(start, end)| new.iter().any(|&(s, e)| s <= start && end <= e))
+    };
+    if kept { NonBreaking } else { Breaking }
+}
+
+/// Parses a range as formatted by [`ranges`] into inclusive bounds.
+fn parse_range(item: &str) -> Option<(i64, i64)> {
+    let (start, end) = item.split_once(" to ").unwrap_or((item, item));
+    let end = match end {
+        "max" => i64::from(i32::MAX),
+        end => end.parse().ok()?,
+    };
+    Some((start.parse().ok()?, end))
+}
+
+/// Sorts ranges and joins overlapping or adjacent ones.
+fn merged(ranges: impl Iterator<Item = (i64, i64)>) -> Vec<(i64, i64)> {
+    let mut ranges: Vec<(i64, i64)> = ranges.collect();
+    ranges.sort_unstable();
+    let mut merged: Vec<(i64, i64)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Formats ranges as written in `.proto` files. Message ranges are stored
+/// with an exclusive end, enum ranges with an inclusive one.
+fn ranges(ranges: &BTreeSet<ReservedRange>, exclusive_end: bool) -> Option<String> {
+    join(ranges.iter().map(|r| {
+        let end = if exclusive_end { r.end - 1 } else { r.end };
+        match end {
+            end if end == r.start => end.to_string(),
+            // 536870911 is the largest field number, written as `max`.
+            536_870_911 | i32::MAX => format!("{} to max", r.start),
+            end => format!("{} to {end}", r.start),
+        }
+    }))
+}
+
+fn names(names: &BTreeSet<ReservedName>) -> Option<String> {
+    join(names.iter().map(|n| n.name.clone()))
+}
+
+fn join(items: impl Iterator<Item = String>) -> Option<String> {
+    let items: Vec<String> = items.collect();
+    (!items.is_empty()).then(|| items.join(", "))
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> Option<String> {
+    value.as_ref().map(ToString::to_string)
+}
+
+fn label(label: &Option<String>) -> String {
+    label.clone().unwrap_or_else(|| "optional".to_string())
+}
+
+/// Like [`CanonicalField::display_type`], without the leading dot.
+fn display_type(type_name: &str, group: bool) -> String {
+    let type_name = self::type_name(type_name);
+    if group {
+        format!("group {type_name}")
+    } else {
+        type_name
+    }
+}
+
+fn type_name(type_name: &str) -> String {
+    type_name.trim_start_matches('.').to_string()
+}
@@ -0,0 +1,862 @@
+//! Versioned semantic fingerprints.
+//!
+//! A fingerprint names the canonical encoding and hash it was computed with,
+//! e.g. `ps1-sha256:3f2a…`, so stored fingerprints stay comparable when the
+//! canonical model grows. The `ps1` encoding is specified in
+//! `docs/fingerprint-format.md`.
+//!
+//! The profile, hash and digest encoding can be chosen with
+//! [`FingerprintOptions`]; all three are recorded in the prefix (e.g.
+//! `ps1.api-blake3-b64u:…`), so fingerprints of different kinds never compare
+//! equal by accident.
+
+use crate::canonical::{
+    CanonicalEnum, CanonicalEnumValue, CanonicalExtension, CanonicalFile, CanonicalMessage,
+    CanonicalMethod, CanonicalService, qualify,
+};
+use crate::profile::FingerprintProfile;
+use anyhow::Context;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// A fingerprint format version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FingerprintVersion {
+    /// Unprefixed SHA-256 hex of the pretty-printed serde JSON model, as
+    /// produced before fingerprints were versioned, without the model members
+    /// added since. Only kept for migration.
+    Legacy,
+    /// SHA-256 over the `ps1` canonical encoding, prefixed `ps1-sha256:`.
+    Ps1,
+}
+
+impl FingerprintVersion {
+    /// The version used by [`generate_fingerprint`](crate::generate_fingerprint).
+    pub const CURRENT: FingerprintVersion = FingerprintVersion::Ps1;
+
+    /// Determines which version produced a stored fingerprint.
+    pub fn detect(fingerprint: &str) -> Option<Self> {
+        if fingerprint.starts_with("ps1") {
+            return FingerprintOptions::detect(fingerprint).map(|_| FingerprintVersion::Ps1);
+        }
+        is_sha256_hex(fingerprint).then_some(FingerprintVersion::Legacy)
+    }
+}
+
+impl fmt::Display for FingerprintVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FingerprintVersion::Legacy => write!(f, "legacy"),
+            FingerprintVersion::Ps1 => write!(f, "ps1"),
+        }
+    }
+}
+
+impl FromStr for FingerprintVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "legacy" => Ok(FingerprintVersion::Legacy),
+            "ps1" => Ok(FingerprintVersion::Ps1),
+            other => {
+                anyhow::bail!("unknown fingerprint version '{other}' (expected ps1 or legacy)")
+            }
+        }
+    }
+}
+
+/// Computes the fingerprint of `canonical_file` in the given version.
+pub fn fingerprint_with_version(
+    canonical_file: &CanonicalFile,
+    version: FingerprintVersion,
+) -> anyhow::Result<String> {
+    match version {
+        FingerprintVersion::Legacy => {
+            let json_string =
+                serde_json::to_string_pretty(&legacy_projection(canonical_file.clone()))
+                    .context("Failed to serialize canonical representation to JSON")?;
+            Ok(sha256_hex(json_string.as_bytes()))
+        }
+        FingerprintVersion::Ps1 => ps1_fingerprint(canonical_file),
+    }
+}
+
+/// The model as it was when legacy fingerprints were computed.
+///
+/// Members added to the canonical model since then are reset to their
+/// defaults, which serde skips, so the JSON matches the frozen legacy layout:
+/// enum value `deprecated`, `debug_redact` and custom options, and the `group`
+/// flag of fields and extensions.
+fn legacy_projection(mut file: CanonicalFile) -> CanonicalFile {
+    file.messages = file.messages.into_iter().map(legacy_message).collect();
+    file.enums = file.enums.into_iter().map(legacy_enum).collect();
+    file.extensions = file
+        .extensions
+        .into_iter()
+        .map(|mut e| {
+            e.group = false;
+            e
+        })
+        .collect();
+    file
+}
+
+fn legacy_message(mut message: CanonicalMessage) -> CanonicalMessage {
+    message.fields = message
+        .fields
+        .into_iter()
+        .map(|mut f| {
+            f.group = false;
+            f
+        })
+        .collect();
+    message.nested_messages = message
+        .nested_messages
+        .into_iter()
+        .map(legacy_message)
+        .collect();
+    message.nested_enums = message.nested_enums.into_iter().map(legacy_enum).collect();
+    message
+}
+
+fn legacy_enum(mut enumeration: CanonicalEnum) -> CanonicalEnum {
+    enumeration.values = enumeration
+        .values
+        .into_iter()
+        .map(|v| CanonicalEnumValue {
+            name: v.name,
+            number: v.number,
+            ..Default::default()
+        })
+        .collect();
+    enumeration
+}
+
+/// Returns true if `stored` is the fingerprint of `canonical_file` in
+/// whichever version, algorithm and encoding `stored` was computed with.
+pub fn matches_fingerprint(canonical_file: &CanonicalFile, stored: &str) -> anyhow::Result<bool> {
+    if let Some(options) = FingerprintOptions::detect(stored) {
+        return Ok(fingerprint_with_options(canonical_file, &options)? == stored);
+    }
+    let version = FingerprintVersion::detect(stored)
+        .with_context(|| format!("'{stored}' is not a recognized fingerprint"))?;
+    Ok(fingerprint_with_version(canonical_file, version)? == stored)
+}
+
+/// Like [`matches_fingerprint`], but only for collision-resistant
+/// fingerprints, as needed by signatures and lockfiles.
+///
+/// Fails if `stored` is an `xxh3` or legacy fingerprint, which an adversary
+/// could match with a different file.
+pub fn matches_cryptographic_fingerprint(
+    canonical_file: &CanonicalFile,
+    stored: &str,
+) -> anyhow::Result<bool> {
+    ensure_cryptographic(stored)?;
+    matches_fingerprint(canonical_file, stored)
+}
+
+/// Fails unless `fingerprint` is a `ps1` fingerprint over a cryptographic hash.
+pub fn ensure_cryptographic(fingerprint: &str) -> anyhow::Result<()> {
+    match FingerprintOptions::detect(fingerprint) {
+        Some(options) if options.algorithm.is_cryptographic() => Ok(()),
+        Some(options) => anyhow::bail!(
+            "'{fingerprint}' uses {}, which is not collision resistant",
+            options.algorithm
+        ),
+        None if FingerprintVersion::detect(fingerprint).is_some() => anyhow::bail!(
+            "'{fingerprint}' is a legacy fingerprint; recompute it as ps1 before signing or locking"
+        ),
+        None => anyhow::bail!("'{fingerprint}' is not a recognized fingerprint"),
+    }
+}
+
+/// The hash applied to the `ps1` encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+    Blake3,
+    /// 128-bit XXH3. Much faster, but not collision resistant against an
+    /// adversary; meant for caches, not for signing or locking.
+    Xxh3,
+}
+
+impl HashAlgorithm {
+    /// Every supported algorithm.
+    pub const ALL: [HashAlgorithm; 4] = [
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha512,
+        HashAlgorithm::Blake3,
+        HashAlgorithm::Xxh3,
+    ];
+
+    /// The name used in fingerprint prefixes and on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Xxh3 => "xxh3",
+        }
+    }
+
+    /// Whether the hash is collision resistant, and so fit for signatures
+    /// and lockfiles.
+    pub fn is_cryptographic(self) -> bool {
+        self != HashAlgorithm::Xxh3
+    }
+
+    /// The digest length in bytes.
+    pub fn digest_len(self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 | HashAlgorithm::Blake3 => 32,
+            HashAlgorithm::Sha512 => 64,
+            HashAlgorithm::Xxh3 => 16,
+        }
+    }
+
+    /// The multicodec code identifying the algorithm in a multihash.
+    pub fn multihash_code(self) -> u64 {
+        match self {
+            HashAlgorithm::Sha256 => 0x12,
+            HashAlgorithm::Sha512 => 0x13,
+            HashAlgorithm::Blake3 => 0x1e,
+            HashAlgorithm::Xxh3 => 0xb3e4,
+        }
+    }
+
+    fn digest(self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha256 => Sha256::digest(bytes).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(bytes).to_vec(),
+            HashAlgorithm::Blake3 => blake3::hash(bytes).as_bytes().to_vec(),
+            HashAlgorithm::Xxh3 => xxhash_rust::xxh3::xxh3_128(bytes).to_be_bytes().to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HashAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == s)
+            .with_context(|| {
+                format!("unknown hash algorithm '{s}' (expected sha256, sha512, blake3 or xxh3)")
+            })
+    }
+}
+
+/// How the digest is written after the prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DigestEncoding {
+    /// Lowercase hex.
+    #[default]
+    Hex,
+    /// Unpadded base64url.
+    Base64Url,
+    /// Lowercase hex of a multihash: the algorithm's multicodec code and the
+    /// digest length as unsigned varints, followed by the digest.
+    Multihash,
+}
+
+impl DigestEncoding {
+    /// Every supported encoding.
+    pub const ALL: [DigestEncoding; 3] = [
+        DigestEncoding::Hex,
+        DigestEncoding::Base64Url,
+        DigestEncoding::Multihash,
+    ];
+
+    /// The name used on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            DigestEncoding::Hex => "hex",
+            DigestEncoding::Base64Url => "base64url",
+            DigestEncoding::Multihash => "multihash",
+        }
+    }
+
+    /// The suffix added to the prefix. Hex has none, which keeps default
+    /// fingerprints in the original `ps1-sha256:` form.
+    fn suffix(self) -> Option<&'static str> {
+        match self {
+            DigestEncoding::Hex => None,
+            DigestEncoding::Base64Url => Some("b64u"),
+            DigestEncoding::Multihash => Some("mh"),
+        }
+    }
+}
+
+impl fmt::Display for DigestEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DigestEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DigestEncoding::ALL
+            .into_iter()
+            .find(|encoding| encoding.name() == s)
+            .with_context(|| {
+                format!("unknown digest encoding '{s}' (expected hex, base64url or multihash)")
+            })
+    }
+}
+
+/// The profile, hash and digest encoding of a `ps1` fingerprint.
+///
+/// The default, the exact profile hashed with SHA-256 in hex, produces the
+/// same fingerprints as [`generate_fingerprint`](crate::generate_fingerprint).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FingerprintOptions {
+    pub profile: FingerprintProfile,
+    pub algorithm: HashAlgorithm,
+    pub encoding: DigestEncoding,
+}
+
+impl FingerprintOptions {
+    pub fn new(algorithm: HashAlgorithm, encoding: DigestEncoding) -> Self {
+        Self {
+            profile: FingerprintProfile::default(),
+            algorithm,
+            encoding,
+        }
+    }
+
+    /// Returns these options with `profile` selected.
+    pub fn with_profile(mut self, profile: FingerprintProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// The prefix written before the `:`, e.g. `ps1-sha512`,
+    /// `ps1.api-sha256` or `ps1-blake3-b64u`.
+    pub fn prefix(&self) -> String {
+        let encoding = match self.profile {
+            FingerprintProfile::Exact => "ps1".to_string(),
+            profile => format!("ps1.{profile}"),
+        };
+        match self.encoding.suffix() {
+            Some(suffix) => format!("{encoding}-{}-{suffix}", self.algorithm),
+            None => format!("{encoding}-{}", self.algorithm),
+        }
+    }
+
+    /// Determines the options a stored `ps1` fingerprint was computed with.
+    /// Returns `None` unless the prefix is known and the digest is well formed
+    /// for it.
+    pub fn detect(fingerprint: &str) -> Option<Self> {
+        let (prefix, digest) = fingerprint.split_once(':')?;
+        let mut parts = prefix.split('-');
+        let profile = match parts.next()?.strip_prefix("ps1")? {
+            "" => FingerprintProfile::Exact,
+            profile => match profile.strip_prefix('.')?.parse().ok()? {
+                FingerprintProfile::Exact => return None,
+                profile => profile,
+            },
+        };
+        let algorithm = parts.next()?.parse::<HashAlgorithm>().ok()?;
+        let encoding = match parts.next() {
+            None => DigestEncoding::Hex,
+            Some(suffix) => *DigestEncoding::ALL
+                .iter()
+                .find(|encoding| encoding.suffix() == Some(suffix))?,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        let options = Self::new(algorithm, encoding).with_profile(profile);
+        (options.decode(digest)?.len() == algorithm.digest_len()).then_some(options)
+    }
+
+    /// Hashes `bytes` and returns the complete fingerprint.
+    pub(crate) fn fingerprint_bytes(&self, bytes: &[u8]) -> String {
+        let digest = self.algorithm.digest(bytes);
+        let encoded = match self.encoding {
+            DigestEncoding::Hex => to_hex(&digest),
+            DigestEncoding::Base64Url => BASE64URL.encode(&digest),
+            DigestEncoding::Multihash => {
+                let mut multihash = Vec::with_capacity(digest.len() + 4);
+                write_varint(self.algorithm.multihash_code(), &mut multihash);
+                write_varint(digest.len() as u64, &mut multihash);
+                multihash.extend_from_slice(&digest);
+                to_hex(&multihash)
+            }
+        };
+        format!("{}:{encoded}", self.prefix())
+    }
+
+    /// Recovers the raw digest from its encoded form.
+    fn decode(&self, digest: &str) -> Option<Vec<u8>> {
+        match self.encoding {
+            DigestEncoding::Hex => from_hex(digest),
+            DigestEncoding::Base64Url => BASE64URL.decode(digest).ok(),
+            DigestEncoding::Multihash => {
+                let bytes = from_hex(digest)?;
+                let mut rest = bytes.as_slice();
+                if read_varint(&mut rest)? != self.algorithm.multihash_code() {
+                    return None;
+                }
+                let len = read_varint(&mut rest)?;
+                (len == rest.len() as u64).then(|| rest.to_vec())
+            }
+        }
+    }
+}
+
+/// Computes the `ps1` fingerprint of `canonical_file` with the given profile,
+/// hash and digest encoding.
+pub fn fingerprint_with_options(
+    canonical_file: &CanonicalFile,
+    options: &FingerprintOptions,
+) -> anyhow::Result<String> {
+    let encoded = encode_value(&options.profile.apply(canonical_file))?;
+    Ok(options.fingerprint_bytes(encoded.as_bytes()))
+}
+
+/// The kind of definition an [`ElementFingerprint`] belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElementKind {
+    Message,
+    Enum,
+    Service,
+    Method,
+}
+
+impl fmt::Display for ElementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElementKind::Message => write!(f, "message"),
+            ElementKind::Enum => write!(f, "enum"),
+            ElementKind::Service => write!(f, "service"),
+            ElementKind::Method => write!(f, "method"),
+        }
+    }
+}
+
+/// The fingerprint of a single definition within a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ElementFingerprint {
+    /// Fully-qualified name without a leading dot, e.g. `acme.v1.Order.Item`
+    /// or `acme.v1.OrderService.Get`.
+    pub name: String,
+    pub kind: ElementKind,
+    pub fingerprint: String,
+}
+
+/// Computes a `ps1` fingerprint for every message, enum, service and method
+/// in `canonical_file`, including nested definitions, sorted by name.
+///
+/// A message's fingerprint covers its own fields, options and reserved
+/// ranges but not its nested messages and enums, which get entries of their
+/// own. A service's fingerprint covers all of its methods. Type references
+/// are fully qualified, so the fingerprint of a message changes when a field
+/// is retargeted but not when the referenced type itself changes.
+pub fn element_fingerprints(
+    canonical_file: &CanonicalFile,
+) -> anyhow::Result<Vec<ElementFingerprint>> {
+    let package = canonical_file.package.as_deref().unwrap_or_default();
+    let mut elements = Vec::new();
+
+    for message in &canonical_file.messages {
+        collect_message(message, package, &mut elements)?;
+    }
+    for en in &canonical_file.enums {
+        elements.push(element(qualify(package, &en.name), ElementKind::Enum, en)?);
+    }
+    for service in &canonical_file.services {
+        let service_name = qualify(package, &service.name);
+        for method in &service.methods {
+            elements.push(element(
+                qualify(&service_name, &method.name),
+                ElementKind::Method,
+                method,
+            )?);
+        }
+        elements.push(element(service_name, ElementKind::Service, service)?);
+    }
+
+    elements.sort_by(|a, b| a.name.cmp(&b.name).then(a.kind.cmp(&b.kind)));
+    Ok(elements)
+}
+
+fn collect_message(
+    message: &CanonicalMessage,
+    scope: &str,
+    elements: &mut Vec<ElementFingerprint>,
+) -> anyhow::Result<()> {
+    let name = qualify(scope, &message.name);
+    for nested in &message.nested_messages {
+        collect_message(nested, &name, elements)?;
+    }
+    for en in &message.nested_enums {
+        elements.push(element(qualify(&name, &en.name), ElementKind::Enum, en)?);
+    }
+
+    elements.push(element(
+        name,
+        ElementKind::Message,
+        &without_nested(message),
+    )?);
+    Ok(())
+}
+
+fn element<T: Serialize>(
+    name: String,
+    kind: ElementKind,
+    value: &T,
+) -> anyhow::Result<ElementFingerprint> {
+    Ok(ElementFingerprint {
+        name,
+        kind,
+        fingerprint: ps1_fingerprint(value)?,
+    })
+}
+
+/// The fingerprint of a definition together with every type it reaches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClosureFingerprint {
+    /// The definition the closure starts from.
+    pub root: String,
+    pub fingerprint: String,
+    /// Fully-qualified names of all definitions and extensions in the
+    /// closure, including the root, sorted.
+    pub members: Vec<String>,
+}
+
+/// Computes a `ps1` fingerprint over the definition `root` and everything
+/// reachable from it across `files`.
+///
+/// `root` is the fully-qualified name of a message, enum, service or method
+/// (`<service>.<method>`), with or without a leading dot. The closure follows
+/// field types, method input and output types, and extensions of any message
+/// in the closure, including the types of those extensions. `files` must
+/// define every type that is reached, e.g. the result of
+/// [`canonical_files_with_imports`](crate::canonical_files_with_imports).
+///
+/// The fingerprint changes when any member of the closure changes and is
+/// unaffected by definitions outside it, so two peers can compare exactly the
+/// surface they share.
+pub fn closure_fingerprint(
+    files: &[CanonicalFile],
+    root: &str,
+) -> anyhow::Result<ClosureFingerprint> {
+    let root = root.trim_start_matches('.');
+    let mut definitions = BTreeMap::new();
+    let mut extensions: BTreeMap<String, Vec<(String, &CanonicalExtension)>> = BTreeMap::new();
+    for file in files {
+        let package = file.package.as_deref().unwrap_or_default();
+        for message in &file.messages {
+            index_message(message, package, &mut definitions);
+        }
+        for en in &file.enums {
+            definitions.insert(qualify(package, &en.name), Definition::Enum(en));
+        }
+        for service in &file.services {
+            let service_name = qualify(package, &service.name);
+            for method in &service.methods {
+                definitions.insert(
+                    qualify(&service_name, &method.name),
+                    Definition::Method(method),
+                );
+            }
+            definitions.insert(service_name, Definition::Service(service));
+        }
+        for extension in &file.extensions {
+            extensions
+                .entry(extension.extendee.trim_start_matches('.').to_string())
+                .or_default()
+                .push((qualify(package, &extension.name), extension));
+        }
+    }
+
+    if !definitions.contains_key(root) {
+        anyhow::bail!("'{root}' is not defined in the given files");
+    }
+
+    let mut reached = BTreeMap::new();
+    let mut reached_extensions = BTreeMap::new();
+    let mut pending = vec![(root.to_string(), root.to_string())];
+    while let Some((name, referrer)) = pending.pop() {
+        if reached.contains_key(&name) {
+            continue;
+        }
+        let definition = definitions
+            .get(&name)
+            .with_context(|| format!("'{name}' referenced by '{referrer}' is not defined"))?;
+
+        for reference in definition.references() {
+            pending.push((reference.to_string(), name.clone()));
+        }
+        if let Definition::Message(_) = definition {
+            for (extension_name, extension) in extensions.get(&name).into_iter().flatten() {
+                if let Some(type_name) = extension.type_name.strip_prefix('.') {
+                    pending.push((type_name.to_string(), extension_name.clone()));
+                }
+                reached_extensions.insert(extension_name.clone(), *extension);
+            }
+        }
+        reached.insert(name, definition);
+    }
+
+    let mut closure = BTreeMap::<&str, serde_json::Map<String, Value>>::new();
+    for (name, definition) in &reached {
+        let (group, value) = match definition {
+            Definition::Message(message) => ("messages", serde_json::to_value(message)),
+            Definition::Enum(en) => ("enums", serde_json::to_value(en)),
+            Definition::Service(service) => ("services", serde_json::to_value(service)),
+            Definition::Method(method) => ("methods", serde_json::to_value(method)),
+        };
+        let value = value.context("Failed to convert canonical representation to JSON")?;
+        closure
+            .entry(group)
+            .or_default()
+            .insert(name.clone(), value);
+    }
+    for (name, extension) in &reached_extensions {
+        let value = serde_json::to_value(extension)
+            .context("Failed to convert canonical representation to JSON")?;
+        closure
+            .entry("extensions")
+            .or_default()
+            .insert(name.clone(), value);
+    }
+
+    let mut encoded = serde_json::Map::new();
+    encoded.insert("root".to_string(), Value::String(root.to_string()));
+    for (group, members) in closure {
+        encoded.insert(group.to_string(), Value::Object(members));
+    }
+    let fingerprint = ps1_fingerprint(&encoded)?;
+
+    let members: BTreeSet<String> = reached
+        .into_keys()
+        .chain(reached_extensions.into_keys())
+        .collect();
+    Ok(ClosureFingerprint {
+        root: root.to_string(),
+        fingerprint,
+        members: members.into_iter().collect(),
+    })
+}
+
+/// A definition that can be part of a closure.
+enum Definition<'a> {
+    /// A message without its nested definitions, which are indexed on
+    /// their own.
+    Message(CanonicalMessage),
+    Enum(&'a CanonicalEnum),
+    Service(&'a CanonicalService),
+    Method(&'a CanonicalMethod),
+}
+
+impl Definition<'_> {
+    /// Fully-qualified names of the types this definition refers to.
+    fn references(&self) -> Vec<&str> {
+        match self {
+            Definition::Message(message) => message
+                .fields
+                .iter()
+                .filter_map(|field| field.type_name.strip_prefix('.'))
+                .collect(),
+            Definition::Enum(_) => Vec::new(),
+            Definition::Service(service) => {
+                service.methods.iter().flat_map(method_references).collect()
+            }
+            Definition::Method(method) => method_references(method).collect(),
+        }
+    }
+}
+
+fn method_references(method: &CanonicalMethod) -> impl Iterator<Item = &str> {
+    [&method.input_type, &method.output_type]
+        .into_iter()
+        .map(|type_name| type_name.trim_start_matches('.'))
+}
+
+fn index_message<'a>(
+    message: &'a CanonicalMessage,
+    scope: &str,
+    definitions: &mut BTreeMap<String, Definition<'a>>,
+) {
+    let name = qualify(scope, &message.name);
+    for nested in &message.nested_messages {
+        index_message(nested, &name, definitions);
+    }
+    for en in &message.nested_enums {
+        definitions.insert(qualify(&name, &en.name), Definition::Enum(en));
+    }
+    definitions.insert(name, Definition::Message(without_nested(message)));
+}
+
+/// Returns `message` without its nested messages and enums.
+fn without_nested(message: &CanonicalMessage) -> CanonicalMessage {
+    CanonicalMessage {
+        nested_messages: Default::default(),
+        nested_enums: Default::default(),
+        ..message.clone()
+    }
+}
+
+/// Encodes `canonical_file` in the `ps1` canonical form.
+///
+/// The model is converted to the JSON data model, members that are `null`,
+/// empty arrays or empty objects are dropped, and the result is written with
+/// object keys sorted by their UTF-8 bytes and no insignificant whitespace.
+pub fn encode_canonical(canonical_file: &CanonicalFile) -> anyhow::Result<String> {
+    encode_value(canonical_file)
+}
+
+/// Hashes `value` with the `ps1` rules, returning a `ps1-sha256:` fingerprint.
+pub(crate) fn ps1_fingerprint<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    Ok(format!("ps1-sha256:{}", ps1_digest(value)?))
+}
+
+/// The SHA-256 hex digest of the `ps1` encoding of `value`.
+pub(crate) fn ps1_digest<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    let encoded = encode_value(value)?;
+    Ok(sha256_hex(encoded.as_bytes()))
+}
+
+/// Encodes any part of the canonical model with the `ps1` rules.
+pub(crate) fn encode_value<T: Serialize>(element: &T) -> anyhow::Result<String> {
+    let value = serde_json::to_value(element)
+        .context("Failed to convert canonical representation to JSON")?;
+    let mut out = String::new();
+    if let Some(value) = prune(value) {
+        write_value(&value, &mut out);
+    }
+    Ok(out)
+}
+
+/// Drops members that carry no information, so that adding an optional
+/// member to the model does not change existing encodings.
+fn prune(value: Value) -> Option<Value> {
+    match value {
+        Value::Null => None,
+        Value::Array(items) => {
+            let items: Vec<Value> = items.into_iter().filter_map(prune).collect();
+            (!items.is_empty()).then_some(Value::Array(items))
+        }
+        Value::Object(members) => {
+            let members: serde_json::Map<String, Value> = members
+                .into_iter()
+                .filter_map(|(k, v)| prune(v).map(|v| (k, v)))
+                .collect();
+            (!members.is_empty()).then_some(Value::Object(members))
+        }
+        other => Some(other),
+    }
+}
+
+fn write_value(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&n.to_string()),
+        Value::String(s) => write_string(s, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(members) => {
+            let mut keys: Vec<&String> = members.keys().collect();
+            keys.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(key, out);
+                out.push(':');
+                write_value(&members[key], out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// Writes a JSON string, escaping only `"`, `\` and control characters.
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Decodes lowercase hex, the only form [`to_hex`] produces.
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn is_sha256_hex(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
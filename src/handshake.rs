@@ -0,0 +1,273 @@
+//! Schema handshakes between peers.
+//!
+//! Each peer advertises a [`HandshakeManifest`] listing the services it
+//! speaks, with the closure fingerprint of each service and, optionally, the
+//! canonical definitions of the service and everything it reaches. Comparing
+//! the local manifest with a peer's gives a [`Compatibility`] per service, as
+//! in [`Spec::compare_with`], so a peer can decide at connect time whether to
+//! talk:
+//!
+//! - `Green`: the closure fingerprints are equal.
+//! - `Yellow`: the fingerprints differ, but the
+//!   [`BreakingEngine`](crate::compat::BreakingEngine) reports no breaking
+//!   change from the local shape to the peer's.
+//! - `Red`: anything else, including a service only one side has, or a
+//!   differing fingerprint without shapes to compare.
+
+use crate::canonical::{CanonicalFile, qualify};
+use crate::compat::{BreakingConfig, BreakingEngine, BreakingResult};
+use crate::fingerprint::closure_fingerprint;
+use crate::spec::{Compatibility, Spec};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Version of the [`HandshakeManifest`] format.
+pub const HANDSHAKE_MANIFEST_VERSION: u32 = 1;
+
+/// The services a peer speaks, as advertised to other peers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandshakeManifest {
+    pub version: u32,
+    /// Services keyed by fully-qualified name without a leading dot.
+    pub services: BTreeMap<String, ServiceManifest>,
+}
+
+/// The advertised state of a single service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceManifest {
+    /// The closure fingerprint of the service.
+    pub fingerprint: String,
+    /// The members of the service's closure, one file per package. Nested
+    /// messages and enums are listed at the top level under their dotted
+    /// path, e.g. `Order.Item`. Without it, a differing fingerprint is always
+    /// `Red`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<Vec<CanonicalFile>>,
+}
+
+/// The outcome of a handshake.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandshakeResult {
+    /// Every service either side advertises.
+    pub services: BTreeMap<String, Compatibility>,
+}
+
+impl HandshakeManifest {
+    /// Advertises the services of a self-contained `spec`. Use
+    /// [`HandshakeManifest::from_files`] if the services reach imported types.
+    pub fn from_spec(spec: &Spec) -> anyhow::Result<Self> {
+        Self::from_files(std::slice::from_ref(&spec.canonical_file))
+    }
+
+    /// Advertises the services defined in `files[0]`. The remaining files
+    /// must define every type the services reach, e.g. the result of
+    /// [`canonical_files_with_imports`](crate::canonical_files_with_imports).
+    pub fn from_files(files: &[CanonicalFile]) -> anyhow::Result<Self> {
+        let mut services = BTreeMap::new();
+        let Some(file) = files.first() else {
+            return Ok(Self::new(services));
+        };
+        let package = file.package.as_deref().unwrap_or_default();
+        for service in &file.services {
+            let name = qualify(package, &service.name);
+            let closure = closure_fingerprint(files, &name)?;
+            let members: BTreeSet<&str> = closure.members.iter().map(String::as_str).collect();
+
+            services.insert(
+                name,
+                ServiceManifest {
+                    fingerprint: closure.fingerprint,
+                    shape: Some(closure_shape(files, &members)),
+                },
+            );
+        }
+        Ok(Self::new(services))
+    }
+
+    /// Advertises precomputed `(service, fingerprint)` pairs, such as the
+    /// `SERVICE_FINGERPRINTS` table generated by [`crate::build`]. These
+    /// carry no shapes, so only equality can be established.
+    pub fn from_fingerprints<'a>(
+        fingerprints: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        Self::new(
+            fingerprints
+                .into_iter()
+                .map(|(service, fingerprint)| {
+                    (
+                        service.trim_start_matches('.').to_string(),
+                        ServiceManifest {
+                            fingerprint: fingerprint.to_string(),
+                            shape: None,
+                        },
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn new(services: BTreeMap<String, ServiceManifest>) -> Self {
+        Self {
+            version: HANDSHAKE_MANIFEST_VERSION,
+            services,
+        }
+    }
+
+    /// Compares this (local) manifest with a peer's, per service, under the
+    /// default rules of [`Spec::compare_with`].
+    pub fn compare_with(&self, peer: &HandshakeManifest) -> anyhow::Result<HandshakeResult> {
+        self.compare_with_config(peer, &BreakingConfig::default())
+    }
+
+    /// Compares this (local) manifest with a peer's, per service, under the
+    /// rules selected by `config`.
+    ///
+    /// As in [`Spec::compare_with_config`], the local manifest plays the
+    /// previous version and the peer's the new one, so a peer that added a
+    /// field is `Yellow` and a peer that removed one is `Red`. Use
+    /// [`Direction::Full`](crate::compat::Direction::Full) to also require
+    /// that the local peer can read the other's data the other way round.
+    pub fn compare_with_config(
+        &self,
+        peer: &HandshakeManifest,
+        config: &BreakingConfig,
+    ) -> anyhow::Result<HandshakeResult> {
+        if peer.version != HANDSHAKE_MANIFEST_VERSION {
+            anyhow::bail!(
+                "unsupported handshake manifest version {} (expected {HANDSHAKE_MANIFEST_VERSION})",
+                peer.version
+            );
+        }
+        let mut services = BTreeMap::new();
+        for (name, local) in &self.services {
+            let compatibility = match peer.services.get(name) {
+                Some(remote) => compare_service(local, remote, config),
+                None => Compatibility::Red,
+            };
+            services.insert(name.clone(), compatibility);
+        }
+        for name in peer.services.keys() {
+            services.entry(name.clone()).or_insert(Compatibility::Red);
+        }
+        Ok(HandshakeResult { services })
+    }
+}
+
+impl HandshakeResult {
+    /// The result for `service`, or `None` if neither side advertises it.
+    pub fn service(&self, service: &str) -> Option<Compatibility> {
+        self.services.get(service.trim_start_matches('.')).copied()
+    }
+
+    /// The worst result over all services; `Green` if there are none.
+    pub fn overall(&self) -> Compatibility {
+        self.services
+            .values()
+            .fold(Compatibility::Green, |worst, &c| match (worst, c) {
+                (Compatibility::Red, _) | (_, Compatibility::Red) => Compatibility::Red,
+                (Compatibility::Yellow, _) | (_, Compatibility::Yellow) => Compatibility::Yellow,
+                _ => Compatibility::Green,
+            })
+    }
+}
+
+fn compare_service(
+    local: &ServiceManifest,
+    remote: &ServiceManifest,
+    config: &BreakingConfig,
+) -> Compatibility {
+    if local.fingerprint == remote.fingerprint {
+        return Compatibility::Green;
+    }
+    let (Some(local), Some(remote)) = (&local.shape, &remote.shape) else {
+        return Compatibility::Red;
+    };
+
+    // Files are matched by package; a package only one side has is compared
+    // with an empty file so that its definitions count as added or deleted.
+    let by_package = |shape: &[CanonicalFile]| -> BTreeMap<Option<String>, CanonicalFile> {
+        shape
+            .iter()
+            .map(|f| (f.package.clone(), f.clone()))
+            .collect()
+    };
+    let previous = by_package(local);
+    let current = by_package(remote);
+    let packages: BTreeSet<&Option<String>> = previous.keys().chain(current.keys()).collect();
+
+    let engine = BreakingEngine::new();
+    let mut result = BreakingResult::new();
+    for package in packages {
+        let empty = |other: &CanonicalFile| CanonicalFile {
+            package: other.package.clone(),
+            syntax: other.syntax.clone(),
+            ..Default::default()
+        };
+        let (previous, current) = match (previous.get(package), current.get(package)) {
+            (Some(previous), Some(current)) => (previous.clone(), current.clone()),
+            (Some(previous), None) => (previous.clone(), empty(previous)),
+            (None, Some(current)) => (empty(current), current.clone()),
+            (None, None) => continue,
+        };
+        let package_result = engine.check(&current, &previous, config);
+        result.has_breaking_changes |= package_result.has_breaking_changes;
+        result.changes.extend(package_result.changes);
+    }
+    Compatibility::from_breaking_result(false, &result)
+}
+
+/// The definitions of `files` named in `members`, one file per package.
+///
+/// Nested messages and enums are moved to the top level under their dotted
+/// path, without their nested definitions, so that a closure only holds its
+/// own members. Services keep all their methods, which are always members.
+fn closure_shape(files: &[CanonicalFile], members: &BTreeSet<&str>) -> Vec<CanonicalFile> {
+    let mut shape: BTreeMap<Option<String>, CanonicalFile> = BTreeMap::new();
+    for file in files {
+        let package = file.package.as_deref().unwrap_or_default();
+        let is_member = |path: &str| members.contains(qualify(package, path).as_str());
+        let entry = shape
+            .entry(file.package.clone())
+            .or_insert_with(|| CanonicalFile {
+                package: file.package.clone(),
+                syntax: file.syntax.clone(),
+                ..Default::default()
+            });
+
+        for (path, message) in file.messages_by_path() {
+            if is_member(&path) {
+                let mut message = message.clone();
+                message.name = path;
+                message.nested_messages.clear();
+                message.nested_enums.clear();
+                entry.messages.insert(message);
+            }
+        }
+        for (path, enumeration) in file.enums_by_path() {
+            if is_member(&path) {
+                let mut enumeration = enumeration.clone();
+                enumeration.name = path;
+                entry.enums.insert(enumeration);
+            }
+        }
+        for service in &file.services {
+            if is_member(&service.name) {
+                entry.services.insert(service.clone());
+            }
+        }
+        for extension in &file.extensions {
+            if is_member(&extension.name) {
+                entry.extensions.insert(extension.clone());
+            }
+        }
+    }
+    shape
+        .into_values()
+        .filter(|file| {
+            !(file.messages.is_empty()
+                && file.enums.is_empty()
+                && file.services.is_empty()
+                && file.extensions.is_empty())
+        })
+        .collect()
+}
@@ -1,15 +1,34 @@
+pub mod build;
 pub mod canonical;
+pub mod changelog;
 pub mod compat;
 pub mod compatibility;
+pub mod diff;
+pub mod fingerprint;
+pub mod handshake;
+pub mod lock;
+pub mod module;
 pub mod normalize;
+pub mod parse;
+pub mod profile;
+pub mod resolve;
+pub mod roundtrip;
+pub mod sign;
+pub mod source;
 pub mod spec;
 pub mod test_bulk_count;
+pub mod wire;
 
+pub use changelog::Changelog;
+pub use diff::CanonicalDiff;
+pub use fingerprint::{DigestEncoding, FingerprintOptions, FingerprintVersion, HashAlgorithm};
+pub use module::ModuleManifest;
+pub use parse::{ParseError, ParseMode};
+pub use profile::FingerprintProfile;
+pub use source::{SourceProvider, VirtualFileMap};
 pub use spec::{Compatibility, Spec};
 
-use anyhow::Context;
-use protobuf_parse::Parser;
-use sha2::{Digest, Sha256};
+use std::path::Path;
 
 /// Generates a semantic fingerprint for a given Protobuf file content.
 ///
@@ -23,71 +42,64 @@ use sha2::{Digest, Sha256};
 ///
 /// # Returns
 ///
-/// A `Result` containing the hex-encoded SHA-256 fingerprint string,
-/// or an error if parsing or processing fails.
+/// A `Result` containing the versioned fingerprint (e.g. `ps1-sha256:…`),
+/// or an error if parsing or processing fails. Parse failures can be
+/// downcast to [`ParseError`].
 pub fn generate_fingerprint(proto_content: &str) -> anyhow::Result<String> {
-    // The parser works with the filesystem, so we need to create a temporary
-    // directory and file to hold the content.
-    let temp_dir = tempfile::tempdir().context("Failed to create temp directory")?;
-    let file_name = "input.proto";
-    let temp_path = temp_dir.path().join(file_name);
-    std::fs::write(&temp_path, proto_content).context("Failed to write to temp file")?;
+    // 1. Parse the proto file. Failures are reported as a typed `ParseError`.
+    let file_descriptor = parse::parse_file_descriptor(proto_content, None)?;
 
-    // To handle imports correctly without needing the entire dependency tree,
-    // we create dummy files for each imported `.proto` file.
-    for line in proto_content.lines() {
-        if line.trim().starts_with("import ") {
-            let path_str = line
-                .trim()
-                .trim_start_matches("import ")
-                .trim_start_matches("public ")
-                .trim_matches(|c| c == '"' || c == ';');
-
-            // The parser has built-in knowledge of standard google.protobuf types.
-            // If we create a dummy file, it will override the built-in and fail
-            // because our dummy file is empty. So, we only create dummies for
-            // non-standard imports.
-            if !path_str.starts_with("google/protobuf/") {
-                let import_path = temp_dir.path().join(path_str);
-                if let Some(parent) = import_path.parent() {
-                    std::fs::create_dir_all(parent).context(format!(
-                        "Failed to create parent dirs for import: {path_str}"
-                    ))?;
-                }
-                std::fs::write(&import_path, "syntax = \"proto3\";")
-                    .context(format!("Failed to create dummy import file: {path_str}"))?;
-            }
-        }
-    }
-
-    // 1. Parse the proto file using the public `Parser` API.
-    let parsed = Parser::new()
-        .pure()
-        .include(temp_dir.path()) // Search for imports in the temp dir.
-        .input(&temp_path) // The file to parse.
-        .file_descriptor_set()
-        .context("Protobuf parsing failed")?;
+    // 2. Normalize the AST into our canonical representation.
+    let canonical_file = normalize::normalize_file(&file_descriptor);
 
-    // The result contains all parsed files, including imports. We need to find the
-    // one corresponding to our input file.
-    let file_descriptor = parsed
-        .file
-        .into_iter()
-        .find(|d| d.name() == file_name)
-        .context("Could not find the parsed file descriptor for the input file")?;
+    fingerprint_canonical_file(&canonical_file)
+}
 
-    // 2. Normalize the AST into our canonical representation.
+/// Generates a semantic fingerprint with a chosen hash and digest encoding.
+///
+/// The algorithm and encoding are named in the prefix, e.g.
+/// `ps1-sha512:…` or `ps1-blake3-mh:…`. With default options this returns
+/// the same value as [`generate_fingerprint`].
+pub fn generate_fingerprint_with_options(
+    proto_content: &str,
+    options: &FingerprintOptions,
+) -> anyhow::Result<String> {
+    let file_descriptor = parse::parse_file_descriptor(proto_content, None)?;
     let canonical_file = normalize::normalize_file(&file_descriptor);
+    fingerprint::fingerprint_with_options(&canonical_file, options)
+}
 
-    // 3. Serialize the canonical representation to a stable JSON string.
-    let json_string = serde_json::to_string_pretty(&canonical_file)
-        .context("Failed to serialize canonical representation to JSON")?;
+/// Generates the wire fingerprint of a Protobuf file content.
+///
+/// Unlike [`generate_fingerprint`], this ignores names and only covers what
+/// determines the binary encoding, so two files with equal wire fingerprints
+/// interoperate on the wire. See [`wire`] for what is included.
+pub fn generate_wire_fingerprint(proto_content: &str) -> anyhow::Result<String> {
+    let files = canonical_files_with_imports(proto_content, None)?;
+    wire::wire_fingerprint(&files)
+}
 
-    // 4. Compute the SHA-256 hash of the JSON string.
-    let mut hasher = Sha256::new();
-    hasher.update(json_string.as_bytes());
-    let hash_result = hasher.finalize();
+/// Computes the semantic fingerprint of an already normalized file.
+///
+/// This is the hashing half of [`generate_fingerprint`], for callers that
+/// hold a `CanonicalFile` and do not want to parse the source again.
+pub fn fingerprint_canonical_file(
+    canonical_file: &canonical::CanonicalFile,
+) -> anyhow::Result<String> {
+    fingerprint::fingerprint_with_version(canonical_file, FingerprintVersion::CURRENT)
+}
 
-    // 5. Format as a hex string and return.
-    Ok(format!("{hash_result:x}"))
+/// Returns the canonical model of `proto_content` followed by those of every
+/// file it transitively imports.
+///
+/// Imports are resolved relative to the directory of `file_path`, as for
+/// [`Spec::try_from_file`]. Standard `google/protobuf/*`
+/// imports yield their built-in definitions. The result is the input for
+/// [`fingerprint::closure_fingerprint`].
+pub fn canonical_files_with_imports(
+    proto_content: &str,
+    file_path: Option<&Path>,
+) -> Result<Vec<canonical::CanonicalFile>, ParseError> {
+    let descriptors = parse::parse_file_descriptors_with_imports(proto_content, file_path)?;
+    Ok(descriptors.iter().map(normalize::normalize_file).collect())
 }
@@ -0,0 +1,213 @@
+//! Schema lockfiles.
+//!
+//! A `proto-sign.lock` records the semantic fingerprint of every `.proto`
+//! file in a tree, optionally its wire fingerprint, and the canonical model
+//! the fingerprint was computed from. Checking a tree against its lockfile
+//! reports files whose semantic content changed without the lock being
+//! updated, down to the messages, enums, services and methods that changed.
+
+use crate::canonical::CanonicalFile;
+use crate::fingerprint::{ElementKind, element_fingerprints, matches_cryptographic_fingerprint};
+use crate::source::VirtualFileMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Default file name of a lockfile, placed at the root of the tree it locks.
+pub const LOCKFILE_NAME: &str = "proto-sign.lock";
+
+/// Version of the lockfile format.
+pub const LOCKFILE_VERSION: u32 = 1;
+
+/// The contents of a `proto-sign.lock`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    /// Locked files, keyed by path relative to the tree root with `/`
+    /// separators.
+    pub files: BTreeMap<String, LockEntry>,
+}
+
+/// The locked state of a single file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockEntry {
+    /// The semantic fingerprint.
+    pub fingerprint: String,
+    /// The wire fingerprint, if the lock was created with wire fingerprints.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wire_fingerprint: Option<String>,
+    /// The canonical model, kept so that changes can be reported per element.
+    pub canonical: CanonicalFile,
+}
+
+/// How a file or element differs from the lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl fmt::Display for LockChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockChangeKind::Added => write!(f, "added"),
+            LockChangeKind::Removed => write!(f, "removed"),
+            LockChangeKind::Modified => write!(f, "modified"),
+        }
+    }
+}
+
+/// A message, enum, service or method that differs from the lock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ElementChange {
+    pub name: String,
+    pub kind: ElementKind,
+    pub change: LockChangeKind,
+}
+
+/// A file that differs from the lock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub change: LockChangeKind,
+    /// True if the wire fingerprint differs. Only checked for files locked
+    /// with a wire fingerprint.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub wire_changed: bool,
+    /// The elements that changed, for modified files.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub elements: Vec<ElementChange>,
+}
+
+impl Lockfile {
+    /// Locks every `.proto` file below `dir`, with wire fingerprints if
+    /// `wire` is set. Imports are resolved against `dir`.
+    pub fn from_dir(dir: &Path, wire: bool) -> anyhow::Result<Self> {
+        Self::from_sources(&VirtualFileMap::from_dir(dir)?, wire)
+    }
+
+    /// Locks every file in `sources`, resolving imports from the same map.
+    pub fn from_sources(sources: &VirtualFileMap, wire: bool) -> anyhow::Result<Self> {
+        let mut files = BTreeMap::new();
+        for path in sources.paths() {
+            let content = sources.get(path).unwrap_or_default();
+            let descriptors = crate::parse::parse_all_with_sources(content, path, sources)?;
+            let canonical_files: Vec<CanonicalFile> = descriptors
+                .iter()
+                .map(crate::normalize::normalize_file)
+                .collect();
+            let wire_fingerprint = if wire {
+                Some(crate::wire::wire_fingerprint(&canonical_files)?)
+            } else {
+                None
+            };
+            let canonical = canonical_files.into_iter().next().unwrap_or_default();
+            files.insert(
+                path.to_string(),
+                LockEntry {
+                    fingerprint: crate::fingerprint_canonical_file(&canonical)?,
+                    wire_fingerprint,
+                    canonical,
+                },
+            );
+        }
+        Ok(Self {
+            version: LOCKFILE_VERSION,
+            files,
+        })
+    }
+
+    /// Returns true if any entry has a wire fingerprint.
+    pub fn has_wire_fingerprints(&self) -> bool {
+        self.files.values().any(|e| e.wire_fingerprint.is_some())
+    }
+
+    /// Lists the files in `current` that differ from this lock.
+    ///
+    /// Fingerprints are compared in the version they were locked with, so a
+    /// lock written by an older release still checks cleanly, but `xxh3` and
+    /// legacy fingerprints are rejected. Wire fingerprints are compared only
+    /// where both sides have one.
+    pub fn check(&self, current: &Lockfile) -> anyhow::Result<Vec<FileChange>> {
+        let mut changes = Vec::new();
+        for (path, locked) in &self.files {
+            let Some(entry) = current.files.get(path) else {
+                changes.push(file_change(path, LockChangeKind::Removed));
+                continue;
+            };
+            let semantic_changed =
+                !matches_cryptographic_fingerprint(&entry.canonical, &locked.fingerprint)?;
+            let wire_changed = matches!(
+                (&locked.wire_fingerprint, &entry.wire_fingerprint),
+                (Some(old), Some(new)) if old != new
+            );
+            if semantic_changed || wire_changed {
+                changes.push(FileChange {
+                    wire_changed,
+                    elements: element_changes(&locked.canonical, &entry.canonical)?,
+                    ..file_change(path, LockChangeKind::Modified)
+                });
+            }
+        }
+        for path in current.files.keys() {
+            if !self.files.contains_key(path) {
+                changes.push(file_change(path, LockChangeKind::Added));
+            }
+        }
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(changes)
+    }
+}
+
+fn file_change(path: &str, change: LockChangeKind) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        change,
+        wire_changed: false,
+        elements: Vec::new(),
+    }
+}
+
+/// Compares the per-element fingerprints of two versions of a file.
+fn element_changes(
+    locked: &CanonicalFile,
+    current: &CanonicalFile,
+) -> anyhow::Result<Vec<ElementChange>> {
+    let by_name =
+        |file: &CanonicalFile| -> anyhow::Result<BTreeMap<(String, ElementKind), String>> {
+            Ok(element_fingerprints(file)?
+                .into_iter()
+                .map(|e| ((e.name, e.kind), e.fingerprint))
+                .collect())
+        };
+    let old = by_name(locked)?;
+    let new = by_name(current)?;
+
+    let mut changes = Vec::new();
+    for ((name, kind), fingerprint) in &old {
+        let change = match new.get(&(name.clone(), *kind)) {
+            None => LockChangeKind::Removed,
+            Some(other) if other != fingerprint => LockChangeKind::Modified,
+            Some(_) => continue,
+        };
+        changes.push(ElementChange {
+            name: name.clone(),
+            kind: *kind,
+            change,
+        });
+    }
+    for (name, kind) in new.keys() {
+        if !old.contains_key(&(name.clone(), *kind)) {
+            changes.push(ElementChange {
+                name: name.clone(),
+                kind: *kind,
+                change: LockChangeKind::Added,
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.name.cmp(&b.name).then(a.kind.cmp(&b.kind)));
+    Ok(changes)
+}
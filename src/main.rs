@@ -1,8 +1,23 @@
 use anyhow::Result;
 use clap::Parser;
+use proto_sign::canonical::canonical_json_schema;
+use proto_sign::changelog::{GitVersion, git_versions};
+use proto_sign::compat::{BreakingConfig, Direction};
+use proto_sign::fingerprint::{
+    DigestEncoding, FingerprintOptions, FingerprintVersion, HashAlgorithm, closure_fingerprint,
+    element_fingerprints, fingerprint_with_options, fingerprint_with_version,
+};
+use proto_sign::lock::{LOCKFILE_NAME, Lockfile};
+use proto_sign::profile::FingerprintProfile;
+use proto_sign::roundtrip::{RoundTripOptions, verify_round_trip};
+use proto_sign::sign::{self, DetachedSignature};
 use proto_sign::spec::{Compatibility, Spec};
+use proto_sign::wire::wire_fingerprint;
+use proto_sign::{
+    CanonicalDiff, Changelog, ModuleManifest, ParseMode, canonical_files_with_imports,
+};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "proto-sign")]
@@ -23,11 +38,87 @@ enum Commands {
         new_file: PathBuf,
         #[arg(long, help = "Show detailed breaking change analysis")]
         detailed: bool,
+        #[arg(long, help = "Rules that make a change Red (comma-separated)")]
+        use_rules: Option<String>,
+        #[arg(
+            long,
+            help = "Categories whose rules make a change Red (comma-separated) [default: FILE,PACKAGE]"
+        )]
+        use_categories: Option<String>,
+        #[arg(long, help = "Rules to exclude (comma-separated)")]
+        except_rules: Option<String>,
+        #[arg(
+            long,
+            help = "Which readers must accept the other schema's data: backward, forward or full",
+            default_value = "backward"
+        )]
+        direction: Direction,
+        #[arg(long, help = "Approximate files the parser rejects instead of failing")]
+        best_effort: bool,
     },
-    #[command(about = "Generate semantic fingerprint for a .proto file")]
+    #[command(about = "Generate semantic fingerprint for a .proto file or module directory")]
     Fingerprint {
-        #[arg(help = "Path to the .proto file")]
+        #[arg(help = "Path to the .proto file, or a directory to fingerprint as a module")]
         file: PathBuf,
+        #[arg(
+            long,
+            help = "Fingerprint format version (ps1, or legacy for migrating stored fingerprints)",
+            default_value = "ps1"
+        )]
+        fingerprint_version: FingerprintVersion,
+        #[arg(
+            long,
+            help = "Hash algorithm: sha256, sha512, blake3 or xxh3 (fast, not for signing) [default: sha256]",
+            conflicts_with = "fingerprint_version"
+        )]
+        algorithm: Option<HashAlgorithm>,
+        #[arg(
+            long,
+            help = "Digest encoding: hex, base64url or multihash [default: hex]",
+            conflicts_with = "fingerprint_version"
+        )]
+        encoding: Option<DigestEncoding>,
+        #[arg(
+            long,
+            help = "Attributes to hash: exact, api (ignores deprecation and codegen options) or wire (also ignores JSON names and defaults) [default: exact]",
+            conflicts_with = "fingerprint_version"
+        )]
+        profile: Option<FingerprintProfile>,
+        #[arg(
+            long,
+            help = "Print a fingerprint for each message, enum, service and method",
+            conflicts_with_all = ["fingerprint_version", "algorithm", "encoding", "profile"]
+        )]
+        per_element: bool,
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Fingerprint a definition and every type it reaches, across imports",
+            conflicts_with_all = ["fingerprint_version", "algorithm", "encoding", "profile", "per_element"]
+        )]
+        closure: Option<String>,
+        #[arg(
+            long,
+            help = "Print the wire fingerprint, which ignores names and covers only the binary encoding",
+            conflicts_with_all = ["fingerprint_version", "algorithm", "encoding", "profile", "per_element", "closure"]
+        )]
+        wire: bool,
+        #[arg(
+            long,
+            value_name = "MANIFEST",
+            help = "Compare a module directory against a stored JSON manifest",
+            conflicts_with_all = ["fingerprint_version", "algorithm", "encoding", "profile", "per_element", "closure", "wire"]
+        )]
+        verify: Option<PathBuf>,
+        #[arg(long, help = "Output format", value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    #[command(about = "Print the canonical JSON model of a .proto file")]
+    Canonical {
+        #[arg(help = "Path to the .proto file", required_unless_present = "schema")]
+        file: Option<PathBuf>,
+        #[arg(long, help = "Print the JSON Schema of the canonical model instead")]
+        schema: bool,
     },
     #[command(about = "Check for breaking changes using Buf-compatible rules")]
     Breaking {
@@ -43,6 +134,151 @@ enum Commands {
         use_categories: Option<String>,
         #[arg(long, help = "Rules to exclude (comma-separated)")]
         except_rules: Option<String>,
+        #[arg(
+            long,
+            help = "Which readers must accept the other schema's data: backward, forward or full",
+            default_value = "backward"
+        )]
+        direction: Direction,
+        #[arg(
+            long,
+            help = "Report removed and added elements that were probably renamed"
+        )]
+        detect_renames: bool,
+        #[arg(long, help = "Approximate files the parser rejects instead of failing")]
+        best_effort: bool,
+    },
+    #[command(about = "List every semantic change between two .proto files")]
+    Diff {
+        #[arg(help = "Path to the old .proto file")]
+        old_file: PathBuf,
+        #[arg(help = "Path to the new .proto file")]
+        new_file: PathBuf,
+        #[arg(long, help = "Output format", value_enum, default_value = "text")]
+        format: OutputFormat,
+        #[arg(long, help = "Approximate files the parser rejects instead of failing")]
+        best_effort: bool,
+    },
+    #[command(about = "Recommend a major, minor or patch version bump between two .proto files")]
+    Semver {
+        #[arg(help = "Path to the old .proto file")]
+        old_file: PathBuf,
+        #[arg(help = "Path to the new .proto file")]
+        new_file: PathBuf,
+        #[arg(long, help = "Output format", value_enum, default_value = "text")]
+        format: OutputFormat,
+        #[arg(long, help = "Rules to use (comma-separated)")]
+        use_rules: Option<String>,
+        #[arg(long, help = "Categories to use (comma-separated)")]
+        use_categories: Option<String>,
+        #[arg(long, help = "Rules to exclude (comma-separated)")]
+        except_rules: Option<String>,
+        #[arg(
+            long,
+            help = "Which readers must accept the other schema's data: backward, forward or full",
+            default_value = "backward"
+        )]
+        direction: Direction,
+        #[arg(long, help = "Approximate files the parser rejects instead of failing")]
+        best_effort: bool,
+    },
+    #[command(about = "Write a Markdown changelog of API changes across versions of a .proto file")]
+    Changelog {
+        #[arg(
+            help = "Versions of the .proto file, oldest first; a single file with --git",
+            required = true
+        )]
+        files: Vec<PathBuf>,
+        #[arg(
+            long,
+            value_name = "RANGE",
+            help = "Read the versions from a git revision range, e.g. v1.0..HEAD"
+        )]
+        git: Option<String>,
+        #[arg(long, help = "Output format", value_enum, default_value = "text")]
+        format: OutputFormat,
+        #[arg(long, help = "Rules to use (comma-separated)")]
+        use_rules: Option<String>,
+        #[arg(long, help = "Categories to use (comma-separated)")]
+        use_categories: Option<String>,
+        #[arg(long, help = "Rules to exclude (comma-separated)")]
+        except_rules: Option<String>,
+        #[arg(
+            long,
+            help = "Which readers must accept the other schema's data: backward, forward or full",
+            default_value = "backward"
+        )]
+        direction: Direction,
+        #[arg(long, help = "Approximate files the parser rejects instead of failing")]
+        best_effort: bool,
+    },
+    #[command(
+        about = "Check wire compatibility by decoding random old messages with the new schema"
+    )]
+    Roundtrip {
+        #[arg(help = "Path to the old .proto file")]
+        old_file: PathBuf,
+        #[arg(help = "Path to the new .proto file")]
+        new_file: PathBuf,
+        #[arg(long, help = "Random samples per field", default_value_t = 32)]
+        samples: usize,
+        #[arg(long, help = "Seed for the random samples", default_value_t = 0)]
+        seed: u64,
+        #[arg(long, help = "Also fail on JSON name and value changes")]
+        json: bool,
+        #[arg(long, help = "Output format", value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    #[command(about = "Write a proto-sign.lock for every .proto file in a directory")]
+    Lock {
+        #[arg(help = "Root directory of the .proto files", default_value = ".")]
+        dir: PathBuf,
+        #[arg(long, help = "Also record wire fingerprints")]
+        wire: bool,
+        #[arg(
+            long,
+            help = "Where to write the lockfile [default: <dir>/proto-sign.lock]"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Check a directory against its proto-sign.lock")]
+    CheckLock {
+        #[arg(help = "Root directory of the .proto files", default_value = ".")]
+        dir: PathBuf,
+        #[arg(
+            long,
+            help = "Lockfile to check against [default: <dir>/proto-sign.lock]"
+        )]
+        lock: Option<PathBuf>,
+        #[arg(long, help = "Output format", value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    #[command(about = "Generate an ed25519 key pair for signing fingerprints")]
+    Keygen {
+        #[arg(long, help = "Where to write the private key (PKCS#8 PEM)")]
+        out: PathBuf,
+        #[arg(long, help = "Where to write the public key [default: <out>.pub]")]
+        public_out: Option<PathBuf>,
+    },
+    #[command(about = "Sign the semantic fingerprint of a .proto file")]
+    Sign {
+        #[arg(help = "Path to the .proto file")]
+        file: PathBuf,
+        #[arg(long, help = "Private key (PKCS#8 PEM)")]
+        key: PathBuf,
+        #[arg(long, help = "Signer identity to record [default: the key ID]")]
+        signer_id: Option<String>,
+        #[arg(long, help = "Where to write the signature [default: <file>.sig]")]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Verify a detached signature against a .proto file")]
+    Verify {
+        #[arg(help = "Path to the .proto file")]
+        file: PathBuf,
+        #[arg(long, help = "Signer's public key (SPKI PEM)")]
+        key: PathBuf,
+        #[arg(long, help = "Signature file [default: <file>.sig]")]
+        signature: Option<PathBuf>,
     },
 }
 
@@ -52,6 +288,131 @@ enum OutputFormat {
     Json,
 }
 
+/// Builds the rule selection shared by `compare` and `breaking`.
+fn breaking_config(
+    use_rules: Option<String>,
+    use_categories: Option<String>,
+    except_rules: Option<String>,
+    direction: Direction,
+) -> BreakingConfig {
+    let split = |list: String| list.split(',').map(|s| s.trim().to_string()).collect();
+    let mut config = BreakingConfig::default();
+
+    if let Some(rules) = use_rules {
+        config.use_rules = split(rules);
+        config.use_categories.clear(); // Clear default categories when specific rules are used
+    }
+    if let Some(categories) = use_categories {
+        config.use_categories = split(categories);
+    }
+    if let Some(except) = except_rules {
+        config.except_rules = split(except);
+    }
+    config.direction = direction;
+    config
+}
+
+/// Parses a file for comparison, warning on stderr when the result is degraded.
+///
+/// Commands that accept `--best-effort` exit with [`DEGRADED_EXIT_CODE`] after
+/// printing results computed from a degraded spec.
+fn load_spec<'a>(path: &Path, content: &'a str, best_effort: bool) -> Result<Spec<'a>> {
+    warn_if_degraded(Spec::try_from_file_with_mode(
+        path,
+        content,
+        parse_mode(best_effort),
+    )?)
+}
+
+/// Like [`load_spec`] for a version read from git, with imports from the
+/// same revision.
+fn load_git_spec(version: &GitVersion, best_effort: bool) -> Result<Spec<'_>> {
+    warn_if_degraded(Spec::try_from_sources_with_mode(
+        &version.sources,
+        &version.path,
+        parse_mode(best_effort),
+    )?)
+}
+
+fn parse_mode(best_effort: bool) -> ParseMode {
+    if best_effort {
+        ParseMode::BestEffort
+    } else {
+        ParseMode::Strict
+    }
+}
+
+fn warn_if_degraded(spec: Spec<'_>) -> Result<Spec<'_>> {
+    if let Some(error) = &spec.degraded {
+        eprintln!("Warning: using approximate parse, results may be incomplete: {error}");
+    }
+    Ok(spec)
+}
+
+/// Exit code for results computed from an approximate parse, so that CI can
+/// refuse them even when no breaking change was found.
+const DEGRADED_EXIT_CODE: i32 = 2;
+
+/// Exits with [`DEGRADED_EXIT_CODE`] if any spec was approximated.
+fn exit_if_degraded(specs: &[&Spec]) {
+    if specs.iter().any(|spec| spec.is_degraded()) {
+        std::process::exit(DEGRADED_EXIT_CODE);
+    }
+}
+
+/// Serializes a result as JSON with a top-level `degraded` flag.
+fn to_json_with_degraded(value: &impl serde::Serialize, specs: &[&Spec]) -> Result<String> {
+    let mut json = serde_json::to_value(value)?;
+    if let Some(object) = json.as_object_mut() {
+        let degraded = specs.iter().any(|spec| spec.is_degraded());
+        object.insert("degraded".to_string(), degraded.into());
+    }
+    Ok(serde_json::to_string_pretty(&json)?)
+}
+
+/// Prints the Merkle manifest of a module directory, or verifies the
+/// directory against a stored manifest and exits with 1 if files changed.
+fn fingerprint_module(dir: &Path, verify: Option<&Path>, format: OutputFormat) -> Result<()> {
+    let manifest = ModuleManifest::from_dir(dir)?;
+
+    let Some(stored_path) = verify else {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&manifest)?),
+            OutputFormat::Text => println!("{}", manifest.root),
+        }
+        return Ok(());
+    };
+
+    let stored = fs::read_to_string(stored_path).map_err(|e| {
+        anyhow::anyhow!("Failed to read manifest '{}': {}", stored_path.display(), e)
+    })?;
+    let stored: ModuleManifest = serde_json::from_str(&stored)
+        .map_err(|e| anyhow::anyhow!("Invalid manifest '{}': {}", stored_path.display(), e))?;
+    let diff = stored.diff(&manifest);
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+        OutputFormat::Text => {
+            if diff.is_empty() {
+                println!("Module matches manifest ({})", manifest.root);
+            }
+            for path in &diff.changed {
+                println!("changed  {path}");
+            }
+            for path in &diff.added {
+                println!("added    {path}");
+            }
+            for path in &diff.removed {
+                println!("removed  {path}");
+            }
+        }
+    }
+    if !diff.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
 
@@ -60,6 +421,11 @@ fn main() -> Result<()> {
             old_file,
             new_file,
             detailed,
+            use_rules,
+            use_categories,
+            except_rules,
+            direction,
+            best_effort,
         } => {
             let old_content = fs::read_to_string(&old_file).map_err(|e| {
                 anyhow::anyhow!("Failed to read old file '{}': {}", old_file.display(), e)
@@ -68,64 +434,142 @@ fn main() -> Result<()> {
                 anyhow::anyhow!("Failed to read new file '{}': {}", new_file.display(), e)
             })?;
 
-            let old_spec = Spec::try_from(old_content.as_str())?;
-            let new_spec = Spec::try_from(new_content.as_str())?;
+            let old_spec = load_spec(&old_file, &old_content, best_effort)?;
+            let new_spec = load_spec(&new_file, &new_content, best_effort)?;
 
-            let compatibility = old_spec.compare_with(&new_spec);
+            let config = breaking_config(use_rules, use_categories, except_rules, direction);
+            let breaking_result = old_spec.check_breaking_changes_with_config(&new_spec, &config);
+            let compatibility = Compatibility::from_breaking_result(
+                old_spec.fingerprint == new_spec.fingerprint,
+                &breaking_result,
+            );
 
             match compatibility {
-                Compatibility::Green => {
-                    println!("Green: Files are semantically identical");
-                    if detailed {
-                        let breaking_result = old_spec.check_breaking_changes(&new_spec);
-                        println!(
-                            "Detailed analysis: {} rules executed, {} breaking changes found",
-                            breaking_result.executed_rules.len(),
-                            breaking_result.changes.len()
-                        );
+                Compatibility::Green => println!("Green: Files are semantically identical"),
+                Compatibility::Yellow => {
+                    let compatible = match config.direction {
+                        Direction::Backward => "backward-compatible",
+                        Direction::Forward => "forward-compatible",
+                        Direction::Full => "fully compatible",
+                    };
+                    println!("Yellow: New file is {compatible} with old file")
+                }
+                Compatibility::Red => println!("Red: Breaking change detected"),
+            }
+            if detailed {
+                println!(
+                    "Detailed analysis: {} rules executed, {} breaking changes found",
+                    breaking_result.executed_rules.len(),
+                    breaking_result.changes.len()
+                );
+                for change in &breaking_result.changes {
+                    println!("  - {}: {}", change.rule_id, change.message);
+                }
+            }
+            exit_if_degraded(&[&old_spec, &new_spec]);
+            if compatibility == Compatibility::Red {
+                std::process::exit(1);
+            }
+        }
+        Commands::Fingerprint {
+            file,
+            fingerprint_version,
+            algorithm,
+            encoding,
+            profile,
+            per_element,
+            closure,
+            wire,
+            verify,
+            format,
+        } => {
+            if file.is_dir() || verify.is_some() {
+                if per_element || closure.is_some() || wire {
+                    anyhow::bail!("--per-element, --closure and --wire need a single .proto file");
+                }
+                if algorithm.is_some() || encoding.is_some() || profile.is_some() {
+                    anyhow::bail!(
+                        "--algorithm, --encoding and --profile need a single .proto file"
+                    );
+                }
+                return fingerprint_module(&file, verify.as_deref(), format);
+            }
+
+            let content = fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read file '{}': {}", file.display(), e))?;
+
+            if let Some(root) = closure {
+                let files = canonical_files_with_imports(&content, Some(&file))?;
+                let closure = closure_fingerprint(&files, &root)?;
+                match format {
+                    OutputFormat::Json => {
+                        println!("{}", serde_json::to_string_pretty(&closure)?);
                     }
-                    std::process::exit(0);
+                    OutputFormat::Text => println!("{}", closure.fingerprint),
                 }
-                Compatibility::Yellow => {
-                    println!("Yellow: New file is backward-compatible with old file");
-                    if detailed {
-                        let breaking_result = old_spec.check_breaking_changes(&new_spec);
-                        println!(
-                            "Detailed analysis: {} rules executed, {} breaking changes found",
-                            breaking_result.executed_rules.len(),
-                            breaking_result.changes.len()
-                        );
-                        if breaking_result.has_breaking_changes {
-                            println!(
-                                "Note: Some breaking changes were detected but are considered acceptable for backward compatibility"
-                            );
-                        }
+                return Ok(());
+            }
+
+            if wire {
+                let files = canonical_files_with_imports(&content, Some(&file))?;
+                let fingerprint = wire_fingerprint(&files)?;
+                match format {
+                    OutputFormat::Json => {
+                        let json = serde_json::json!({ "wire_fingerprint": fingerprint });
+                        println!("{}", serde_json::to_string_pretty(&json)?);
                     }
-                    std::process::exit(0);
+                    OutputFormat::Text => println!("{fingerprint}"),
                 }
-                Compatibility::Red => {
-                    println!("Red: Breaking change detected");
-                    if detailed {
-                        let breaking_result = old_spec.check_breaking_changes(&new_spec);
-                        println!(
-                            "Detailed analysis: {} rules executed, {} breaking changes found",
-                            breaking_result.executed_rules.len(),
-                            breaking_result.changes.len()
-                        );
-                        for change in &breaking_result.changes {
-                            println!("  - {}: {}", change.rule_id, change.message);
+                return Ok(());
+            }
+
+            let spec = Spec::try_from_file(&file, &content)?;
+            if per_element {
+                let elements = element_fingerprints(&spec.canonical_file)?;
+                match format {
+                    OutputFormat::Json => {
+                        println!("{}", serde_json::to_string_pretty(&elements)?);
+                    }
+                    OutputFormat::Text => {
+                        for element in &elements {
+                            println!("{}  {} {}", element.fingerprint, element.kind, element.name);
                         }
                     }
-                    std::process::exit(1);
+                }
+            } else {
+                let fingerprint = if algorithm.is_some() || encoding.is_some() || profile.is_some()
+                {
+                    let options = FingerprintOptions::new(
+                        algorithm.unwrap_or_default(),
+                        encoding.unwrap_or_default(),
+                    )
+                    .with_profile(profile.unwrap_or_default());
+                    fingerprint_with_options(&spec.canonical_file, &options)?
+                } else {
+                    fingerprint_with_version(&spec.canonical_file, fingerprint_version)?
+                };
+                match format {
+                    OutputFormat::Json => {
+                        let json = serde_json::json!({ "fingerprint": fingerprint });
+                        println!("{}", serde_json::to_string_pretty(&json)?);
+                    }
+                    OutputFormat::Text => println!("{fingerprint}"),
                 }
             }
         }
-        Commands::Fingerprint { file } => {
-            let content = fs::read_to_string(&file)
-                .map_err(|e| anyhow::anyhow!("Failed to read file '{}': {}", file.display(), e))?;
+        Commands::Canonical { file, schema } => {
+            if schema {
+                let json = serde_json::to_string_pretty(&canonical_json_schema())?;
+                println!("{json}");
+            } else if let Some(file) = file {
+                let content = fs::read_to_string(&file).map_err(|e| {
+                    anyhow::anyhow!("Failed to read file '{}': {}", file.display(), e)
+                })?;
 
-            let fingerprint = proto_sign::generate_fingerprint(&content)?;
-            println!("{fingerprint}");
+                let spec = Spec::try_from_file(&file, &content)?;
+                let json = serde_json::to_string_pretty(&spec.canonical_file)?;
+                println!("{json}");
+            }
         }
         Commands::Breaking {
             old_file,
@@ -134,9 +578,10 @@ fn main() -> Result<()> {
             use_rules,
             use_categories,
             except_rules,
+            direction,
+            detect_renames,
+            best_effort,
         } => {
-            use proto_sign::compat::BreakingConfig;
-
             let old_content = fs::read_to_string(&old_file).map_err(|e| {
                 anyhow::anyhow!("Failed to read old file '{}': {}", old_file.display(), e)
             })?;
@@ -144,33 +589,18 @@ fn main() -> Result<()> {
                 anyhow::anyhow!("Failed to read new file '{}': {}", new_file.display(), e)
             })?;
 
-            let old_spec = Spec::try_from(old_content.as_str())?;
-            let new_spec = Spec::try_from(new_content.as_str())?;
-
-            // Build configuration
-            let mut config = BreakingConfig::default();
-
-            if let Some(rules) = use_rules {
-                config.use_rules = rules.split(',').map(|s| s.trim().to_string()).collect();
-                config.use_categories.clear(); // Clear default categories when specific rules are used
-            }
-
-            if let Some(categories) = use_categories {
-                config.use_categories = categories
-                    .split(',')
-                    .map(|s| s.trim().to_string())
-                    .collect();
-            }
-
-            if let Some(except) = except_rules {
-                config.except_rules = except.split(',').map(|s| s.trim().to_string()).collect();
-            }
+            let old_spec = load_spec(&old_file, &old_content, best_effort)?;
+            let new_spec = load_spec(&new_file, &new_content, best_effort)?;
 
+            let config = BreakingConfig {
+                detect_renames,
+                ..breaking_config(use_rules, use_categories, except_rules, direction)
+            };
             let breaking_result = old_spec.check_breaking_changes_with_config(&new_spec, &config);
 
             match format {
                 OutputFormat::Json => {
-                    let json = serde_json::to_string_pretty(&breaking_result)?;
+                    let json = to_json_with_degraded(&breaking_result, &[&old_spec, &new_spec])?;
                     println!("{json}");
                 }
                 OutputFormat::Text => {
@@ -189,6 +619,9 @@ fn main() -> Result<()> {
                                 );
                             }
                             println!("    Categories: {}", change.categories.join(", "));
+                            if let Some(hint) = &change.hint {
+                                println!("    Hint: {hint}");
+                            }
                             println!();
                         }
                         println!("Summary:");
@@ -208,14 +641,334 @@ fn main() -> Result<()> {
                         println!("No breaking changes detected.");
                         println!("Rules executed: {}", breaking_result.executed_rules.len());
                     }
+                    if !breaking_result.informational.is_empty() {
+                        println!();
+                        println!("Informational changes:");
+                        for change in &breaking_result.informational {
+                            println!("  [{}] {}", change.rule_id, change.message);
+                        }
+                    }
                 }
             }
 
+            exit_if_degraded(&[&old_spec, &new_spec]);
             if breaking_result.has_breaking_changes {
                 std::process::exit(1);
             }
         }
+        Commands::Diff {
+            old_file,
+            new_file,
+            format,
+            best_effort,
+        } => {
+            let old_content = fs::read_to_string(&old_file).map_err(|e| {
+                anyhow::anyhow!("Failed to read old file '{}': {}", old_file.display(), e)
+            })?;
+            let new_content = fs::read_to_string(&new_file).map_err(|e| {
+                anyhow::anyhow!("Failed to read new file '{}': {}", new_file.display(), e)
+            })?;
+
+            let old_spec = load_spec(&old_file, &old_content, best_effort)?;
+            let new_spec = load_spec(&new_file, &new_content, best_effort)?;
+            let diff = CanonicalDiff::between(&old_spec.canonical_file, &new_spec.canonical_file);
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", to_json_with_degraded(&diff, &[&old_spec, &new_spec])?)
+                }
+                OutputFormat::Text => print!("{diff}"),
+            }
+            exit_if_degraded(&[&old_spec, &new_spec]);
+            if diff.has_breaking_changes() {
+                std::process::exit(1);
+            }
+        }
+        Commands::Semver {
+            old_file,
+            new_file,
+            format,
+            use_rules,
+            use_categories,
+            except_rules,
+            direction,
+            best_effort,
+        } => {
+            let old_content = fs::read_to_string(&old_file).map_err(|e| {
+                anyhow::anyhow!("Failed to read old file '{}': {}", old_file.display(), e)
+            })?;
+            let new_content = fs::read_to_string(&new_file).map_err(|e| {
+                anyhow::anyhow!("Failed to read new file '{}': {}", new_file.display(), e)
+            })?;
+
+            let old_spec = load_spec(&old_file, &old_content, best_effort)?;
+            let new_spec = load_spec(&new_file, &new_content, best_effort)?;
+
+            let config = breaking_config(use_rules, use_categories, except_rules, direction);
+            let recommendation = old_spec.recommend_version_bump(&new_spec, &config);
+
+            match format {
+                OutputFormat::Json => {
+                    let specs = [&old_spec, &new_spec];
+                    println!("{}", to_json_with_degraded(&recommendation, &specs)?)
+                }
+                OutputFormat::Text => {
+                    println!("{}", recommendation.bump);
+                    for reason in &recommendation.reasons {
+                        println!("  {reason}");
+                    }
+                }
+            }
+            exit_if_degraded(&[&old_spec, &new_spec]);
+        }
+        Commands::Changelog {
+            files,
+            git,
+            format,
+            use_rules,
+            use_categories,
+            except_rules,
+            direction,
+            best_effort,
+        } => {
+            // Versions from git resolve their imports from the same revision
+            let (git_sources, file_sources): (Vec<GitVersion>, Vec<(PathBuf, String)>) = match git {
+                Some(range) => {
+                    let [file] = files.as_slice() else {
+                        anyhow::bail!("--git takes exactly one .proto file");
+                    };
+                    (git_versions(file, &range)?, Vec::new())
+                }
+                None => {
+                    let contents = files
+                        .into_iter()
+                        .map(|file| {
+                            let content = fs::read_to_string(&file).map_err(|e| {
+                                anyhow::anyhow!("Failed to read file '{}': {}", file.display(), e)
+                            })?;
+                            Ok((file, content))
+                        })
+                        .collect::<Result<_>>()?;
+                    (Vec::new(), contents)
+                }
+            };
+            let count = git_sources.len() + file_sources.len();
+            if count < 2 {
+                anyhow::bail!("A changelog needs at least two versions, found {count}");
+            }
+
+            let versions = git_sources
+                .iter()
+                .map(|version| {
+                    Ok((
+                        version.revision.clone(),
+                        load_git_spec(version, best_effort)?,
+                    ))
+                })
+                .chain(file_sources.iter().map(|(file, content)| {
+                    Ok((
+                        file.display().to_string(),
+                        load_spec(file, content, best_effort)?,
+                    ))
+                }))
+                .collect::<Result<Vec<_>>>()?;
+            let config = breaking_config(use_rules, use_categories, except_rules, direction);
+            let changelog = Changelog::from_versions(&versions, &config);
+
+            let specs: Vec<&Spec> = versions.iter().map(|(_, spec)| spec).collect();
+            match format {
+                OutputFormat::Json => println!("{}", to_json_with_degraded(&changelog, &specs)?),
+                OutputFormat::Text => print!("{changelog}"),
+            }
+            exit_if_degraded(&specs);
+        }
+        Commands::Roundtrip {
+            old_file,
+            new_file,
+            samples,
+            seed,
+            json,
+            format,
+        } => {
+            let old_content = fs::read_to_string(&old_file).map_err(|e| {
+                anyhow::anyhow!("Failed to read old file '{}': {}", old_file.display(), e)
+            })?;
+            let new_content = fs::read_to_string(&new_file).map_err(|e| {
+                anyhow::anyhow!("Failed to read new file '{}': {}", new_file.display(), e)
+            })?;
+
+            let options = RoundTripOptions { samples, seed };
+            let report = verify_round_trip(
+                &old_content,
+                Some(&old_file),
+                &new_content,
+                Some(&new_file),
+                &options,
+            )?;
+
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                OutputFormat::Text => print!("{report}"),
+            }
+            let compatible = if json {
+                report.is_wire_json_compatible()
+            } else {
+                report.is_wire_compatible()
+            };
+            if !compatible {
+                std::process::exit(1);
+            }
+        }
+        Commands::Lock { dir, wire, output } => {
+            let lockfile = Lockfile::from_dir(&dir, wire)?;
+            let output = output.unwrap_or_else(|| dir.join(LOCKFILE_NAME));
+            write_file(&output, &serde_json::to_string_pretty(&lockfile)?)?;
+            println!(
+                "Locked {} files in {}",
+                lockfile.files.len(),
+                output.display()
+            );
+        }
+        Commands::CheckLock { dir, lock, format } => {
+            let lock_path = lock.unwrap_or_else(|| dir.join(LOCKFILE_NAME));
+            let locked = fs::read_to_string(&lock_path).map_err(|e| {
+                anyhow::anyhow!("Failed to read lockfile '{}': {}", lock_path.display(), e)
+            })?;
+            let locked: Lockfile = serde_json::from_str(&locked).map_err(|e| {
+                anyhow::anyhow!("Invalid lockfile '{}': {}", lock_path.display(), e)
+            })?;
+            let current = Lockfile::from_dir(&dir, locked.has_wire_fingerprints())?;
+            let changes = locked.check(&current)?;
+
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&changes)?),
+                OutputFormat::Text => {
+                    if changes.is_empty() {
+                        println!("Lockfile is up to date ({} files)", locked.files.len());
+                    }
+                    for change in &changes {
+                        println!("{}: {}", change.change, change.path);
+                        for element in &change.elements {
+                            println!("  {} {} {}", element.change, element.kind, element.name);
+                        }
+                        if change.wire_changed {
+                            println!("  wire encoding changed");
+                        }
+                    }
+                    if !changes.is_empty() {
+                        println!();
+                        println!("Run `proto-sign lock` to update {}", lock_path.display());
+                    }
+                }
+            }
+            if !changes.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Commands::Keygen { out, public_out } => {
+            let public_out = public_out.unwrap_or_else(|| with_suffix(&out, ".pub"));
+            for path in [&out, &public_out] {
+                if path.exists() {
+                    anyhow::bail!("Refusing to overwrite existing file '{}'", path.display());
+                }
+            }
+
+            let key = sign::generate_signing_key();
+            write_private_file(&out, &sign::signing_key_to_pem(&key)?)?;
+            write_file(
+                &public_out,
+                &sign::verifying_key_to_pem(&key.verifying_key())?,
+            )?;
+            println!("Key ID: {}", sign::key_id(&key.verifying_key()));
+            println!("Private key: {}", out.display());
+            println!("Public key: {}", public_out.display());
+        }
+        Commands::Sign {
+            file,
+            key,
+            signer_id,
+            output,
+        } => {
+            let content = fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read file '{}': {}", file.display(), e))?;
+            let key = sign::signing_key_from_pem(&read_key(&key)?)?;
+            let signer_id = signer_id.unwrap_or_else(|| sign::key_id(&key.verifying_key()));
+
+            let spec = Spec::try_from_file(&file, &content)?;
+            let signature = DetachedSignature::sign_file(&spec.canonical_file, &key, &signer_id)?;
+            let output = output.unwrap_or_else(|| with_suffix(&file, ".sig"));
+            write_file(&output, &serde_json::to_string_pretty(&signature)?)?;
+            println!(
+                "Signed {} as {}",
+                signature.fingerprint, signature.signer_id
+            );
+            println!("Signature: {}", output.display());
+        }
+        Commands::Verify {
+            file,
+            key,
+            signature,
+        } => {
+            let content = fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read file '{}': {}", file.display(), e))?;
+            let key = sign::verifying_key_from_pem(&read_key(&key)?)?;
+            let signature_path = signature.unwrap_or_else(|| with_suffix(&file, ".sig"));
+            let signature = fs::read_to_string(&signature_path).map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to read signature '{}': {}",
+                    signature_path.display(),
+                    e
+                )
+            })?;
+            let signature: DetachedSignature = serde_json::from_str(&signature).map_err(|e| {
+                anyhow::anyhow!("Invalid signature '{}': {}", signature_path.display(), e)
+            })?;
+
+            let spec = Spec::try_from_file(&file, &content)?;
+            if let Err(e) = signature.verify(&spec.canonical_file, &key) {
+                println!("Invalid: {e}");
+                std::process::exit(1);
+            }
+            println!(
+                "Valid: {} signed by {} at {}",
+                signature.fingerprint, signature.signer_id, signature.signed_at
+            );
+        }
     }
 
     Ok(())
 }
+
+/// Appends `suffix` to the file name of `path`, e.g. `a.proto` -> `a.proto.sig`.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn read_key(path: &Path) -> Result<String> {
+    fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read key '{}': {}", path.display(), e))
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content)
+        .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", path.display(), e))
+}
+
+/// Like [`write_file`], but readable only by the owner on Unix.
+fn write_private_file(path: &Path, content: &str) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", path.display(), e))
+}
@@ -0,0 +1,154 @@
+//! Merkle fingerprints for a module, i.e. a directory tree of `.proto` files.
+//!
+//! Every file is a leaf whose hash is its semantic fingerprint. Every
+//! directory is an internal node whose hash covers the sorted names and hashes
+//! of its children, so the root changes whenever any file does. The resulting
+//! [`ModuleManifest`] can be stored and later compared against a checkout to
+//! find exactly which files changed, without keeping the old sources.
+
+use crate::fingerprint::ps1_fingerprint;
+use crate::source::VirtualFileMap;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The fingerprints of a module's files and directories.
+///
+/// Paths are relative to the module root and use `/` separators, e.g.
+/// `acme/v1/order.proto`. The module root itself is the directory `""`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleManifest {
+    /// Fingerprint of the module root.
+    pub root: String,
+    /// Fingerprint of every directory that contains `.proto` files, directly
+    /// or below it.
+    pub directories: BTreeMap<String, String>,
+    /// Fingerprint of every `.proto` file.
+    pub files: BTreeMap<String, String>,
+}
+
+/// The files that differ between two manifests.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ManifestDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl ManifestDiff {
+    /// Returns true if no file was added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl ModuleManifest {
+    /// Fingerprints every `.proto` file below `dir`.
+    ///
+    /// Imports are resolved against `dir`, so files must import each other
+    /// by their path relative to the module root.
+    pub fn from_dir(dir: &Path) -> anyhow::Result<Self> {
+        let sources = VirtualFileMap::from_dir(dir)?;
+        if sources.is_empty() {
+            anyhow::bail!("no .proto files found in '{}'", dir.display());
+        }
+        Self::from_sources(&sources)
+    }
+
+    /// Fingerprints every file in `sources`, resolving imports from the same
+    /// map.
+    pub fn from_sources(sources: &VirtualFileMap) -> anyhow::Result<Self> {
+        let mut files = BTreeMap::new();
+        for path in sources.paths() {
+            let content = sources.get(path).unwrap_or_default();
+            let descriptor = crate::parse::parse_with_sources(content, path, sources)?;
+            let canonical_file = crate::normalize::normalize_file(&descriptor);
+            files.insert(
+                path.to_string(),
+                crate::fingerprint_canonical_file(&canonical_file)?,
+            );
+        }
+        Self::from_file_fingerprints(files)
+    }
+
+    /// Builds the tree over already computed file fingerprints.
+    pub fn from_file_fingerprints(files: BTreeMap<String, String>) -> anyhow::Result<Self> {
+        // Children of each directory: files by name, subdirectories by name
+        // with a trailing `/` so the two can never collide.
+        let mut children: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+        for (path, fingerprint) in &files {
+            let (dir, name) = split_path(path);
+            children
+                .entry(dir.to_string())
+                .or_default()
+                .insert(name.to_string(), fingerprint.clone());
+            let mut dir = dir;
+            while !dir.is_empty() {
+                let parent = split_path(dir).0;
+                children.entry(parent.to_string()).or_default();
+                dir = parent;
+            }
+        }
+
+        // Hash the deepest directories first, so every subdirectory's hash is
+        // known before its parent is hashed.
+        let mut directories: BTreeMap<String, String> = BTreeMap::new();
+        let mut order: Vec<&String> = children.keys().collect();
+        order.sort_by_key(|dir| std::cmp::Reverse(depth(dir)));
+        for dir in order {
+            let mut entries = children[dir].clone();
+            for (sub, fingerprint) in &directories {
+                let (parent, name) = split_path(sub);
+                if !sub.is_empty() && parent == dir.as_str() {
+                    entries.insert(format!("{name}/"), fingerprint.clone());
+                }
+            }
+            directories.insert(dir.clone(), ps1_fingerprint(&entries)?);
+        }
+
+        let root = directories
+            .get("")
+            .cloned()
+            .context("a module needs at least one file")?;
+        Ok(Self {
+            root,
+            directories,
+            files,
+        })
+    }
+
+    /// Lists the files that differ between this manifest and `current`.
+    pub fn diff(&self, current: &ModuleManifest) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+        if self.root == current.root {
+            return diff;
+        }
+        for (path, fingerprint) in &self.files {
+            match current.files.get(path) {
+                None => diff.removed.push(path.clone()),
+                Some(other) if other != fingerprint => diff.changed.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        for path in current.files.keys() {
+            if !self.files.contains_key(path) {
+                diff.added.push(path.clone());
+            }
+        }
+        diff
+    }
+}
+
+/// Splits `a/b/c.proto` into `("a/b", "c.proto")`.
+fn split_path(path: &str) -> (&str, &str) {
+    path.rsplit_once('/').unwrap_or(("", path))
+}
+
+fn depth(dir: &str) -> usize {
+    if dir.is_empty() {
+        0
+    } else {
+        dir.matches('/').count() + 1
+    }
+}
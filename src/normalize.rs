@@ -13,6 +13,8 @@ use protobuf::descriptor::{
     DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
     FileDescriptorProto, MethodDescriptorProto, ServiceDescriptorProto, field_descriptor_proto,
 };
+use protobuf::{UnknownFields, UnknownValueRef};
+use std::collections::BTreeMap;
 
 pub fn normalize_file(file: &FileDescriptorProto) -> CanonicalFile {
     let mut canonical_file = CanonicalFile {
@@ -104,6 +106,8 @@ pub fn normalize_file(file: &FileDescriptorProto) -> CanonicalFile {
         }
     }
 
+    crate::resolve::resolve_type_references(&mut canonical_file);
+
     canonical_file
 }
 
@@ -181,16 +185,7 @@ fn normalize_field(field: &FieldDescriptorProto) -> CanonicalField {
         field_descriptor_proto::Label::LABEL_REPEATED => "repeated",
     };
 
-    // For primitive types, `type_name` is empty and `type` is set.
-    // For message/enum types, `type_name` is set and `type` is TYPE_MESSAGE/TYPE_ENUM.
-    let type_name = if field.type_name().is_empty() {
-        format!("{:?}", field.type_())
-            .to_lowercase()
-            .replace("type_", "")
-    } else {
-        // Keep the fully qualified name for message/enum types.
-        field.type_name().to_string()
-    };
+    let type_name = field_type_name(field);
 
     // Extract field options
     let mut options = std::collections::BTreeMap::new();
@@ -281,6 +276,7 @@ fn normalize_field(field: &FieldDescriptorProto) -> CanonicalField {
             Some(label.to_string())
         },
         type_name,
+        group: is_group(field),
         oneof_index: field.oneof_index,
         // normalized fast-paths
         default,
@@ -297,6 +293,24 @@ fn normalize_field(field: &FieldDescriptorProto) -> CanonicalField {
     }
 }
 
+/// For primitive types, `type_name` is empty and `type` is set.
+/// For message/enum/group types, `type_name` is set and is kept fully qualified.
+fn field_type_name(field: &FieldDescriptorProto) -> String {
+    if field.type_name().is_empty() {
+        format!("{:?}", field.type_())
+            .to_lowercase()
+            .replace("type_", "")
+    } else {
+        field.type_name().to_string()
+    }
+}
+
+/// Groups share their type name with the generated nested message, so the
+/// group encoding has to be tracked separately to tell them apart.
+fn is_group(field: &FieldDescriptorProto) -> bool {
+    field.type_() == field_descriptor_proto::Type::TYPE_GROUP
+}
+
 fn normalize_enum(en: &EnumDescriptorProto) -> CanonicalEnum {
     let mut canonical_enum = CanonicalEnum {
         name: en.name().to_string(),
@@ -326,25 +340,23 @@ fn normalize_enum(en: &EnumDescriptorProto) -> CanonicalEnum {
 
         // Extract custom options from uninterpreted_option
         for uninterpreted in &enum_options.uninterpreted_option {
-            if let Some(name_part) = uninterpreted.name.first() {
-                if name_part.has_name_part() {
-                    let name = name_part.name_part();
-                    // Handle Protobuf Editions features
-                    if name == "features.json_format" {
-                        if let Some(identifier_value) = uninterpreted.identifier_value.as_ref() {
-                            options.insert("json_format".to_string(), identifier_value.clone());
-                        }
-                    } else {
-                        // Handle other custom options
-                        if let Some(string_value) = uninterpreted.string_value.as_ref() {
-                            if let Ok(string_val) = String::from_utf8(string_value.clone()) {
-                                options.insert(name.to_string(), string_val);
-                            }
-                        } else if let Some(identifier_value) =
-                            uninterpreted.identifier_value.as_ref()
-                        {
-                            options.insert(name.to_string(), identifier_value.clone());
+            if let Some(name_part) = uninterpreted.name.first()
+                && name_part.has_name_part()
+            {
+                let name = name_part.name_part();
+                // Handle Protobuf Editions features
+                if name == "features.json_format" {
+                    if let Some(identifier_value) = uninterpreted.identifier_value.as_ref() {
+                        options.insert("json_format".to_string(), identifier_value.clone());
+                    }
+                } else {
+                    // Handle other custom options
+                    if let Some(string_value) = uninterpreted.string_value.as_ref() {
+                        if let Ok(string_val) = String::from_utf8(string_value.clone()) {
+                            options.insert(name.to_string(), string_val);
                         }
+                    } else if let Some(identifier_value) = uninterpreted.identifier_value.as_ref() {
+                        options.insert(name.to_string(), identifier_value.clone());
                     }
                 }
             }
@@ -381,10 +393,64 @@ fn normalize_enum(en: &EnumDescriptorProto) -> CanonicalEnum {
 }
 
 fn normalize_enum_value(val: &EnumValueDescriptorProto) -> CanonicalEnumValue {
-    CanonicalEnumValue {
+    let mut canonical_value = CanonicalEnumValue {
         name: val.name().to_string(),
         number: val.number(),
+        ..Default::default()
+    };
+
+    if let Some(value_options) = val.options.as_ref() {
+        if value_options.has_deprecated() {
+            canonical_value.deprecated = Some(value_options.deprecated());
+        }
+
+        // `debug_redact` is newer than the bundled descriptor.proto, so it only
+        // shows up as an unknown field when the descriptor came from protoc.
+        let unknown_fields = value_options.special_fields.unknown_fields();
+        if let Some(UnknownValueRef::Varint(v)) =
+            unknown_fields.get(ENUM_VALUE_OPTIONS_DEBUG_REDACT)
+        {
+            canonical_value.debug_redact = Some(v != 0);
+        }
+
+        canonical_value.options = collect_custom_options(unknown_fields);
     }
+
+    canonical_value
+}
+
+/// Field number of `google.protobuf.EnumValueOptions.debug_redact`.
+const ENUM_VALUE_OPTIONS_DEBUG_REDACT: u32 = 3;
+
+/// Renders custom (extension) options, which the parser leaves as unknown
+/// fields, into a map keyed by extension field number.
+fn collect_custom_options(unknown_fields: &UnknownFields) -> BTreeMap<String, String> {
+    let mut options: BTreeMap<String, String> = BTreeMap::new();
+
+    for (number, value) in unknown_fields.iter() {
+        if number == ENUM_VALUE_OPTIONS_DEBUG_REDACT {
+            continue;
+        }
+        let rendered = match value {
+            UnknownValueRef::Fixed32(v) => v.to_string(),
+            UnknownValueRef::Fixed64(v) => v.to_string(),
+            UnknownValueRef::Varint(v) => v.to_string(),
+            UnknownValueRef::LengthDelimited(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) => text.to_string(),
+                Err(_) => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+            },
+        };
+        // Repeated options are joined into a single comma-separated entry.
+        options
+            .entry(number.to_string())
+            .and_modify(|existing| {
+                existing.push(',');
+                existing.push_str(&rendered);
+            })
+            .or_insert(rendered);
+    }
+
+    options
 }
 
 fn normalize_service(svc: &ServiceDescriptorProto) -> CanonicalService {
@@ -411,10 +477,10 @@ fn normalize_method(method: &MethodDescriptorProto) -> CanonicalMethod {
         deprecated: None,
     };
 
-    if let Some(options) = method.options.as_ref() {
-        if options.has_idempotency_level() {
-            m.idempotency_level = Some(format!("{:?}", options.idempotency_level()));
-        }
+    if let Some(options) = method.options.as_ref()
+        && options.has_idempotency_level()
+    {
+        m.idempotency_level = Some(format!("{:?}", options.idempotency_level()));
     }
 
     m
@@ -427,24 +493,15 @@ fn normalize_extension(ext: &FieldDescriptorProto) -> CanonicalExtension {
         field_descriptor_proto::Label::LABEL_REPEATED => "repeated",
     };
 
-    // For primitive types, `type_name` is empty and `type` is set.
-    // For message/enum types, `type_name` is set and `type` is TYPE_MESSAGE/TYPE_ENUM.
-    let type_name = if ext.type_name().is_empty() {
-        format!("{:?}", ext.type_())
-            .to_lowercase()
-            .replace("type_", "")
-    } else {
-        // Keep the fully qualified name for message/enum types.
-        ext.type_name().to_string()
-    };
+    let type_name = field_type_name(ext);
 
     let mut default = None;
     let mut deprecated = None;
 
-    if let Some(ext_options) = ext.options.as_ref() {
-        if ext_options.has_deprecated() {
-            deprecated = Some(ext_options.deprecated());
-        }
+    if let Some(ext_options) = ext.options.as_ref()
+        && ext_options.has_deprecated()
+    {
+        deprecated = Some(ext_options.deprecated());
     }
 
     // Extract default value if present
@@ -457,6 +514,7 @@ fn normalize_extension(ext: &FieldDescriptorProto) -> CanonicalExtension {
         number: ext.number(),
         extendee: ext.extendee().to_string(), // The message being extended
         type_name,
+        group: is_group(ext),
         label: if label == "optional" {
             None
         } else {
@@ -512,17 +570,10 @@ fn normalize_compatibility_message(msg: &DescriptorProto) -> CompatibilityMessag
 }
 
 fn normalize_compatibility_field(field: &FieldDescriptorProto) -> CompatibilityField {
-    let type_name = if field.type_name().is_empty() {
-        format!("{:?}", field.type_())
-            .to_lowercase()
-            .replace("type_", "")
-    } else {
-        field.type_name().to_string()
-    };
-
     CompatibilityField {
         number: field.number(),
-        type_name,
+        type_name: field_type_name(field),
+        group: is_group(field),
     }
 }
 
@@ -0,0 +1,206 @@
+//! Fingerprint profiles.
+//!
+//! A profile selects which attributes of the canonical model participate in a
+//! fingerprint. `exact` hashes everything; `api` ignores deprecation and
+//! code-generation options; `wire` additionally ignores everything that only
+//! affects the JSON mapping or generated code, keeping what determines the
+//! binary encoding. Profile membership of each canonical field is listed in
+//! `docs/fingerprint-format.md`.
+
+use crate::canonical::{
+    CanonicalEnum, CanonicalEnumValue, CanonicalExtension, CanonicalField, CanonicalFile,
+    CanonicalMessage, CanonicalMethod,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Which canonical attributes a fingerprint covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FingerprintProfile {
+    /// Every attribute of the canonical model.
+    #[default]
+    Exact,
+    /// Drops `deprecated`, `debug_redact`, file-level language options and
+    /// C++/Java code-generation hints.
+    Api,
+    /// Like `api`, and also drops `json_name`, `jstype`, defaults, imports,
+    /// method idempotency and custom options. Element names are kept; for a
+    /// name-independent fingerprint use [`crate::wire`].
+    Wire,
+}
+
+impl FingerprintProfile {
+    /// Every profile.
+    pub const ALL: [FingerprintProfile; 3] = [
+        FingerprintProfile::Exact,
+        FingerprintProfile::Api,
+        FingerprintProfile::Wire,
+    ];
+
+    /// The name used in fingerprint prefixes and on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            FingerprintProfile::Exact => "exact",
+            FingerprintProfile::Api => "api",
+            FingerprintProfile::Wire => "wire",
+        }
+    }
+
+    /// Returns the part of `file` this profile covers.
+    pub fn apply(self, file: &CanonicalFile) -> CanonicalFile {
+        let mut file = file.clone();
+        if self == FingerprintProfile::Exact {
+            return file;
+        }
+        let wire = self == FingerprintProfile::Wire;
+
+        file.go_package = None;
+        file.java_package = None;
+        file.csharp_namespace = None;
+        file.ruby_package = None;
+        file.java_multiple_files = None;
+        file.java_outer_classname = None;
+        file.java_string_check_utf8 = None;
+        file.java_generic_services = None;
+        file.objc_class_prefix = None;
+        file.php_class_prefix = None;
+        file.php_namespace = None;
+        file.php_metadata_namespace = None;
+        file.php_generic_services = None;
+        file.swift_prefix = None;
+        file.cc_generic_services = None;
+        file.cc_enable_arenas = None;
+        file.py_generic_services = None;
+        file.optimize_for = None;
+        if wire {
+            file.imports.clear();
+        }
+
+        file.messages = file
+            .messages
+            .into_iter()
+            .map(|m| message(m, wire))
+            .collect();
+        file.enums = file
+            .enums
+            .into_iter()
+            .map(|e| enumeration(e, wire))
+            .collect();
+        file.services = file
+            .services
+            .into_iter()
+            .map(|mut s| {
+                s.methods = s.methods.into_iter().map(|m| method(m, wire)).collect();
+                s
+            })
+            .collect();
+        file.extensions = file
+            .extensions
+            .into_iter()
+            .map(|e| extension(e, wire))
+            .collect();
+        file
+    }
+}
+
+impl fmt::Display for FingerprintProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for FingerprintProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(FingerprintProfile::Exact),
+            "api" => Ok(FingerprintProfile::Api),
+            "wire" => Ok(FingerprintProfile::Wire),
+            other => {
+                anyhow::bail!("unknown fingerprint profile '{other}' (expected exact, api or wire)")
+            }
+        }
+    }
+}
+
+fn message(mut message: CanonicalMessage, wire: bool) -> CanonicalMessage {
+    message.deprecated = None;
+    message.no_standard_descriptor_accessor = None;
+    message.fields = message.fields.into_iter().map(|f| field(f, wire)).collect();
+    message.nested_messages = message
+        .nested_messages
+        .into_iter()
+        .map(|m| self::message(m, wire))
+        .collect();
+    message.nested_enums = message
+        .nested_enums
+        .into_iter()
+        .map(|e| enumeration(e, wire))
+        .collect();
+    message
+}
+
+fn field(mut field: CanonicalField, wire: bool) -> CanonicalField {
+    field.deprecated = None;
+    field.ctype = None;
+    field.cpp_string_type = None;
+    field.java_utf8_validation = None;
+    if wire {
+        field.json_name = None;
+        field.jstype = None;
+        field.default = None;
+        // Only `packed` changes the encoding; the rest mirror typed members
+        // or are custom options.
+        field.options.retain(|key, _| key == "packed");
+    } else {
+        field.options.remove("deprecated");
+        field.options.remove("ctype");
+    }
+    field
+}
+
+fn enumeration(mut enumeration: CanonicalEnum, wire: bool) -> CanonicalEnum {
+    enumeration.deprecated = None;
+    if wire {
+        enumeration.allow_alias = None;
+        enumeration.options = BTreeMap::new();
+    } else {
+        enumeration.options.remove("deprecated");
+    }
+    enumeration.values = enumeration
+        .values
+        .into_iter()
+        .map(|v| enum_value(v, wire))
+        .collect();
+    enumeration
+}
+
+fn enum_value(mut value: CanonicalEnumValue, wire: bool) -> CanonicalEnumValue {
+    value.deprecated = None;
+    value.debug_redact = None;
+    if wire {
+        value.options = BTreeMap::new();
+    } else {
+        value.options.remove("deprecated");
+        value.options.remove("debug_redact");
+    }
+    value
+}
+
+fn method(mut method: CanonicalMethod, wire: bool) -> CanonicalMethod {
+    method.deprecated = None;
+    if wire {
+        method.idempotency_level = None;
+    }
+    method
+}
+
+fn extension(mut extension: CanonicalExtension, wire: bool) -> CanonicalExtension {
+    extension.deprecated = None;
+    if wire {
+        extension.default = None;
+    }
+    extension
+}
@@ -0,0 +1,189 @@
+//! Resolves type references in a `CanonicalFile` to fully-qualified names.
+//!
+//! `protobuf_parse` already emits fully-qualified references (`.pkg.Foo`), but
+//! the text fallback records whatever token appeared in the source (`Foo`,
+//! `pkg.Foo`, `Outer.Inner`). Running every file through this pass makes both
+//! produce the same canonical form, so fingerprints and rules agree.
+
+use crate::canonical::{CanonicalExtension, CanonicalField, CanonicalFile, CanonicalMessage};
+use std::collections::BTreeSet;
+
+/// Rewrites every message, enum and extension type reference in `file` to a
+/// fully-qualified name with a leading dot, following protobuf scoping rules.
+///
+/// References are looked up from the innermost enclosing scope outward. Names
+/// that do not resolve against definitions in the file (typically imported
+/// types) are assumed to be fully-qualified when they contain a dot, and to
+/// live in the file's package otherwise.
+pub fn resolve_type_references(file: &mut CanonicalFile) {
+    let package_scope = match file.package.as_deref() {
+        Some(package) if !package.is_empty() => format!(".{package}"),
+        _ => String::new(),
+    };
+
+    let symbols = collect_symbols(file, &package_scope);
+    let resolver = Resolver {
+        symbols: &symbols,
+        package_scope: &package_scope,
+    };
+
+    file.messages = std::mem::take(&mut file.messages)
+        .into_iter()
+        .map(|message| resolver.resolve_message(message, &package_scope))
+        .collect();
+
+    file.extensions = std::mem::take(&mut file.extensions)
+        .into_iter()
+        .map(|extension| resolver.resolve_extension(extension, &package_scope))
+        .collect();
+
+    file.services = std::mem::take(&mut file.services)
+        .into_iter()
+        .map(|mut service| {
+            service.methods = std::mem::take(&mut service.methods)
+                .into_iter()
+                .map(|mut method| {
+                    method.input_type = resolver.resolve(&method.input_type, &package_scope);
+                    method.output_type = resolver.resolve(&method.output_type, &package_scope);
+                    method
+                })
+                .collect();
+            service
+        })
+        .collect();
+}
+
+/// Collects the fully-qualified names of every package component, message and
+/// enum defined in the file.
+fn collect_symbols(file: &CanonicalFile, package_scope: &str) -> BTreeSet<String> {
+    let mut symbols = BTreeSet::new();
+
+    // Packages are scopes too: `pkg.Foo` resolves by first finding `pkg`.
+    let mut prefix = String::new();
+    for component in package_scope.split('.').filter(|c| !c.is_empty()) {
+        prefix = format!("{prefix}.{component}");
+        symbols.insert(prefix.clone());
+    }
+
+    for en in &file.enums {
+        symbols.insert(format!("{}.{}", package_scope, en.name));
+    }
+
+    fn collect_from_messages(
+        messages: &BTreeSet<CanonicalMessage>,
+        scope: &str,
+        symbols: &mut BTreeSet<String>,
+    ) {
+        for message in messages {
+            let message_name = format!("{}.{}", scope, message.name);
+            for en in &message.nested_enums {
+                symbols.insert(format!("{}.{}", message_name, en.name));
+            }
+            collect_from_messages(&message.nested_messages, &message_name, symbols);
+            symbols.insert(message_name);
+        }
+    }
+
+    collect_from_messages(&file.messages, package_scope, &mut symbols);
+    symbols
+}
+
+struct Resolver<'a> {
+    symbols: &'a BTreeSet<String>,
+    package_scope: &'a str,
+}
+
+impl Resolver<'_> {
+    fn resolve_message(&self, mut message: CanonicalMessage, scope: &str) -> CanonicalMessage {
+        let message_scope = format!("{}.{}", scope, message.name);
+
+        message.fields = std::mem::take(&mut message.fields)
+            .into_iter()
+            .map(|field| self.resolve_field(field, &message_scope))
+            .collect();
+
+        message.nested_messages = std::mem::take(&mut message.nested_messages)
+            .into_iter()
+            .map(|nested| self.resolve_message(nested, &message_scope))
+            .collect();
+
+        message
+    }
+
+    fn resolve_field(&self, mut field: CanonicalField, scope: &str) -> CanonicalField {
+        if field.group && !field.type_name.starts_with('.') && !field.type_name.contains('.') {
+            // A group's type is always the message generated alongside it in
+            // the enclosing scope, even when the fallback never recorded it.
+            field.type_name = format!("{}.{}", scope, field.type_name);
+        } else {
+            field.type_name = self.resolve(&field.type_name, scope);
+        }
+        field
+    }
+
+    fn resolve_extension(
+        &self,
+        mut extension: CanonicalExtension,
+        scope: &str,
+    ) -> CanonicalExtension {
+        extension.extendee = self.resolve(&extension.extendee, scope);
+        extension.type_name = self.resolve(&extension.type_name, scope);
+        extension
+    }
+
+    /// Resolves a single type reference relative to `scope`.
+    fn resolve(&self, name: &str, scope: &str) -> String {
+        if name.is_empty() || name.starts_with('.') || is_scalar_type(name) || name.contains('<') {
+            return name.to_string();
+        }
+
+        // Protobuf resolves only the first component by walking outward through
+        // the enclosing scopes; the remainder is then looked up inside it.
+        let (first, rest) = match name.split_once('.') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (name, None),
+        };
+
+        let mut current_scope = scope;
+        loop {
+            let candidate = format!("{current_scope}.{first}");
+            if self.symbols.contains(&candidate) {
+                return match rest {
+                    Some(rest) => format!("{candidate}.{rest}"),
+                    None => candidate,
+                };
+            }
+            match current_scope.rfind('.') {
+                Some(pos) => current_scope = &current_scope[..pos],
+                None => break,
+            }
+        }
+
+        if rest.is_some() {
+            format!(".{name}")
+        } else {
+            format!("{}.{}", self.package_scope, name)
+        }
+    }
+}
+
+fn is_scalar_type(name: &str) -> bool {
+    matches!(
+        name,
+        "double"
+            | "float"
+            | "int32"
+            | "int64"
+            | "uint32"
+            | "uint64"
+            | "sint32"
+            | "sint64"
+            | "fixed32"
+            | "fixed64"
+            | "sfixed32"
+            | "sfixed64"
+            | "bool"
+            | "string"
+            | "bytes"
+    )
+}
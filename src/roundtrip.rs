@@ -0,0 +1,616 @@
+//! Wire round-trip verification.
+//!
+//! The WIRE and WIRE_JSON rules decide compatibility from type tables. This
+//! module checks it empirically instead: for every field that exists in both
+//! versions of a message, it fills instances of the old message with random
+//! values, encodes them with the old schema and decodes them with the new
+//! one, using `protobuf`'s dynamic messages.
+//!
+//! A field fails the WIRE check when decoding fails or reads back a different
+//! value, e.g. an `int32` read as `bool`, invalid UTF-8 `bytes` read as
+//! `string`, or a value outside a closed enum. It fails the WIRE_JSON check
+//! when its JSON name changes or its value has a different proto3 JSON
+//! representation, e.g. an enum (a name) read as `int32` (a number).
+//!
+//! Fields deleted or added in the new version are not sampled. Sampling is
+//! seeded, so a report is reproducible for the same seed.
+
+use crate::parse;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use protobuf::MessageDyn;
+use protobuf::reflect::{
+    EnumDescriptor, FieldDescriptor, FileDescriptor, MessageDescriptor, ReflectValueBox,
+    ReflectValueRef, RuntimeFieldType, RuntimeType, Syntax,
+};
+use serde::Serialize;
+use serde_json::{Map, Value as Json};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// Nested messages deeper than this only get their required fields.
+const MAX_OPTIONAL_DEPTH: usize = 3;
+
+/// Nested messages deeper than this are left empty.
+const MAX_DEPTH: usize = 8;
+
+/// How many samples to generate and from which seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundTripOptions {
+    /// Samples per field; checking a field stops at its first mismatch.
+    pub samples: usize,
+    pub seed: u64,
+}
+
+impl Default for RoundTripOptions {
+    fn default() -> Self {
+        RoundTripOptions {
+            samples: 32,
+            seed: 0,
+        }
+    }
+}
+
+/// A field whose samples did not survive the round trip.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldMismatch {
+    /// Fully-qualified message name without a leading dot.
+    pub message: String,
+    /// Field name in the old schema.
+    pub field: String,
+    pub number: u32,
+    /// `WIRE` or `WIRE_JSON`, the narrowest category the mismatch breaks.
+    pub category: String,
+    pub problem: String,
+}
+
+/// The outcome of [`verify_round_trip`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RoundTripReport {
+    pub samples: usize,
+    pub seed: u64,
+    /// Fields present in both versions.
+    pub fields_checked: usize,
+    pub mismatches: Vec<FieldMismatch>,
+}
+
+impl RoundTripReport {
+    /// Whether every sampled field read back unchanged on the wire.
+    pub fn is_wire_compatible(&self) -> bool {
+        self.mismatches.iter().all(|m| m.category != "WIRE")
+    }
+
+    /// Whether every sampled field also kept its JSON name and value.
+    pub fn is_wire_json_compatible(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl fmt::Display for RoundTripReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Checked {} fields with {} samples each (seed {}).",
+            self.fields_checked, self.samples, self.seed
+        )?;
+        if self.mismatches.is_empty() {
+            return writeln!(f, "All samples read back unchanged.");
+        }
+        for mismatch in &self.mismatches {
+            writeln!(
+                f,
+                "  [{}] {}.{} ({}): {}",
+                mismatch.category,
+                mismatch.message,
+                mismatch.field,
+                mismatch.number,
+                mismatch.problem
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Encodes random instances of every message in the old file and decodes
+/// them with the new file, reporting the fields that change.
+///
+/// Imports are resolved relative to the directory of each path, as for
+/// [`Spec::try_from_file`](crate::spec::Spec::try_from_file).
+pub fn verify_round_trip(
+    old_content: &str,
+    old_path: Option<&Path>,
+    new_content: &str,
+    new_path: Option<&Path>,
+    options: &RoundTripOptions,
+) -> anyhow::Result<RoundTripReport> {
+    let old = Schema::load(old_content, old_path)?;
+    let new = Schema::load(new_content, new_path)?;
+    let mut rng = fastrand::Rng::with_seed(options.seed);
+
+    let mut report = RoundTripReport {
+        samples: options.samples,
+        seed: options.seed,
+        fields_checked: 0,
+        mismatches: Vec::new(),
+    };
+    for old_message in all_messages(&old.file) {
+        let Some(new_message) = new
+            .file
+            .message_by_full_name(&format!(".{}", old_message.full_name()))
+        else {
+            continue;
+        };
+        for old_field in old_message.fields() {
+            let Some(new_field) = new_message.field_by_number(old_field.number() as u32) else {
+                continue;
+            };
+            report.fields_checked += 1;
+
+            let pair = FieldPair {
+                old: &old_field,
+                new: &new_field,
+                closed_enums: &new.closed_enums,
+            };
+            let problem =
+                (0..options.samples).find_map(|_| pair.check(&mut rng, &old_message, &new_message));
+            if let Some((category, problem)) = problem {
+                report.mismatches.push(FieldMismatch {
+                    message: old_message.full_name().to_string(),
+                    field: old_field.name().to_string(),
+                    number: old_field.number() as u32,
+                    category: category.to_string(),
+                    problem,
+                });
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// The dynamic descriptor of a file and what it needs for comparisons.
+struct Schema {
+    file: FileDescriptor,
+    /// Full names of the enums defined in proto2 files, which are closed.
+    closed_enums: HashSet<String>,
+}
+
+impl Schema {
+    fn load(content: &str, path: Option<&Path>) -> anyhow::Result<Self> {
+        let protos = parse::parse_file_descriptors_with_imports(content, path)?;
+        let files = FileDescriptor::new_dynamic_fds(protos, &[])?;
+
+        let mut closed_enums = HashSet::new();
+        for file in files.iter().filter(|f| f.syntax() == Syntax::Proto2) {
+            let nested = all_messages(file).flat_map(|m| m.nested_enums().collect::<Vec<_>>());
+            for enum_descriptor in file.enums().chain(nested) {
+                closed_enums.insert(enum_descriptor.full_name().to_string());
+            }
+        }
+        let file = files
+            .into_iter()
+            .next()
+            .expect("the input file is parsed first");
+        Ok(Schema { file, closed_enums })
+    }
+}
+
+/// Every message of a file, nested ones included, without map entries.
+fn all_messages(file: &FileDescriptor) -> impl Iterator<Item = MessageDescriptor> + '_ {
+    let mut pending: Vec<MessageDescriptor> = file.messages().collect();
+    std::iter::from_fn(move || {
+        let message = pending.pop()?;
+        pending.extend(message.nested_messages().filter(|m| !m.is_map_entry()));
+        Some(message)
+    })
+}
+
+/// The same field number in the old and new version of a message.
+struct FieldPair<'a> {
+    old: &'a FieldDescriptor,
+    new: &'a FieldDescriptor,
+    closed_enums: &'a HashSet<String>,
+}
+
+impl FieldPair<'_> {
+    /// Round-trips one sample, returning the broken category and problem.
+    fn check(
+        &self,
+        rng: &mut fastrand::Rng,
+        old_message: &MessageDescriptor,
+        new_message: &MessageDescriptor,
+    ) -> Option<(&'static str, String)> {
+        let mut written = old_message.new_instance();
+        for field in old_message.fields() {
+            if field.is_required() && field.number() != self.old.number() {
+                set_random_field(rng, &mut *written, &field, 0);
+            }
+        }
+        set_random_field(rng, &mut *written, self.old, 0);
+        // Only unsatisfiable samples, e.g. required cycles, fail to encode
+        let bytes = written.write_to_bytes_dyn().ok()?;
+
+        let mut decoded = new_message.new_instance();
+        if let Err(e) = decoded.merge_from_bytes_dyn(&bytes) {
+            return Some(("WIRE", format!("decoding failed: {e}")));
+        }
+        // Compare with what old readers see, e.g. without implicit defaults
+        let mut sample = old_message.new_instance();
+        sample.merge_from_bytes_dyn(&bytes).ok()?;
+
+        let old_value = field_value(&*sample, self.old, Some(self.new), self.closed_enums);
+        let new_value = field_value(&*decoded, self.new, Some(self.old), self.closed_enums);
+        if old_value != new_value {
+            return Some(("WIRE", format!("{old_value} read back as {new_value}")));
+        }
+
+        if self.old.json_name() != self.new.json_name() {
+            return Some((
+                "WIRE_JSON",
+                format!(
+                    "JSON name {} changed to {}",
+                    self.old.json_name(),
+                    self.new.json_name()
+                ),
+            ));
+        }
+        let old_json = field_json(&*sample, self.old, Some(self.new));
+        let new_json = field_json(&*decoded, self.new, Some(self.old));
+        if old_json != new_json {
+            return Some((
+                "WIRE_JSON",
+                format!("JSON value {old_json} read back as {new_json}"),
+            ));
+        }
+        None
+    }
+}
+
+// ========================================
+// Sample Generation
+// ========================================
+
+fn set_random_field(
+    rng: &mut fastrand::Rng,
+    message: &mut dyn MessageDyn,
+    field: &FieldDescriptor,
+    depth: usize,
+) {
+    match field.runtime_field_type() {
+        RuntimeFieldType::Singular(runtime_type) => {
+            let value = random_value(rng, &runtime_type, depth);
+            field.set_singular_field(message, value);
+        }
+        RuntimeFieldType::Repeated(runtime_type) => {
+            for _ in 0..rng.usize(1..=3) {
+                let value = random_value(rng, &runtime_type, depth);
+                field.mut_repeated(message).push(value);
+            }
+        }
+        RuntimeFieldType::Map(key_type, value_type) => {
+            for _ in 0..rng.usize(1..=2) {
+                let key = random_value(rng, &key_type, depth);
+                let value = random_value(rng, &value_type, depth);
+                field.mut_map(message).insert(key, value);
+            }
+        }
+    }
+}
+
+fn random_value(
+    rng: &mut fastrand::Rng,
+    runtime_type: &RuntimeType,
+    depth: usize,
+) -> ReflectValueBox {
+    match runtime_type {
+        // Small numbers collide with enum values and booleans
+        RuntimeType::I32 if rng.bool() => ReflectValueBox::I32(rng.i32(-2..=5)),
+        RuntimeType::I64 if rng.bool() => ReflectValueBox::I64(rng.i64(-2..=5)),
+        RuntimeType::U32 if rng.bool() => ReflectValueBox::U32(rng.u32(..=5)),
+        RuntimeType::U64 if rng.bool() => ReflectValueBox::U64(rng.u64(..=5)),
+        RuntimeType::I32 => ReflectValueBox::I32(rng.i32(..)),
+        RuntimeType::I64 => ReflectValueBox::I64(rng.i64(..)),
+        RuntimeType::U32 => ReflectValueBox::U32(rng.u32(..)),
+        RuntimeType::U64 => ReflectValueBox::U64(rng.u64(..)),
+        RuntimeType::F32 => ReflectValueBox::F32(rng.f32() * 2000.0 - 1000.0),
+        RuntimeType::F64 => ReflectValueBox::F64(rng.f64() * 2000.0 - 1000.0),
+        RuntimeType::Bool => ReflectValueBox::Bool(rng.bool()),
+        RuntimeType::String => {
+            const CHARS: &[char] = &['a', 'z', '0', ' ', '_', 'é', 'ß', '中', '✓'];
+            let length = rng.usize(1..=8);
+            ReflectValueBox::String(
+                (0..length)
+                    .map(|_| CHARS[rng.usize(..CHARS.len())])
+                    .collect(),
+            )
+        }
+        RuntimeType::VecU8 => {
+            let length = rng.usize(1..=8);
+            ReflectValueBox::Bytes((0..length).map(|_| rng.u8(..)).collect())
+        }
+        RuntimeType::Enum(enum_descriptor) => {
+            let values: Vec<i32> = enum_descriptor.values().map(|v| v.value()).collect();
+            let number = values
+                .get(rng.usize(..values.len().max(1)))
+                .copied()
+                .unwrap_or(0);
+            ReflectValueBox::Enum(enum_descriptor.clone(), number)
+        }
+        RuntimeType::Message(message_descriptor) => {
+            ReflectValueBox::Message(random_message(rng, message_descriptor, depth + 1))
+        }
+    }
+}
+
+/// A message with its required fields set and, near the top, about half of
+/// its other fields, at most one per oneof.
+fn random_message(
+    rng: &mut fastrand::Rng,
+    descriptor: &MessageDescriptor,
+    depth: usize,
+) -> Box<dyn MessageDyn> {
+    let mut message = descriptor.new_instance();
+    if depth > MAX_DEPTH {
+        return message;
+    }
+    let mut oneofs_set = HashSet::new();
+    for field in descriptor.fields() {
+        let optional = depth <= MAX_OPTIONAL_DEPTH && rng.bool();
+        if !field.is_required() && !optional {
+            continue;
+        }
+        if let Some(oneof) = field.containing_oneof()
+            && !oneofs_set.insert(oneof.name().to_string())
+        {
+            continue;
+        }
+        set_random_field(rng, &mut *message, &field, depth);
+    }
+    message
+}
+
+// ========================================
+// Value Comparison
+// ========================================
+
+/// A decoded value, comparable across wire-compatible types: integers,
+/// booleans and enums compare as numbers, strings and bytes as bytes.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i128),
+    Float(f64),
+    Bytes(Vec<u8>),
+    /// A number a closed enum does not define; readers drop it.
+    UnknownEnum(i32),
+    /// Set fields by number.
+    Message(BTreeMap<u32, Value>),
+    /// The values of a singular or repeated field; empty when unset.
+    List(Vec<Value>),
+    /// Map entries, sorted.
+    Map(Vec<(Value, Value)>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{n}"),
+            Value::Float(n) => write!(f, "{n}"),
+            Value::Bytes(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) => write!(f, "{text:?}"),
+                Err(_) => {
+                    write!(f, "0x")?;
+                    bytes.iter().try_for_each(|b| write!(f, "{b:02x}"))
+                }
+            },
+            Value::UnknownEnum(n) => write!(f, "unknown enum value {n}"),
+            Value::Message(fields) => {
+                write!(f, "{{")?;
+                for (i, (number, value)) in fields.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { ", " };
+                    write!(f, "{separator}{number}: {value}")?;
+                }
+                write!(f, "}}")
+            }
+            Value::List(values) if values.len() == 1 => write!(f, "{}", values[0]),
+            Value::List(values) if values.is_empty() => write!(f, "(unset)"),
+            Value::List(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { ", " };
+                    write!(f, "{separator}{value}")?;
+                }
+                write!(f, "]")
+            }
+            Value::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { ", " };
+                    write!(f, "{separator}{key}: {value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// The value of `field`. Nested messages only include the fields `peer`'s
+/// message type also has, so deletions inside them are not reported here.
+fn field_value(
+    message: &dyn MessageDyn,
+    field: &FieldDescriptor,
+    peer: Option<&FieldDescriptor>,
+    closed_enums: &HashSet<String>,
+) -> Value {
+    let peer_message = peer.and_then(message_type);
+    // A message read as bytes, or the reverse, keeps its encoding
+    let peer_bytes = peer.is_some_and(|peer| {
+        matches!(
+            peer.runtime_field_type(),
+            RuntimeFieldType::Singular(RuntimeType::VecU8)
+                | RuntimeFieldType::Repeated(RuntimeType::VecU8)
+        )
+    });
+    let value = |v: ReflectValueRef| match v {
+        ReflectValueRef::Message(m) if peer_bytes => {
+            Value::Bytes(m.write_to_bytes_dyn().unwrap_or_default())
+        }
+        v => reflect_value(v, peer_message.as_ref(), closed_enums),
+    };
+    match field.runtime_field_type() {
+        RuntimeFieldType::Singular(_) => {
+            Value::List(field.get_singular(message).map(value).into_iter().collect())
+        }
+        RuntimeFieldType::Repeated(_) => {
+            let repeated = field.get_repeated(message);
+            Value::List(
+                (0..repeated.len())
+                    .map(|i| value(repeated.get(i)))
+                    .collect(),
+            )
+        }
+        RuntimeFieldType::Map(..) => {
+            let mut entries: Vec<(Value, Value)> = field
+                .get_map(message)
+                .into_iter()
+                .map(|(k, v)| (reflect_value(k, None, closed_enums), value(v)))
+                .collect();
+            entries.sort_by_key(|(key, _)| key.to_string());
+            Value::Map(entries)
+        }
+    }
+}
+
+fn reflect_value(
+    value: ReflectValueRef,
+    peer: Option<&MessageDescriptor>,
+    closed_enums: &HashSet<String>,
+) -> Value {
+    match value {
+        ReflectValueRef::U32(n) => Value::Int(n.into()),
+        ReflectValueRef::U64(n) => Value::Int(n.into()),
+        ReflectValueRef::I32(n) => Value::Int(n.into()),
+        ReflectValueRef::I64(n) => Value::Int(n.into()),
+        ReflectValueRef::F32(n) => Value::Float(n.into()),
+        ReflectValueRef::F64(n) => Value::Float(n),
+        ReflectValueRef::Bool(b) => Value::Int(b.into()),
+        ReflectValueRef::String(s) => Value::Bytes(s.as_bytes().to_vec()),
+        ReflectValueRef::Bytes(b) => Value::Bytes(b.to_vec()),
+        ReflectValueRef::Enum(descriptor, n) => {
+            if closed_enums.contains(descriptor.full_name())
+                && descriptor.value_by_number(n).is_none()
+            {
+                Value::UnknownEnum(n)
+            } else {
+                Value::Int(n.into())
+            }
+        }
+        ReflectValueRef::Message(message) => {
+            let message: &dyn MessageDyn = &*message;
+            let mut fields = BTreeMap::new();
+            for field in message.descriptor_dyn().fields() {
+                let peer_field = match peer {
+                    Some(peer) => match peer.field_by_number(field.number() as u32) {
+                        Some(peer_field) => Some(peer_field),
+                        None => continue,
+                    },
+                    None => None,
+                };
+                let value = field_value(message, &field, peer_field.as_ref(), closed_enums);
+                if !matches!(&value, Value::List(v) if v.is_empty())
+                    && value != Value::Map(Vec::new())
+                {
+                    fields.insert(field.number() as u32, value);
+                }
+            }
+            Value::Message(fields)
+        }
+    }
+}
+
+fn message_type(field: &FieldDescriptor) -> Option<MessageDescriptor> {
+    match field.runtime_field_type() {
+        RuntimeFieldType::Singular(RuntimeType::Message(m))
+        | RuntimeFieldType::Repeated(RuntimeType::Message(m))
+        | RuntimeFieldType::Map(_, RuntimeType::Message(m)) => Some(m),
+        _ => None,
+    }
+}
+
+/// The proto3 JSON representation of `field`, restricted like [`field_value`].
+fn field_json(
+    message: &dyn MessageDyn,
+    field: &FieldDescriptor,
+    peer: Option<&FieldDescriptor>,
+) -> Json {
+    let peer_message = peer.and_then(message_type);
+    let json = |v: ReflectValueRef| json_value(v, peer_message.as_ref());
+    match field.runtime_field_type() {
+        RuntimeFieldType::Singular(_) => {
+            field.get_singular(message).map(json).unwrap_or(Json::Null)
+        }
+        RuntimeFieldType::Repeated(_) => {
+            let repeated = field.get_repeated(message);
+            Json::Array((0..repeated.len()).map(|i| json(repeated.get(i))).collect())
+        }
+        RuntimeFieldType::Map(..) => Json::Object(
+            field
+                .get_map(message)
+                .into_iter()
+                .map(|(k, v)| {
+                    let key = match json_value(k, None) {
+                        Json::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, json(v))
+                })
+                .collect(),
+        ),
+    }
+}
+
+fn json_value(value: ReflectValueRef, peer: Option<&MessageDescriptor>) -> Json {
+    match value {
+        ReflectValueRef::U32(n) => n.into(),
+        ReflectValueRef::I32(n) => n.into(),
+        // 64-bit integers are strings in proto3 JSON
+        ReflectValueRef::U64(n) => n.to_string().into(),
+        ReflectValueRef::I64(n) => n.to_string().into(),
+        ReflectValueRef::F32(n) => f64::from(n).into(),
+        ReflectValueRef::F64(n) => n.into(),
+        ReflectValueRef::Bool(b) => b.into(),
+        ReflectValueRef::String(s) => s.into(),
+        ReflectValueRef::Bytes(b) => BASE64.encode(b).into(),
+        ReflectValueRef::Enum(descriptor, n) => enum_json(&descriptor, n),
+        ReflectValueRef::Message(message) => {
+            let message: &dyn MessageDyn = &*message;
+            let mut object = Map::new();
+            for field in message.descriptor_dyn().fields() {
+                let peer_field = match peer {
+                    Some(peer) => match peer.field_by_number(field.number() as u32) {
+                        Some(peer_field) => Some(peer_field),
+                        None => continue,
+                    },
+                    None => None,
+                };
+                let value = field_json(message, &field, peer_field.as_ref());
+                let empty = match &value {
+                    Json::Null => true,
+                    Json::Array(items) => items.is_empty(),
+                    Json::Object(entries) => entries.is_empty(),
+                    _ => false,
+                };
+                if !empty {
+                    object.insert(field.json_name().to_string(), value);
+                }
+            }
+            Json::Object(object)
+        }
+    }
+}
+
+fn enum_json(descriptor: &EnumDescriptor, number: i32) -> Json {
+    match descriptor.value_by_number(number) {
+        Some(value) => value.name().into(),
+        None => number.into(),
+    }
+}
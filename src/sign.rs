@@ -0,0 +1,169 @@
+//! Detached ed25519 signatures over semantic fingerprints.
+//!
+//! A signature covers a file's fingerprint rather than its bytes, so it stays
+//! valid when the file is reformatted, reordered or re-commented, and breaks
+//! as soon as the canonical form changes. Keys are plain PKCS#8 / SPKI PEM
+//! files and are generated locally.
+
+use crate::canonical::CanonicalFile;
+use crate::fingerprint::{encode_value, ensure_cryptographic, matches_cryptographic_fingerprint};
+use anyhow::Context;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use ed25519_dalek::{Signature, Signer, Verifier};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+/// Version of the [`DetachedSignature`] format and signed payload.
+pub const SIGNATURE_FORMAT_VERSION: u32 = 1;
+
+/// A detached signature over a file's fingerprint and signing metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetachedSignature {
+    pub format_version: u32,
+    /// The signed fingerprint, e.g. `ps1-sha256:…`.
+    pub fingerprint: String,
+    /// Signing time in seconds since the Unix epoch.
+    pub signed_at: u64,
+    /// Free-form identity of the signer, e.g. a team or e-mail address.
+    pub signer_id: String,
+    /// [`key_id`] of the key that produced the signature.
+    pub key_id: String,
+    /// Base64 ed25519 signature over [`DetachedSignature::payload`].
+    pub signature: String,
+}
+
+impl DetachedSignature {
+    /// Signs `fingerprint` with `key`, recording the signer and the time.
+    ///
+    /// Fails for `xxh3` and legacy fingerprints, which are not collision
+    /// resistant.
+    pub fn sign(
+        fingerprint: &str,
+        key: &SigningKey,
+        signer_id: &str,
+        signed_at: u64,
+    ) -> anyhow::Result<Self> {
+        ensure_cryptographic(fingerprint)?;
+        let mut signature = DetachedSignature {
+            format_version: SIGNATURE_FORMAT_VERSION,
+            fingerprint: fingerprint.to_string(),
+            signed_at,
+            signer_id: signer_id.to_string(),
+            key_id: key_id(&key.verifying_key()),
+            signature: String::new(),
+        };
+        let payload = signature.payload()?;
+        signature.signature = BASE64.encode(key.sign(payload.as_bytes()).to_bytes());
+        Ok(signature)
+    }
+
+    /// Signs the fingerprint of `canonical_file`, timestamped now.
+    pub fn sign_file(
+        canonical_file: &CanonicalFile,
+        key: &SigningKey,
+        signer_id: &str,
+    ) -> anyhow::Result<Self> {
+        let fingerprint = crate::fingerprint_canonical_file(canonical_file)?;
+        let signed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("system clock is before the Unix epoch")?
+            .as_secs();
+        Self::sign(&fingerprint, key, signer_id, signed_at)
+    }
+
+    /// The bytes that are signed: the `ps1` encoding of every member except
+    /// `signature`.
+    pub fn payload(&self) -> anyhow::Result<String> {
+        encode_value(&serde_json::json!({
+            "format_version": self.format_version,
+            "fingerprint": self.fingerprint,
+            "signed_at": self.signed_at,
+            "signer_id": self.signer_id,
+            "key_id": self.key_id,
+        }))
+    }
+
+    /// Checks that the signature was made by `key` and that its fingerprint
+    /// is the fingerprint of `canonical_file`. Signatures over `xxh3` or
+    /// legacy fingerprints are rejected.
+    pub fn verify(&self, canonical_file: &CanonicalFile, key: &VerifyingKey) -> anyhow::Result<()> {
+        if self.format_version != SIGNATURE_FORMAT_VERSION {
+            anyhow::bail!(
+                "unsupported signature format version {} (expected {SIGNATURE_FORMAT_VERSION})",
+                self.format_version
+            );
+        }
+        let expected_key = key_id(key);
+        if self.key_id != expected_key {
+            anyhow::bail!(
+                "signature was made with key {}, not {expected_key}",
+                self.key_id
+            );
+        }
+
+        let bytes = BASE64
+            .decode(&self.signature)
+            .context("signature is not valid base64")?;
+        let signature = Signature::from_slice(&bytes).context("signature has the wrong length")?;
+        key.verify(self.payload()?.as_bytes(), &signature)
+            .map_err(|_| anyhow::anyhow!("signature does not match its contents"))?;
+
+        if !matches_cryptographic_fingerprint(canonical_file, &self.fingerprint)? {
+            anyhow::bail!(
+                "file does not match the signed fingerprint {}",
+                self.fingerprint
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Generates a new signing key from the operating system's random source.
+pub fn generate_signing_key() -> SigningKey {
+    SigningKey::generate(&mut rand_core::OsRng)
+}
+
+/// A short identifier for a public key: `ed25519:` followed by the first 16
+/// hex digits of the SHA-256 of the raw key.
+pub fn key_id(key: &VerifyingKey) -> String {
+    let digest = Sha256::digest(key.as_bytes());
+    let hex: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+    format!("ed25519:{hex}")
+}
+
+/// Encodes a signing key as a PKCS#8 PEM document.
+pub fn signing_key_to_pem(key: &SigningKey) -> anyhow::Result<String> {
+    let pem = key
+        .to_pkcs8_pem(LineEnding::LF)
+        .map_err(|e| anyhow::anyhow!("failed to encode private key: {e}"))?;
+    Ok(pem.to_string())
+}
+
+/// Encodes a public key as an SPKI PEM document.
+pub fn verifying_key_to_pem(key: &VerifyingKey) -> anyhow::Result<String> {
+    key.to_public_key_pem(LineEnding::LF)
+        .map_err(|e| anyhow::anyhow!("failed to encode public key: {e}"))
+}
+
+/// Reads a signing key from a PKCS#8 PEM document.
+pub fn signing_key_from_pem(pem: &str) -> anyhow::Result<SigningKey> {
+    SigningKey::from_pkcs8_pem(pem)
+        .map_err(|e| anyhow::anyhow!("not an ed25519 private key in PKCS#8 PEM: {e}"))
+}
+
+/// Reads a public key from an SPKI PEM document. A private key is accepted
+/// too, in which case its public half is used.
+pub fn verifying_key_from_pem(pem: &str) -> anyhow::Result<VerifyingKey> {
+    if let Ok(key) = VerifyingKey::from_public_key_pem(pem) {
+        return Ok(key);
+    }
+    signing_key_from_pem(pem)
+        .map(|key| key.verifying_key())
+        .map_err(|_| anyhow::anyhow!("not an ed25519 public key in SPKI PEM"))
+}
@@ -0,0 +1,172 @@
+//! Source providers that supply `.proto` content to the parser.
+//!
+//! Imports are looked up through a [`SourceProvider`] rather than read from
+//! disk by the parser. [`VirtualFileMap`] serves files from memory, so a
+//! `Spec` can be built from strings or byte buffers without touching the
+//! filesystem.
+
+use crate::parse::ParseError;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Supplies the content of `.proto` files by import path.
+pub trait SourceProvider {
+    /// Returns the content of the file at `path` (as written in an `import`
+    /// statement), or `None` if the provider does not know it.
+    fn read_source(&self, path: &str) -> std::io::Result<Option<Cow<'_, str>>>;
+}
+
+/// An in-memory map from import path to file content.
+///
+/// Paths use forward slashes and are relative to the import root, exactly as
+/// they appear in `import` statements, e.g. `acme/v1/order.proto`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VirtualFileMap {
+    files: BTreeMap<String, String>,
+}
+
+impl VirtualFileMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a file, returning the previous content if any.
+    pub fn insert(
+        &mut self,
+        path: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Option<String> {
+        self.files.insert(path.into(), content.into())
+    }
+
+    /// Adds or replaces a file from raw bytes, which must be valid UTF-8.
+    pub fn insert_bytes(
+        &mut self,
+        path: impl Into<String>,
+        bytes: impl Into<Vec<u8>>,
+    ) -> Result<Option<String>, ParseError> {
+        let path = path.into();
+        let content = String::from_utf8(bytes.into()).map_err(|e| ParseError::Io {
+            file: path.clone(),
+            message: format!("file is not valid UTF-8: {e}"),
+        })?;
+        Ok(self.insert(path, content))
+    }
+
+    /// Loads every `.proto` file below `dir`, keyed by its path relative to
+    /// `dir`. Hidden directories such as `.git` are skipped.
+    pub fn from_dir(dir: &Path) -> Result<Self, ParseError> {
+        let mut map = Self::new();
+        map.add_dir(dir, "")?;
+        Ok(map)
+    }
+
+    fn add_dir(&mut self, dir: &Path, prefix: &str) -> Result<(), ParseError> {
+        let io_error = |path: &Path, e: std::io::Error| ParseError::Io {
+            file: path.display().to_string(),
+            message: e.to_string(),
+        };
+        let entries = std::fs::read_dir(dir).map_err(|e| io_error(dir, e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| io_error(dir, e))?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            let relative = if prefix.is_empty() {
+                name
+            } else {
+                format!("{prefix}/{name}")
+            };
+            if path.is_dir() {
+                self.add_dir(&path, &relative)?;
+            } else if path.extension().is_some_and(|ext| ext == "proto") {
+                let bytes = std::fs::read(&path).map_err(|e| io_error(&path, e))?;
+                self.insert_bytes(relative, bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the content of the file at `path`.
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+
+    /// Iterates over all paths in the map, in sorted order.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// Returns the number of files in the map.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns true if the map holds no files.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl<P: Into<String>, C: Into<String>> FromIterator<(P, C)> for VirtualFileMap {
+    fn from_iter<I: IntoIterator<Item = (P, C)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (path, content) in iter {
+            map.insert(path, content);
+        }
+        map
+    }
+}
+
+impl SourceProvider for VirtualFileMap {
+    fn read_source(&self, path: &str) -> std::io::Result<Option<Cow<'_, str>>> {
+        Ok(self.get(path).map(Cow::Borrowed))
+    }
+}
+
+/// Reads imports from disk, relative to the importing file and then to any
+/// explicit include directories.
+pub(crate) struct FileSystemSources {
+    roots: Vec<PathBuf>,
+}
+
+impl FileSystemSources {
+    /// Import roots for a file at `file_path`: only its directory, so that
+    /// the result does not depend on the working directory.
+    ///
+    /// Content without a path gets no roots, so its imports are always
+    /// stubbed.
+    pub(crate) fn for_file(file_path: Option<&Path>) -> Self {
+        match file_path {
+            Some(file_path) => Self::with_includes(file_path, &[]),
+            None => Self { roots: Vec::new() },
+        }
+    }
+
+    /// Import roots for a file at `file_path` followed by `include_dirs`.
+    pub(crate) fn with_includes(file_path: &Path, include_dirs: &[PathBuf]) -> Self {
+        let roots = file_path
+            .parent()
+            .map(Path::to_path_buf)
+            .into_iter()
+            .chain(include_dirs.iter().cloned())
+            .collect();
+        Self { roots }
+    }
+}
+
+impl SourceProvider for FileSystemSources {
+    fn read_source(&self, path: &str) -> std::io::Result<Option<Cow<'_, str>>> {
+        for root in &self.roots {
+            let candidate = root.join(path);
+            if candidate.exists() {
+                return std::fs::read_to_string(&candidate).map(|c| Some(Cow::Owned(c)));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -1,12 +1,19 @@
 //! Provides the high-level Spec API for comparing Protobuf files.
 
 use crate::compat::{BreakingConfig, BreakingEngine, BreakingResult};
-use crate::compatibility::{CompatibilityModel, get_compatibility_model};
-use crate::generate_fingerprint;
-use std::collections::BTreeMap;
+use crate::compatibility::CompatibilityModel;
+use crate::diff::{CanonicalDiff, ChangeClass};
+use crate::fingerprint_canonical_file;
+use crate::parse::{ParseError, ParseMode, preprocess_proto_content};
+use crate::profile::FingerprintProfile;
+use crate::source::VirtualFileMap;
+use protobuf::descriptor::FileDescriptorProto;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 /// The result of a compatibility comparison between two Protobuf specifications.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Compatibility {
     /// The two specifications are semantically identical.
     Green,
@@ -16,6 +23,53 @@ pub enum Compatibility {
     Red,
 }
 
+impl Compatibility {
+    /// Derives the result of a comparison from its breaking check: Green if
+    /// the two sides are `identical`, Red if `result` has a breaking change,
+    /// and Yellow otherwise.
+    pub fn from_breaking_result(identical: bool, result: &BreakingResult) -> Self {
+        if identical {
+            Compatibility::Green
+        } else if result.has_breaking_changes {
+            Compatibility::Red
+        } else {
+            Compatibility::Yellow
+        }
+    }
+}
+
+/// The semantic-version bump a schema change calls for, in increasing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionBump {
+    /// Nothing in the API changed: formatting, comments, declaration order,
+    /// or options outside the `api` fingerprint profile.
+    Patch,
+    /// The API changed without breaking the selected rules, e.g. a field was added.
+    Minor,
+    /// The selected rules report a breaking change.
+    Major,
+}
+
+impl fmt::Display for VersionBump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            VersionBump::Patch => "patch",
+            VersionBump::Minor => "minor",
+            VersionBump::Major => "major",
+        })
+    }
+}
+
+/// A recommended [`VersionBump`] and the changes that call for it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionRecommendation {
+    pub bump: VersionBump,
+    /// The breaking changes for `major`, the API changes for `minor`, and the
+    /// remaining semantic changes, if any, for `patch`.
+    pub reasons: Vec<String>,
+}
+
 /// Represents a single Protobuf specification, holding its content and derived models for comparison.
 #[derive(Debug)]
 pub struct Spec<'a> {
@@ -27,6 +81,12 @@ pub struct Spec<'a> {
     pub compatibility_model: CompatibilityModel,
     /// The canonical representation for detailed breaking change analysis.
     pub canonical_file: crate::canonical::CanonicalFile,
+    /// The parse error that forced a text-based fallback, if any.
+    ///
+    /// Only set when the spec was built with [`ParseMode::BestEffort`]. Results
+    /// derived from a degraded spec may miss elements and should not be trusted
+    /// for gating.
+    pub degraded: Option<ParseError>,
 }
 
 impl<'a> Spec<'a> {
@@ -34,63 +94,107 @@ impl<'a> Spec<'a> {
     ///
     /// This function parses the content and generates the necessary fingerprint and models,
     /// so it should be called once per file.
-    pub fn try_from(content: &'a str) -> anyhow::Result<Self> {
-        let fingerprint = generate_fingerprint(content)?;
-        let compatibility_model = get_compatibility_model(content)?;
-        let canonical_file = parse_canonical_file(content)?;
-        Ok(Spec {
-            content,
-            fingerprint,
-            compatibility_model,
-            canonical_file,
-        })
+    pub fn try_from(content: &'a str) -> Result<Self, ParseError> {
+        Self::try_from_path(None, content)
     }
 
     /// Creates a new `Spec` from a .proto file path.
     ///
     /// This variant provides file path context for better import resolution.
-    pub fn try_from_file(file_path: &std::path::Path, content: &'a str) -> anyhow::Result<Self> {
-        // Try normal parsing first
-        match Self::try_from_file_internal(file_path, content) {
+    pub fn try_from_file(
+        file_path: &std::path::Path,
+        content: &'a str,
+    ) -> Result<Self, ParseError> {
+        Self::try_from_path(Some(file_path), content)
+    }
+
+    /// Creates a new `Spec` from a .proto file path using the given parse mode.
+    ///
+    /// With [`ParseMode::BestEffort`], a file the parser rejects is approximated
+    /// by simple text parsing instead of failing, and the returned spec is
+    /// marked as [degraded](Spec::is_degraded).
+    pub fn try_from_file_with_mode(
+        file_path: &std::path::Path,
+        content: &'a str,
+        mode: ParseMode,
+    ) -> Result<Self, ParseError> {
+        match Self::try_from_path(Some(file_path), content) {
             Ok(spec) => Ok(spec),
-            Err(e) => {
-                eprintln!(
-                    "Warning: Proto parsing failed, using fallback for {}: {}",
-                    file_path.display(),
-                    e
-                );
-                // Fallback: create a minimal spec with basic parsing
-                Ok(Self::create_fallback_spec(content))
-            }
+            Err(e) if mode == ParseMode::BestEffort => Ok(Self::create_fallback_spec(content, e)),
+            Err(e) => Err(e),
         }
     }
 
-    /// Internal try_from_file implementation without fallback
-    fn try_from_file_internal(
-        file_path: &std::path::Path,
+    /// Returns true if this spec was approximated after a parse failure.
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.is_some()
+    }
+
+    /// Creates a new `Spec` for the file at `path` in an in-memory file map.
+    ///
+    /// Imports are resolved from the same map and parsing happens entirely in
+    /// memory, so this works without any filesystem access.
+    pub fn try_from_sources(sources: &'a VirtualFileMap, path: &str) -> Result<Self, ParseError> {
+        let content = sources.get(path).ok_or_else(|| ParseError::Io {
+            file: path.to_string(),
+            message: "file is not in the source map".to_string(),
+        })?;
+        let descriptor = crate::parse::parse_with_sources(content, path, sources)?;
+        Self::from_descriptor(content, &descriptor, path)
+    }
+
+    /// Creates a new `Spec` for the file at `path` in an in-memory file map
+    /// using the given parse mode, as [`Spec::try_from_file_with_mode`] does.
+    pub fn try_from_sources_with_mode(
+        sources: &'a VirtualFileMap,
+        path: &str,
+        mode: ParseMode,
+    ) -> Result<Self, ParseError> {
+        match Self::try_from_sources(sources, path) {
+            Ok(spec) => Ok(spec),
+            Err(e) if mode == ParseMode::BestEffort => match sources.get(path) {
+                Some(content) => Ok(Self::create_fallback_spec(content, e)),
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn try_from_path(
+        file_path: Option<&std::path::Path>,
         content: &'a str,
-    ) -> anyhow::Result<Self> {
-        let fingerprint = generate_fingerprint(content)?;
-        let compatibility_model = get_compatibility_model(content)?;
-        let canonical_file = parse_canonical_file_with_context(content, Some(file_path))?;
+    ) -> Result<Self, ParseError> {
+        let descriptor = crate::parse::parse_file_descriptor(content, file_path)?;
+        let display_name = file_path
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "<input>".to_string());
+        Self::from_descriptor(content, &descriptor, &display_name)
+    }
+
+    /// Derives every model from a single parsed descriptor.
+    fn from_descriptor(
+        content: &'a str,
+        descriptor: &FileDescriptorProto,
+        display_name: &str,
+    ) -> Result<Self, ParseError> {
+        let canonical_file = crate::normalize::normalize_file(descriptor);
+        let compatibility_model = crate::normalize::normalize_compatibility_file(descriptor);
+        let fingerprint =
+            fingerprint_canonical_file(&canonical_file).map_err(|e| ParseError::Other {
+                file: display_name.to_string(),
+                message: format!("{e:#}"),
+            })?;
         Ok(Spec {
             content,
             fingerprint,
             compatibility_model,
             canonical_file,
+            degraded: None,
         })
     }
 
     /// Create a fallback spec when parsing fails
-    fn create_fallback_spec(content: &'a str) -> Self {
-        // Use simplified fingerprint (just length + first/last chars as a basic hash)
-        let fingerprint = format!(
-            "fallback_{}_{}_{}",
-            content.len(),
-            content.chars().next().unwrap_or('_') as u32,
-            content.chars().last().unwrap_or('_') as u32
-        );
-
+    fn create_fallback_spec(content: &'a str, error: ParseError) -> Self {
         // Use a minimal compatibility model
         let compatibility_model = CompatibilityModel {
             messages: std::collections::BTreeSet::new(),
@@ -98,34 +202,92 @@ impl<'a> Spec<'a> {
         };
 
         // Use the fallback canonical file parser
-        let canonical_file = create_fallback_canonical_file(content);
+        let preprocessed = preprocess_proto_content(content);
+        let canonical_file = create_fallback_canonical_file(&preprocessed);
+
+        // Fingerprint the approximated model like a parsed one, so that equal
+        // fingerprints always mean the breaking rules find nothing
+        let fingerprint = fingerprint_canonical_file(&canonical_file)
+            .unwrap_or_else(|_| crate::fingerprint::sha256_hex(preprocessed.as_bytes()));
 
         Spec {
             content,
             fingerprint,
             compatibility_model,
             canonical_file,
+            degraded: Some(error),
         }
     }
 
     /// Compares this `Spec` (the "old" version) with another `Spec` (the "new" version)
-    /// to determine their compatibility level.
+    /// to determine their compatibility level, using the default rule categories.
     pub fn compare_with(&self, new_spec: &Spec) -> Compatibility {
+        self.compare_with_config(new_spec, &BreakingConfig::default())
+    }
+
+    /// Compares this `Spec` with a new version under the rules selected by `config`.
+    ///
+    /// The result is Green if the fingerprints are equal, Red if
+    /// [`check_breaking_changes_with_config`](Spec::check_breaking_changes_with_config)
+    /// reports any breaking change, and Yellow otherwise, so `compare` and
+    /// `breaking` never disagree.
+    pub fn compare_with_config(&self, new_spec: &Spec, config: &BreakingConfig) -> Compatibility {
         // If the exact fingerprints are identical, the files are semantically identical.
         if self.fingerprint == new_spec.fingerprint {
             return Compatibility::Green;
         }
+        let result = self.check_breaking_changes_with_config(new_spec, config);
+        Compatibility::from_breaking_result(false, &result)
+    }
+
+    /// Recommends a semantic-version bump for moving from this `Spec` to a new
+    /// version under the rules selected by `config`.
+    ///
+    /// `major` corresponds to Red in
+    /// [`compare_with_config`](Spec::compare_with_config). Otherwise the
+    /// result is `minor` if the [`CanonicalDiff`] of the `api` profiles of both
+    /// versions has a non-cosmetic change, and `patch` if not; Green is always
+    /// `patch`.
+    pub fn recommend_version_bump(
+        &self,
+        new_spec: &Spec,
+        config: &BreakingConfig,
+    ) -> VersionRecommendation {
+        let breaking = self.check_breaking_changes_with_config(new_spec, config);
+        if breaking.has_breaking_changes {
+            return VersionRecommendation {
+                bump: VersionBump::Major,
+                reasons: breaking
+                    .changes
+                    .iter()
+                    .map(|change| format!("{}: {}", change.rule_id, change.message))
+                    .collect(),
+            };
+        }
 
-        // If the fingerprints differ, check for backward compatibility.
-        if crate::compatibility::is_compatible(
-            &self.compatibility_model,
-            &new_spec.compatibility_model,
-        ) {
-            return Compatibility::Yellow;
+        let api = FingerprintProfile::Api;
+        let api_diff = CanonicalDiff::between(
+            &api.apply(&self.canonical_file),
+            &api.apply(&new_spec.canonical_file),
+        );
+        let api_changes: Vec<String> = api_diff
+            .entries
+            .iter()
+            .filter(|entry| entry.class != ChangeClass::Cosmetic)
+            .map(ToString::to_string)
+            .collect();
+        if !api_changes.is_empty() {
+            return VersionRecommendation {
+                bump: VersionBump::Minor,
+                reasons: api_changes,
+            };
         }
 
-        // If it's not identical and not compatible, it's a breaking change.
-        Compatibility::Red
+        let diff = CanonicalDiff::between(&self.canonical_file, &new_spec.canonical_file);
+        VersionRecommendation {
+            bump: VersionBump::Patch,
+            reasons: diff.entries.iter().map(ToString::to_string).collect(),
+        }
     }
 
     /// Perform detailed breaking change analysis using the Buf-compatible rule system
@@ -144,198 +306,6 @@ impl<'a> Spec<'a> {
     }
 }
 
-/// Parse a proto file content into a canonical file representation
-fn parse_canonical_file(proto_content: &str) -> anyhow::Result<crate::canonical::CanonicalFile> {
-    parse_canonical_file_with_context(proto_content, None)
-}
-
-/// Parse a proto file content with optional file path context for imports
-fn parse_canonical_file_with_context(
-    proto_content: &str,
-    file_path_context: Option<&std::path::Path>,
-) -> anyhow::Result<crate::canonical::CanonicalFile> {
-    use anyhow::Context;
-
-    let temp_dir = tempfile::tempdir().context("Failed to create temp directory")?;
-    let file_name = "input.proto";
-    let temp_path = temp_dir.path().join(file_name);
-
-    // Pre-process proto content to handle unsupported syntax
-    let processed_content = preprocess_proto_content(proto_content);
-    std::fs::write(&temp_path, &processed_content).context("Failed to write to temp file")?;
-
-    // Handle imports - try to find actual files first, then create dummy files
-    for line in proto_content.lines() {
-        if line.trim().starts_with("import ") {
-            let path_str = line
-                .trim()
-                .trim_start_matches("import ")
-                .trim_start_matches("public ")
-                .trim_start_matches("weak ")
-                .trim_matches(|c| c == '"' || c == ';');
-
-            if !path_str.starts_with("google/protobuf/") {
-                let import_path = temp_dir.path().join(path_str);
-                if let Some(parent) = import_path.parent() {
-                    std::fs::create_dir_all(parent).context(format!(
-                        "Failed to create parent dirs for import: {path_str}"
-                    ))?;
-                }
-
-                // Try to find the actual import file
-                let mut found = false;
-
-                // First, try relative to the file being parsed if we have context
-                if let Some(context_path) = file_path_context {
-                    if let Some(parent_dir) = context_path.parent() {
-                        let actual_import_path = parent_dir.join(path_str);
-                        if actual_import_path.exists() {
-                            let import_content = std::fs::read_to_string(&actual_import_path)
-                                .context(format!("Failed to read import file: {path_str}"))?;
-                            let processed_import = preprocess_proto_content(&import_content);
-                            std::fs::write(&import_path, processed_import)
-                                .context(format!("Failed to copy import file: {path_str}"))?;
-                            found = true;
-                        }
-                    }
-                }
-
-                // If not found with context, try current working directory
-                if !found {
-                    let current_dir = std::path::Path::new(".");
-                    let actual_import_path = current_dir.join(path_str);
-
-                    if actual_import_path.exists() {
-                        let import_content = std::fs::read_to_string(&actual_import_path)
-                            .context(format!("Failed to read import file: {path_str}"))?;
-                        let processed_import = preprocess_proto_content(&import_content);
-                        std::fs::write(&import_path, processed_import)
-                            .context(format!("Failed to copy import file: {path_str}"))?;
-                        found = true;
-                    }
-                }
-
-                // Fallback: create a dummy proto3 file
-                if !found {
-                    std::fs::write(&import_path, "syntax = \"proto3\";")
-                        .context(format!("Failed to create dummy import file: {path_str}"))?;
-                }
-            }
-        }
-    }
-
-    // Attempt parsing with error recovery
-    match try_parse_with_fallback(&temp_dir, &temp_path, file_name, &processed_content) {
-        Ok(canonical_file) => Ok(canonical_file),
-        Err(e) => {
-            eprintln!("Warning: Proto parsing failed, using fallback: {e}");
-            Ok(create_fallback_canonical_file(&processed_content))
-        }
-    }
-}
-
-/// Preprocess proto content to handle unsupported syntax and edge cases
-fn preprocess_proto_content(content: &str) -> String {
-    let lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-    let mut processed_lines = Vec::new();
-
-    // Detect if this is an editions file and convert to proto3
-    let mut is_editions = false;
-    let mut has_syntax_declaration = false;
-
-    for line in &lines {
-        let trimmed = line.trim();
-        if trimmed.starts_with("edition =") {
-            is_editions = true;
-            // Convert edition to proto3
-            processed_lines.push("syntax = \"proto3\";".to_string());
-            continue;
-        } else if trimmed.starts_with("syntax =") {
-            has_syntax_declaration = true;
-        }
-    }
-
-    // If no syntax declaration and not editions, default to proto2
-    if !has_syntax_declaration && !is_editions {
-        processed_lines.insert(0, "syntax = \"proto2\";".to_string());
-        processed_lines.insert(1, "".to_string());
-    }
-
-    for line in &lines {
-        let trimmed = line.trim();
-
-        // Skip edition lines (already handled)
-        if trimmed.starts_with("edition =") {
-            continue;
-        }
-
-        // Convert editions-specific features to proto3 equivalents
-        if is_editions {
-            let converted_line = convert_editions_features(line);
-            processed_lines.push(converted_line);
-        } else {
-            processed_lines.push(line.clone());
-        }
-    }
-
-    processed_lines.join("\n")
-}
-
-/// Convert Protobuf Editions features to proto3 equivalent syntax
-fn convert_editions_features(line: &str) -> String {
-    let mut result = line.to_string();
-
-    // Convert [features.field_presence = LEGACY_REQUIRED] to similar proto2/proto3 syntax
-    if result.contains("[features.field_presence = LEGACY_REQUIRED]") {
-        // Remove the feature annotation for now - this is a simplification
-        result = result.replace("[features.field_presence = LEGACY_REQUIRED]", "");
-        result = result.trim_end().to_string();
-        if result.ends_with(' ') {
-            result = result.trim_end().to_string();
-        }
-    }
-
-    // Convert other editions features as needed
-    if result.contains("[features.") {
-        // For now, remove unsupported features annotations
-        if let Some(start) = result.find("[features.") {
-            if let Some(end) = result[start..].find(']') {
-                let before = &result[..start];
-                let after = &result[start + end + 1..];
-                result = format!("{}{}", before.trim_end(), after);
-            }
-        }
-    }
-
-    result
-}
-
-/// Attempt to parse proto files with error recovery
-fn try_parse_with_fallback(
-    temp_dir: &tempfile::TempDir,
-    temp_path: &std::path::Path,
-    file_name: &str,
-    _processed_content: &str,
-) -> anyhow::Result<crate::canonical::CanonicalFile> {
-    use anyhow::Context;
-    use protobuf_parse::Parser;
-
-    let parsed = Parser::new()
-        .pure()
-        .include(temp_dir.path())
-        .input(temp_path)
-        .file_descriptor_set()
-        .context("Protobuf parsing failed")?;
-
-    let file_descriptor = parsed
-        .file
-        .into_iter()
-        .find(|d| d.name() == file_name)
-        .context("Could not find the parsed file descriptor for the input file")?;
-
-    Ok(crate::normalize::normalize_file(&file_descriptor))
-}
-
 /// Create a fallback canonical file when parsing fails
 /// This extracts basic information using simple text parsing
 fn create_fallback_canonical_file(content: &str) -> crate::canonical::CanonicalFile {
@@ -382,40 +352,46 @@ fn create_fallback_canonical_file(content: &str) -> crate::canonical::CanonicalF
     let mut current_message = String::new();
     let mut current_enum = String::new();
     let mut current_enum_options = BTreeMap::new();
+    let mut current_enum_values = BTreeSet::new();
     let mut brace_count = 0;
+    let extension_numbers = enum_value_extension_numbers(content);
 
     for line in content.lines() {
         let trimmed = line.trim();
 
         // Handle message parsing
-        if trimmed.starts_with("message ") && !in_enum {
-            if let Some(name_part) = trimmed.strip_prefix("message ") {
-                if let Some(space_pos) = name_part.find(' ') {
-                    current_message = name_part[..space_pos].to_string();
-                } else if let Some(brace_pos) = name_part.find('{') {
-                    current_message = name_part[..brace_pos].trim().to_string();
-                } else {
-                    current_message = name_part.trim().to_string();
-                }
-                in_message = true;
-                brace_count = trimmed.matches('{').count();
+        if trimmed.starts_with("message ")
+            && !in_enum
+            && !in_message
+            && let Some(name_part) = trimmed.strip_prefix("message ")
+        {
+            if let Some(space_pos) = name_part.find(' ') {
+                current_message = name_part[..space_pos].to_string();
+            } else if let Some(brace_pos) = name_part.find('{') {
+                current_message = name_part[..brace_pos].trim().to_string();
+            } else {
+                current_message = name_part.trim().to_string();
             }
+            in_message = true;
+            brace_count = 0;
         }
 
         // Handle enum parsing
-        if trimmed.starts_with("enum ") && !in_message {
-            if let Some(name_part) = trimmed.strip_prefix("enum ") {
-                if let Some(space_pos) = name_part.find(' ') {
-                    current_enum = name_part[..space_pos].to_string();
-                } else if let Some(brace_pos) = name_part.find('{') {
-                    current_enum = name_part[..brace_pos].trim().to_string();
-                } else {
-                    current_enum = name_part.trim().to_string();
-                }
-                in_enum = true;
-                current_enum_options.clear();
-                brace_count = trimmed.matches('{').count();
+        if trimmed.starts_with("enum ")
+            && !in_message
+            && let Some(name_part) = trimmed.strip_prefix("enum ")
+        {
+            if let Some(space_pos) = name_part.find(' ') {
+                current_enum = name_part[..space_pos].to_string();
+            } else if let Some(brace_pos) = name_part.find('{') {
+                current_enum = name_part[..brace_pos].trim().to_string();
+            } else {
+                current_enum = name_part.trim().to_string();
             }
+            in_enum = true;
+            current_enum_options.clear();
+            current_enum_values.clear();
+            brace_count = 0;
         }
 
         // Extract enum options while in enum
@@ -428,6 +404,11 @@ fn create_fallback_canonical_file(content: &str) -> crate::canonical::CanonicalF
             }
         }
 
+        // Extract enum values while in enum
+        if in_enum && let Some(value) = parse_enum_value_line(trimmed, &extension_numbers) {
+            current_enum_values.insert(value);
+        }
+
         // Handle brace counting for both messages and enums
         if in_message || in_enum {
             brace_count += trimmed.matches('{').count();
@@ -456,7 +437,7 @@ fn create_fallback_canonical_file(content: &str) -> crate::canonical::CanonicalF
                     // Create a minimal enum with options
                     let en = CanonicalEnum {
                         name: current_enum.clone(),
-                        values: BTreeSet::new(),
+                        values: std::mem::take(&mut current_enum_values),
                         reserved_ranges: BTreeSet::new(),
                         reserved_names: BTreeSet::new(),
                         allow_alias: None,
@@ -473,6 +454,11 @@ fn create_fallback_canonical_file(content: &str) -> crate::canonical::CanonicalF
 
     canonical_file.messages = messages;
     canonical_file.enums = enums;
+
+    // Text parsing records type references as written; qualify them so they
+    // compare equal to references produced by the real parser.
+    crate::resolve::resolve_type_references(&mut canonical_file);
+
     canonical_file
 }
 
@@ -492,34 +478,38 @@ fn extract_fields_from_text(
         let trimmed = line.trim();
 
         // Find the specific message
-        if trimmed.starts_with("message ") {
-            if let Some(name_part) = trimmed.strip_prefix("message ") {
-                let name = if let Some(space_pos) = name_part.find(' ') {
-                    &name_part[..space_pos]
-                } else if let Some(brace_pos) = name_part.find('{') {
-                    name_part[..brace_pos].trim()
-                } else {
-                    name_part.trim()
-                };
-
-                if name == message_name {
-                    found_message = true;
-                    in_message = true;
-                    brace_count = trimmed.matches('{').count();
-                }
+        if trimmed.starts_with("message ")
+            && let Some(name_part) = trimmed.strip_prefix("message ")
+        {
+            let name = if let Some(space_pos) = name_part.find(' ') {
+                &name_part[..space_pos]
+            } else if let Some(brace_pos) = name_part.find('{') {
+                name_part[..brace_pos].trim()
+            } else {
+                name_part.trim()
+            };
+
+            if name == message_name && !in_message {
+                found_message = true;
+                in_message = true;
+                brace_count = 0;
             }
         }
 
         if found_message && in_message {
+            // Only lines directly inside the message body declare its fields;
+            // anything deeper belongs to a nested message, enum or group.
+            let depth = brace_count;
             brace_count += trimmed.matches('{').count();
             brace_count -= trimmed.matches('}').count();
 
             // Parse field lines
-            if brace_count > 0 && !trimmed.starts_with("message ") && !trimmed.starts_with("enum ")
+            if depth == 1
+                && !trimmed.starts_with("message ")
+                && !trimmed.starts_with("enum ")
+                && let Some(field) = parse_field_line(trimmed)
             {
-                if let Some(field) = parse_field_line(trimmed) {
-                    fields.insert(field);
-                }
+                fields.insert(field);
             }
 
             if brace_count == 0 {
@@ -567,17 +557,24 @@ fn parse_field_line(line: &str) -> Option<crate::canonical::CanonicalField> {
         return None;
     }
 
-    let type_name = parts[idx].to_string();
-    let field_name = parts[idx + 1].to_string();
+    // Groups declare their type and field together: `optional group Result = 1 {`.
+    // The field name is the lowercased group name.
+    let group = parts[idx] == "group";
+
+    let (type_name, field_name, equals_idx) = if group {
+        let group_name = parts[idx + 1];
+        (group_name.to_string(), group_name.to_lowercase(), idx + 2)
+    } else {
+        (parts[idx].to_string(), parts[idx + 1].to_string(), idx + 2)
+    };
 
     // Extract field number from "= number;"
-    let equals_idx = idx + 2;
     if equals_idx >= parts.len() || parts[equals_idx] != "=" {
         return None;
     }
 
     let number_str = if equals_idx + 1 < parts.len() {
-        parts[equals_idx + 1].trim_end_matches(';')
+        parts[equals_idx + 1].trim_end_matches(['{', ';'])
     } else {
         return None;
     };
@@ -589,6 +586,7 @@ fn parse_field_line(line: &str) -> Option<crate::canonical::CanonicalField> {
         number,
         label,
         type_name,
+        group,
         oneof_index: None,
         options: BTreeMap::new(),
         default: None,
@@ -602,3 +600,113 @@ fn parse_field_line(line: &str) -> Option<crate::canonical::CanonicalField> {
         weak: None,
     })
 }
+
+/// Map from `EnumValueOptions` extension name to field number in `content`
+fn enum_value_extension_numbers(content: &str) -> HashMap<String, String> {
+    let mut numbers = HashMap::new();
+    let mut in_extend = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(extendee) = trimmed.strip_prefix("extend ") {
+            let extendee = extendee.trim_end_matches('{').trim();
+            in_extend = extendee.trim_start_matches('.') == "google.protobuf.EnumValueOptions";
+            continue;
+        }
+        if !in_extend {
+            continue;
+        }
+        if trimmed.starts_with('}') {
+            in_extend = false;
+            continue;
+        }
+        // Declaration format: [label] type name = number [options];
+        let statement = trimmed.split(';').next().unwrap_or_default();
+        let Some((declaration, number)) = statement.split_once('=') else {
+            continue;
+        };
+        let number = number.split('[').next().unwrap_or_default().trim();
+        if let Some(name) = declaration.split_whitespace().last()
+            && number.parse::<u32>().is_ok()
+        {
+            numbers.insert(name.to_string(), number.to_string());
+        }
+    }
+
+    numbers
+}
+
+/// Parse a single enum value line from proto text
+fn parse_enum_value_line(
+    line: &str,
+    extension_numbers: &HashMap<String, String>,
+) -> Option<crate::canonical::CanonicalEnumValue> {
+    use crate::canonical::CanonicalEnumValue;
+
+    let trimmed = line.trim();
+
+    // Skip comments, empty lines, and enum-level statements
+    if trimmed.is_empty()
+        || trimmed.starts_with("//")
+        || trimmed.starts_with("option ")
+        || trimmed.starts_with("reserved ")
+        || trimmed.starts_with("enum ")
+    {
+        return None;
+    }
+
+    // Parse value format: NAME = number [options];
+    // Examples:
+    // FOO = 1;
+    // BAR = 2 [deprecated = true];
+    let statement = trimmed.split(';').next()?;
+    let (name_part, rest) = statement.split_once('=')?;
+    let name = name_part.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let (number_part, options_part) = match rest.split_once('[') {
+        Some((number_part, options_part)) => (number_part, Some(options_part)),
+        None => (rest, None),
+    };
+    let number = number_part.trim().parse::<i32>().ok()?;
+
+    let mut value = CanonicalEnumValue {
+        name: name.to_string(),
+        number,
+        ..Default::default()
+    };
+
+    if let Some(options_part) = options_part {
+        for option in options_part.trim_end_matches(']').split(',') {
+            let Some((key, option_value)) = option.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            let option_value = option_value.trim();
+            match key {
+                "deprecated" => value.deprecated = Some(option_value == "true"),
+                "debug_redact" => value.debug_redact = Some(option_value == "true"),
+                _ => {
+                    // Custom options are keyed by extension number, as in a
+                    // strict parse; extensions declared in imports are unknown
+                    // here and skipped.
+                    let name = key.trim_matches(|c| c == '(' || c == ')');
+                    let short_name = name.rsplit('.').next().unwrap_or(name);
+                    let Some(number) = extension_numbers.get(short_name) else {
+                        continue;
+                    };
+                    let rendered = match option_value {
+                        "true" => "1",
+                        "false" => "0",
+                        other => other.trim_matches('"'),
+                    };
+                    value.options.insert(number.clone(), rendered.to_string());
+                }
+            }
+        }
+    }
+
+    Some(value)
+}
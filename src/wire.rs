@@ -0,0 +1,261 @@
+//! Wire fingerprints.
+//!
+//! A wire fingerprint covers only what determines the binary encoding of a
+//! file's messages: field numbers, wire types, packedness and the structure
+//! of nested and referenced messages. Field, message and enum value names are
+//! ignored, so renaming any of them keeps the fingerprint. Service and method
+//! names are kept because they form the RPC paths peers call.
+//!
+//! Message-typed fields are expanded in place rather than referred to by name.
+//! A field that refers back to a message already being expanded is written as
+//! `{"recursive":n}`, where `n` counts how many levels up the message is.
+
+use crate::canonical::{CanonicalEnum, CanonicalField, CanonicalFile, CanonicalMessage, qualify};
+use crate::fingerprint::ps1_digest;
+use anyhow::Context;
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap};
+
+/// Prefix of wire fingerprints, naming the encoding and hash.
+pub const WIRE_FINGERPRINT_PREFIX: &str = "wire1-sha256:";
+
+/// Computes the wire fingerprint of the first file in `files`.
+///
+/// The remaining files are only used to resolve types the first file refers
+/// to, e.g. the result of
+/// [`canonical_files_with_imports`](crate::canonical_files_with_imports).
+pub fn wire_fingerprint(files: &[CanonicalFile]) -> anyhow::Result<String> {
+    let model = wire_model(files)?;
+    Ok(format!("{WIRE_FINGERPRINT_PREFIX}{}", ps1_digest(&model)?))
+}
+
+/// Builds the name-free model that [`wire_fingerprint`] hashes.
+///
+/// The model is an object with up to three members: `messages`, the wire
+/// shape of every message defined in the file, sorted; `services`, keyed by
+/// RPC path (`/<package>.<Service>/<Method>`); and `extensions`, each pairing
+/// the extendee's shape with the extension field.
+pub fn wire_model(files: &[CanonicalFile]) -> anyhow::Result<Value> {
+    let file = files.first().context("no file to fingerprint")?;
+    let mut index = TypeIndex::default();
+    for file in files {
+        let package = file.package.as_deref().unwrap_or_default();
+        for message in &file.messages {
+            index.add_message(message, package, &file.syntax);
+        }
+        for en in &file.enums {
+            index.enums.insert(qualify(package, &en.name), en);
+        }
+    }
+
+    let package = file.package.as_deref().unwrap_or_default();
+    let mut expander = Expander {
+        index: &index,
+        cache: HashMap::new(),
+    };
+
+    let mut defined = Vec::new();
+    for message in &file.messages {
+        collect_names(message, package, &mut defined);
+    }
+    let mut messages = Vec::new();
+    for name in &defined {
+        messages.push(expander.shape(name)?);
+    }
+    messages.sort_by_cached_key(|shape| shape.to_string());
+
+    let mut services = Map::new();
+    for service in &file.services {
+        for method in &service.methods {
+            let path = format!("/{}/{}", qualify(package, &service.name), method.name);
+            let mut rpc = Map::new();
+            rpc.insert("input".into(), expander.shape(&method.input_type)?);
+            rpc.insert("output".into(), expander.shape(&method.output_type)?);
+            if method.client_streaming {
+                rpc.insert("client_streaming".into(), Value::Bool(true));
+            }
+            if method.server_streaming {
+                rpc.insert("server_streaming".into(), Value::Bool(true));
+            }
+            services.insert(path, Value::Object(rpc));
+        }
+    }
+
+    let mut extensions = Vec::new();
+    for extension in &file.extensions {
+        let field = CanonicalField {
+            number: extension.number,
+            label: extension.label.clone(),
+            type_name: extension.type_name.clone(),
+            group: extension.group,
+            ..Default::default()
+        };
+        extensions.push(json!({
+            "extendee": expander.shape(&extension.extendee)?,
+            "field": expander.field(&field, &file.syntax, &mut Vec::new())?.0,
+        }));
+    }
+    extensions.sort_by_cached_key(|extension| extension.to_string());
+
+    Ok(json!({
+        "messages": messages,
+        "services": services,
+        "extensions": extensions,
+    }))
+}
+
+#[derive(Default)]
+struct TypeIndex<'a> {
+    /// Messages by fully-qualified name, with the syntax of their file.
+    messages: BTreeMap<String, (&'a CanonicalMessage, &'a str)>,
+    enums: BTreeMap<String, &'a CanonicalEnum>,
+}
+
+impl<'a> TypeIndex<'a> {
+    fn add_message(&mut self, message: &'a CanonicalMessage, scope: &str, syntax: &'a str) {
+        let name = qualify(scope, &message.name);
+        for nested in &message.nested_messages {
+            self.add_message(nested, &name, syntax);
+        }
+        for en in &message.nested_enums {
+            self.enums.insert(qualify(&name, &en.name), en);
+        }
+        self.messages.insert(name, (message, syntax));
+    }
+}
+
+struct Expander<'i, 'a> {
+    index: &'i TypeIndex<'a>,
+    /// Shapes of messages whose expansion does not refer to any message
+    /// outside of it, which are therefore the same wherever they appear.
+    cache: HashMap<String, Value>,
+}
+
+impl Expander<'_, '_> {
+    /// Returns the shape of the message `type_name`.
+    fn shape(&mut self, type_name: &str) -> anyhow::Result<Value> {
+        Ok(self.expand(type_name, &mut Vec::new())?.0)
+    }
+
+    /// Expands the message `type_name` below the messages in `stack`.
+    ///
+    /// Also returns the lowest stack position the expansion refers back to,
+    /// or `usize::MAX` if it refers to nothing outside itself.
+    fn expand(
+        &mut self,
+        type_name: &str,
+        stack: &mut Vec<String>,
+    ) -> anyhow::Result<(Value, usize)> {
+        let name = type_name.trim_start_matches('.');
+        if let Some(position) = stack.iter().rposition(|m| m == name) {
+            let levels = stack.len() - position;
+            return Ok((json!({ "recursive": levels }), position));
+        }
+        if let Some(shape) = self.cache.get(name) {
+            return Ok((shape.clone(), usize::MAX));
+        }
+        let &(message, syntax) = self
+            .index
+            .messages
+            .get(name)
+            .with_context(|| format!("message '{name}' is not defined"))?;
+
+        stack.push(name.to_string());
+        let mut lowest = usize::MAX;
+        let mut fields = Vec::new();
+        for field in &message.fields {
+            let (value, reached) = self.field(field, syntax, stack)?;
+            lowest = lowest.min(reached);
+            fields.push(value);
+        }
+        stack.pop();
+
+        let mut shape = Map::new();
+        shape.insert("fields".into(), Value::Array(fields));
+        if message.message_set_wire_format == Some(true) {
+            shape.insert("message_set".into(), Value::Bool(true));
+        }
+        let shape = Value::Object(shape);
+
+        // Positions from this message's own onwards are inside the expansion.
+        if lowest >= stack.len() {
+            self.cache.insert(name.to_string(), shape.clone());
+            lowest = usize::MAX;
+        }
+        Ok((shape, lowest))
+    }
+
+    /// Describes how `field` is encoded. Like [`Expander::expand`], also
+    /// returns the lowest stack position it refers back to.
+    fn field(
+        &mut self,
+        field: &CanonicalField,
+        syntax: &str,
+        stack: &mut Vec<String>,
+    ) -> anyhow::Result<(Value, usize)> {
+        let mut value = Map::new();
+        value.insert("number".into(), json!(field.number));
+        let repeated = field.label.as_deref() == Some("repeated");
+        if repeated {
+            value.insert("repeated".into(), Value::Bool(true));
+        }
+
+        let mut reached = usize::MAX;
+        let encoding = match scalar_encoding(&field.type_name) {
+            Some(encoding) => {
+                if repeated && encoding != "len" && is_packed(field, syntax) {
+                    value.insert("packed".into(), Value::Bool(true));
+                }
+                encoding
+            }
+            None => {
+                let name = field.type_name.trim_start_matches('.');
+                if let Some(en) = self.index.enums.get(name) {
+                    if repeated && is_packed(field, syntax) {
+                        value.insert("packed".into(), Value::Bool(true));
+                    }
+                    let numbers: Vec<i32> = en.values.iter().map(|v| v.number).collect();
+                    value.insert("enum".into(), json!(numbers));
+                    "varint"
+                } else {
+                    let (shape, lowest) = self.expand(name, stack)?;
+                    reached = lowest;
+                    value.insert("message".into(), shape);
+                    if field.group { "group" } else { "len" }
+                }
+            }
+        };
+        value.insert("encoding".into(), Value::String(encoding.to_string()));
+        Ok((Value::Object(value), reached))
+    }
+}
+
+/// The encoding of a scalar type, or `None` for message and enum types.
+fn scalar_encoding(type_name: &str) -> Option<&'static str> {
+    Some(match type_name {
+        "int32" | "int64" | "uint32" | "uint64" | "bool" => "varint",
+        "sint32" | "sint64" => "zigzag",
+        "fixed32" | "sfixed32" | "float" => "fixed32",
+        "fixed64" | "sfixed64" | "double" => "fixed64",
+        "string" | "bytes" => "len",
+        _ => return None,
+    })
+}
+
+/// Repeated numeric fields are packed by default outside proto2, and an
+/// explicit `packed` option overrides the default.
+fn is_packed(field: &CanonicalField, syntax: &str) -> bool {
+    match field.options.get("packed").map(String::as_str) {
+        Some("true") => true,
+        Some("false") => false,
+        _ => syntax != "proto2",
+    }
+}
+
+fn collect_names(message: &CanonicalMessage, scope: &str, names: &mut Vec<String>) {
+    let name = qualify(scope, &message.name);
+    for nested in &message.nested_messages {
+        collect_names(nested, &name, names);
+    }
+    names.push(name);
+}
@@ -1,3 +1,4 @@
+use proto_sign::ParseMode;
 use proto_sign::compat::BreakingConfig;
 use proto_sign::spec::Spec;
 use std::fs;
@@ -104,8 +105,16 @@ pub fn test_breaking_rule(
     let (current_path, current_content) = &current_files[0];
     let (previous_path, previous_content) = &previous_files[0];
 
-    let current_spec = Spec::try_from_file(current_path, current_content.as_str())?;
-    let previous_spec = Spec::try_from_file(previous_path, previous_content.as_str())?;
+    let current_spec = Spec::try_from_file_with_mode(
+        current_path,
+        current_content.as_str(),
+        ParseMode::BestEffort,
+    )?;
+    let previous_spec = Spec::try_from_file_with_mode(
+        previous_path,
+        previous_content.as_str(),
+        ParseMode::BestEffort,
+    )?;
 
     let config = BreakingConfig::default();
     let result = previous_spec.check_breaking_changes_with_config(&current_spec, &config);
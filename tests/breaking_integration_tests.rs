@@ -4,7 +4,7 @@
 //! exact compatibility and correctness of rule implementations.
 
 use proto_sign::canonical::CanonicalFile;
-use proto_sign::compat::{BreakingConfig, BreakingEngine};
+use proto_sign::compat::{BreakingConfig, BreakingEngine, Direction};
 use proto_sign::spec::Spec;
 use std::fs;
 use std::path::PathBuf;
@@ -51,6 +51,8 @@ fn test_message_no_delete_basic() {
         service_no_change_suffixes: Vec::new(),
         message_no_change_suffixes: Vec::new(),
         enum_no_change_suffixes: Vec::new(),
+        direction: Direction::Backward,
+        detect_renames: false,
     };
 
     let result = run_breaking_check(
@@ -137,6 +139,8 @@ fn test_breaking_config_rule_selection() {
         service_no_change_suffixes: Vec::new(),
         message_no_change_suffixes: Vec::new(),
         enum_no_change_suffixes: Vec::new(),
+        direction: Direction::Backward,
+        detect_renames: false,
     };
 
     let result = run_breaking_check(
@@ -175,6 +179,8 @@ fn test_breaking_config_rule_exclusion() {
         service_no_change_suffixes: Vec::new(),
         message_no_change_suffixes: Vec::new(),
         enum_no_change_suffixes: Vec::new(),
+        direction: Direction::Backward,
+        detect_renames: false,
     };
 
     let result = run_breaking_check(
@@ -2,6 +2,7 @@
 //!
 //! This dynamically generates tests for every Buf test case we extracted
 
+use proto_sign::ParseMode;
 use proto_sign::compat::{BreakingConfig, BreakingResult};
 use proto_sign::spec::Spec;
 use std::fs;
@@ -173,6 +174,7 @@ fn run_single_buf_test_case(test_case: &str) -> anyhow::Result<String> {
         summary: std::collections::HashMap::new(),
         executed_rules: Vec::new(),
         failed_rules: Vec::new(),
+        informational: Vec::new(),
     };
 
     // Ensure we have the same number of files in both directories
@@ -189,10 +191,18 @@ fn run_single_buf_test_case(test_case: &str) -> anyhow::Result<String> {
             .map_err(|e| anyhow::anyhow!("Failed to read previous proto: {}", e))?;
 
         // Parse specs with file path context for better import resolution
-        let current_spec = Spec::try_from_file(current_proto, current_content.as_str())
-            .map_err(|e| anyhow::anyhow!("Failed to parse current proto: {}", e))?;
-        let previous_spec = Spec::try_from_file(previous_proto, previous_content.as_str())
-            .map_err(|e| anyhow::anyhow!("Failed to parse previous proto: {}", e))?;
+        let current_spec = Spec::try_from_file_with_mode(
+            current_proto,
+            current_content.as_str(),
+            ParseMode::BestEffort,
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to parse current proto: {}", e))?;
+        let previous_spec = Spec::try_from_file_with_mode(
+            previous_proto,
+            previous_content.as_str(),
+            ParseMode::BestEffort,
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to parse previous proto: {}", e))?;
 
         // Run breaking change detection for this file pair
         let file_result = previous_spec.check_breaking_changes_with_config(&current_spec, &config);
@@ -0,0 +1,138 @@
+use proto_sign::build;
+use proto_sign::canonical_files_with_imports;
+use proto_sign::fingerprint::closure_fingerprint;
+use proto_sign::{
+    DigestEncoding, FingerprintOptions, FingerprintProfile, HashAlgorithm, generate_fingerprint,
+};
+use std::fs;
+use std::path::Path;
+
+const MONEY: &str =
+    "syntax = \"proto3\";\npackage acme.common;\nmessage Money {\n  int64 units = 1;\n}\n";
+const ORDER: &str = r#"syntax = "proto3";
+package acme.v1;
+import "acme/common/money.proto";
+
+message Order {
+  string id = 1;
+  acme.common.Money total = 2;
+}
+
+service HTTPOrderService {
+  rpc Get(Order) returns (Order);
+}
+"#;
+
+fn write_tree(root: &Path) {
+    fs::create_dir_all(root.join("acme/common")).unwrap();
+    fs::create_dir_all(root.join("acme/v1")).unwrap();
+    fs::write(root.join("acme/common/money.proto"), MONEY).unwrap();
+    fs::write(root.join("acme/v1/order.proto"), ORDER).unwrap();
+}
+
+fn generate(root: &Path, protos: &[&str]) -> anyhow::Result<String> {
+    let protos: Vec<_> = protos.iter().map(|p| root.join(p)).collect();
+    build::configure()
+        .include(root)
+        .emit_rerun_if_changed(false)
+        .generate(&protos)
+}
+
+#[test]
+fn test_generates_file_constants() {
+    let dir = tempfile::tempdir().unwrap();
+    write_tree(dir.path());
+
+    let code = generate(
+        dir.path(),
+        &["acme/v1/order.proto", "acme/common/money.proto"],
+    )
+    .unwrap();
+    let money = generate_fingerprint(MONEY).unwrap();
+    assert!(code.starts_with("// @generated by proto-sign."));
+    assert!(code.contains(&format!(
+        "pub const FINGERPRINT_ACME_COMMON_MONEY: &str = \"{money}\";"
+    )));
+    assert!(code.contains("pub const FINGERPRINT_ACME_V1_ORDER: &str = \"ps1-sha256:"));
+    assert!(code.contains(
+        "    (\"acme/common/money.proto\", FINGERPRINT_ACME_COMMON_MONEY),\n    (\"acme/v1/order.proto\", FINGERPRINT_ACME_V1_ORDER),\n"
+    ));
+}
+
+#[test]
+fn test_generates_service_closure_constants() {
+    let dir = tempfile::tempdir().unwrap();
+    let order = ORDER.replace("acme/common/money.proto", "money.proto");
+    fs::write(dir.path().join("money.proto"), MONEY).unwrap();
+    fs::write(dir.path().join("order.proto"), &order).unwrap();
+    let order_path = dir.path().join("order.proto");
+
+    let code = generate(dir.path(), &["order.proto"]).unwrap();
+    let files = canonical_files_with_imports(&order, Some(&order_path)).unwrap();
+    let closure = closure_fingerprint(&files, "acme.v1.HTTPOrderService").unwrap();
+    assert!(code.contains(&format!(
+        "pub const SERVICE_FINGERPRINT_ACME_V1_HTTP_ORDER_SERVICE: &str = \"{}\";",
+        closure.fingerprint
+    )));
+    assert!(code.contains(
+        "    (\"acme.v1.HTTPOrderService\", SERVICE_FINGERPRINT_ACME_V1_HTTP_ORDER_SERVICE),\n"
+    ));
+}
+
+#[test]
+fn test_options_select_fingerprint_kind() {
+    let dir = tempfile::tempdir().unwrap();
+    write_tree(dir.path());
+    let options = FingerprintOptions::new(HashAlgorithm::Blake3, DigestEncoding::Base64Url)
+        .with_profile(FingerprintProfile::Api);
+
+    let code = build::configure()
+        .include(dir.path())
+        .options(options)
+        .emit_rerun_if_changed(false)
+        .generate(&[dir.path().join("acme/common/money.proto")])
+        .unwrap();
+    assert!(code.contains("FINGERPRINT_ACME_COMMON_MONEY: &str = \"ps1.api-blake3-b64u:"));
+}
+
+#[test]
+fn test_constant_name_collisions_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    write_tree(dir.path());
+    fs::create_dir_all(dir.path().join("other")).unwrap();
+    fs::write(
+        dir.path().join("other/money.proto"),
+        MONEY.replace("Money", "Cash"),
+    )
+    .unwrap();
+
+    let error = generate(
+        dir.path(),
+        &["acme/common/money.proto", "other/money.proto"],
+    )
+    .unwrap_err();
+    assert!(
+        error.to_string().contains("FINGERPRINT_ACME_COMMON_MONEY"),
+        "{error}"
+    );
+}
+
+#[test]
+fn test_compile_writes_out_file() {
+    let dir = tempfile::tempdir().unwrap();
+    write_tree(dir.path());
+    let out = dir.path().join("fingerprints.rs");
+
+    let written = build::configure()
+        .include(dir.path())
+        .out_file(&out)
+        .emit_rerun_if_changed(false)
+        .compile(&[dir.path().join("acme/common/money.proto")])
+        .unwrap();
+    assert_eq!(written, out);
+    assert!(
+        fs::read_to_string(&out)
+            .unwrap()
+            .contains("FINGERPRINT_ACME_COMMON_MONEY")
+    );
+}
@@ -0,0 +1,64 @@
+use proto_sign::canonical::{CanonicalFile, CanonicalMessage, canonical_json_schema};
+use proto_sign::compat::{BreakingConfig, BreakingEngine};
+use proto_sign::spec::Spec;
+use std::fs;
+
+fn read_proto(file_name: &str) -> String {
+    fs::read_to_string(format!("tests/data/{file_name}")).expect("Could not read test proto file")
+}
+
+#[test]
+fn test_canonical_json_round_trips() {
+    let content = read_proto("complex_self_contained.proto");
+    let spec = Spec::try_from(&content).expect("Failed to parse proto");
+
+    let json = serde_json::to_string_pretty(&spec.canonical_file).unwrap();
+    let loaded: CanonicalFile = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded, spec.canonical_file);
+    assert_eq!(serde_json::to_string_pretty(&loaded).unwrap(), json);
+}
+
+#[test]
+fn test_omitted_fields_take_defaults() {
+    let loaded: CanonicalFile =
+        serde_json::from_str(r#"{"messages": [{"name": "Empty"}]}"#).unwrap();
+
+    let mut expected = CanonicalFile::default();
+    expected.messages.insert(CanonicalMessage {
+        name: "Empty".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(loaded, expected);
+}
+
+#[test]
+fn test_snapshot_can_be_checked_without_the_original_proto() {
+    let base = read_proto("complex_self_contained.proto");
+    let breaking = read_proto("complex_self_contained_breaking.proto");
+
+    let snapshot = serde_json::to_string(&Spec::try_from(&base).unwrap().canonical_file).unwrap();
+    let previous: CanonicalFile = serde_json::from_str(&snapshot).unwrap();
+    let current = Spec::try_from(&breaking).unwrap();
+
+    let config = BreakingConfig {
+        use_categories: vec!["WIRE".to_string()],
+        ..Default::default()
+    };
+    let result = BreakingEngine::new().check(&current.canonical_file, &previous, &config);
+
+    assert!(result.has_breaking_changes);
+}
+
+#[test]
+fn test_published_schema_is_up_to_date() {
+    let published = fs::read_to_string("schema/canonical-file.schema.json")
+        .expect("Could not read published schema");
+    let generated = serde_json::to_string_pretty(&canonical_json_schema()).unwrap();
+
+    assert_eq!(
+        published.trim_end(),
+        generated,
+        "regenerate with `proto-sign canonical --schema > schema/canonical-file.schema.json`"
+    );
+}
@@ -0,0 +1,334 @@
+use proto_sign::Changelog;
+use proto_sign::changelog::{ReleaseEntry, git_versions};
+use proto_sign::compat::BreakingConfig;
+use proto_sign::spec::Spec;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const V1: &str = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+message Order {
+  string id = 1;
+  string note = 2;
+}
+"#;
+
+fn v2() -> String {
+    V1.replace(
+        "string note = 2;",
+        "string note = 2 [deprecated = true];\n  int64 total = 3;",
+    )
+}
+
+fn v3() -> String {
+    v2().replace("string note = 2 [deprecated = true];\n", "")
+        .replace("string id = 1;", "int64 id = 1;")
+}
+
+fn changelog(versions: &[(&str, &str)]) -> Changelog {
+    let specs: Vec<_> = versions
+        .iter()
+        .map(|(label, content)| (label.to_string(), Spec::try_from(content).unwrap()))
+        .collect();
+    Changelog::from_versions(&specs, &BreakingConfig::default())
+}
+
+fn paths(entries: &[ReleaseEntry]) -> Vec<&str> {
+    entries.iter().map(|e| e.entry.path.as_str()).collect()
+}
+
+#[test]
+fn test_releases_group_changes_between_consecutive_versions() {
+    let (v2, v3) = (v2(), v3());
+    let log = changelog(&[("v1", V1), ("v2", &v2), ("v3", &v3)]);
+    assert_eq!(log.releases.len(), 2);
+
+    let second = &log.releases[0];
+    assert_eq!(
+        (second.previous_version.as_str(), second.version.as_str()),
+        ("v1", "v2")
+    );
+    assert_eq!(paths(&second.added), vec!["acme.v1.Order.total"]);
+    assert_eq!(paths(&second.deprecated), vec!["acme.v1.Order.note"]);
+    assert!(second.removed.is_empty() && second.changed.is_empty());
+    assert!(!second.has_breaking_changes());
+
+    let third = &log.releases[1];
+    assert_eq!(paths(&third.removed), vec!["acme.v1.Order.note"]);
+    assert_eq!(paths(&third.changed), vec!["acme.v1.Order.id"]);
+    assert!(third.added.is_empty() && third.deprecated.is_empty());
+    assert!(
+        third.removed[0]
+            .breaking_rules
+            .contains(&"FIELD_NO_DELETE".to_string())
+    );
+    assert_eq!(third.changed[0].breaking_rules, vec!["FIELD_SAME_TYPE"]);
+    assert!(third.other_breaking.is_empty());
+    assert!(log.has_breaking_changes());
+}
+
+#[test]
+fn test_markdown_lists_newest_release_first() {
+    let (v2, v3) = (v2(), v3());
+    let markdown = changelog(&[("v1", V1), ("v2", &v2), ("v3", &v3)]).to_string();
+
+    assert!(
+        markdown.starts_with("# Changelog\n\n## v3 (from v2)\n"),
+        "{markdown}"
+    );
+    assert!(markdown.find("## v3").unwrap() < markdown.find("## v2 (from v1)").unwrap());
+    assert!(
+        !markdown.contains("### Other breaking changes"),
+        "{markdown}"
+    );
+    assert!(
+        markdown.contains(
+            "### Removed\n\n- field `acme.v1.Order.note` (breaking: **FIELD_NO_DELETE**, "
+        ),
+        "{markdown}"
+    );
+    assert!(
+        markdown.contains("### Changed\n\n- field `acme.v1.Order.id`: type `string` -> `int64` (breaking: **FIELD_SAME_TYPE**)\n"),
+        "{markdown}"
+    );
+    assert!(
+        markdown.contains("### Added\n\n- field `acme.v1.Order.total`\n\n### Deprecated\n\n- field `acme.v1.Order.note`\n"),
+        "{markdown}"
+    );
+}
+
+#[test]
+fn test_cosmetic_only_versions_have_no_api_changes() {
+    let commented = V1.replace("string id = 1;", "// The order id\n  string id = 1;");
+    let log = changelog(&[("v1", V1), ("v1.0.1", &commented)]);
+    assert!(log.releases[0].is_empty());
+    assert!(!log.has_breaking_changes());
+    assert!(
+        log.to_string()
+            .ends_with("## v1.0.1 (from v1)\n\nNo API changes.\n")
+    );
+
+    let json = serde_json::to_value(&log).unwrap();
+    assert_eq!(json["releases"][0]["version"], "v1.0.1");
+    assert_eq!(json["releases"][0]["added"], serde_json::json!([]));
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_git_versions_reads_each_commit_touching_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let proto = root.join("proto");
+    fs::create_dir(&proto).unwrap();
+    let file = proto.join("order.proto");
+
+    git(root, &["init", "-q"]);
+    for (content, other) in [
+        (V1.to_string(), false),
+        (v2(), false),
+        (v2(), true),
+        (v3(), false),
+    ] {
+        if other {
+            fs::write(root.join("README"), "unrelated").unwrap();
+        } else {
+            fs::write(&file, &content).unwrap();
+        }
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "change"]);
+        if content == V1 {
+            git(root, &["tag", "v1"]);
+        }
+    }
+
+    let versions = git_versions(&file, "v1..HEAD").unwrap();
+    let contents: Vec<&str> = versions.iter().map(|v| v.content()).collect();
+    assert_eq!(contents, vec![V1.to_string(), v2(), v3()]);
+    assert_eq!(versions[0].revision, "v1");
+
+    // A single revision yields every commit up to it that touched the file
+    assert_eq!(git_versions(&file, "HEAD").unwrap().len(), 3);
+    assert!(git_versions(&file, "missing..HEAD").is_err());
+}
+
+#[test]
+fn test_git_versions_resolve_imports_from_the_same_revision() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let proto = root.join("proto");
+    fs::create_dir(&proto).unwrap();
+    let file = proto.join("order.proto");
+
+    let money = |name: &str| {
+        format!(
+            "syntax = \"proto3\";\npackage acme.common;\nmessage {name} {{\n  int64 units = 1;\n}}\n"
+        )
+    };
+    let order = |name: &str| {
+        format!(
+            "syntax = \"proto3\";\npackage acme.v1;\nimport \"money.proto\";\nmessage Order {{\n  acme.common.{name} total = 1;\n}}\n"
+        )
+    };
+    git(root, &["init", "-q"]);
+    for name in ["Money", "Amount"] {
+        fs::write(proto.join("money.proto"), money(name)).unwrap();
+        fs::write(&file, order(name)).unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "change"]);
+    }
+    // The working tree no longer has the types either version imports
+    fs::remove_file(proto.join("money.proto")).unwrap();
+
+    let versions = git_versions(&file, "HEAD").unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(
+        versions[0].sources.get("money.proto"),
+        Some(money("Money").as_str())
+    );
+    assert_eq!(
+        versions[1].sources.get("proto/money.proto"),
+        Some(money("Amount").as_str())
+    );
+
+    let specs: Vec<_> = versions
+        .iter()
+        .map(|v| (v.revision.clone(), v.spec().unwrap()))
+        .collect();
+    let log = Changelog::from_versions(&specs, &BreakingConfig::default());
+    let changed = &log.releases[0].changed;
+    assert_eq!(paths(changed), vec!["acme.v1.Order.total"]);
+    assert_eq!(changed[0].entry.new.as_deref(), Some("acme.common.Amount"));
+    assert_eq!(changed[0].breaking_rules, vec!["FIELD_SAME_TYPE"]);
+}
+
+#[test]
+fn test_unmatched_breaking_changes_are_listed_separately() {
+    let proto2 =
+        "syntax = \"proto2\";\npackage acme.v1;\nmessage Order {\n  optional string id = 1;\n}\n";
+    let required = proto2.replace("optional string id", "required string id");
+    let log = changelog(&[("v1", proto2), ("v2", &required)]);
+    let release = &log.releases[0];
+
+    assert!(
+        release
+            .other_breaking
+            .iter()
+            .any(|c| c.rule_id == "MESSAGE_SAME_REQUIRED_FIELDS")
+    );
+    assert!(log.has_breaking_changes());
+    assert!(
+        log.to_string()
+            .contains("### Other breaking changes\n\n- **MESSAGE_SAME_REQUIRED_FIELDS**: "),
+        "{log}"
+    );
+
+    // A rename is reported on the new name, and only on the name itself
+    let renamed = proto2.replace("string id = 1;", "string order_id = 1;");
+    let renamed_log = changelog(&[("v1", proto2), ("v2", &renamed)]);
+    let release = &renamed_log.releases[0];
+    let rules: Vec<(&str, &[String])> = release
+        .changed
+        .iter()
+        .map(|e| {
+            (
+                e.entry.attribute.as_deref().unwrap(),
+                e.breaking_rules.as_slice(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rules,
+        vec![
+            ("name", &["FIELD_SAME_NAME".to_string()][..]),
+            ("json_name", &[][..])
+        ]
+    );
+    assert!(release.other_breaking.is_empty());
+    let json = serde_json::to_value(&log).unwrap();
+    assert_eq!(
+        json["releases"][0]["changed"][0]["path"],
+        "acme.v1.Order.id"
+    );
+    assert!(json["releases"][0]["changed"][0]["breaking_rules"].is_array());
+}
+
+#[test]
+fn test_breaking_rules_match_nested_types_by_full_path() {
+    let v1 = r#"
+syntax = "proto3";
+package acme.v1;
+message Foo {
+  string id = 1;
+}
+message Bar {
+  message Foo {
+    string id = 1;
+  }
+  Foo foo = 1;
+}
+"#;
+    // Deleting the top-level Foo must not annotate the nested Bar.Foo, which
+    // is only deprecated
+    let v2 = r#"
+syntax = "proto3";
+package acme.v1;
+message Bar {
+  message Foo {
+    option deprecated = true;
+    string id = 1 [deprecated = true];
+  }
+  Foo foo = 1;
+}
+"#;
+    let log = changelog(&[("v1", v1), ("v2", v2)]);
+    let release = &log.releases[0];
+
+    assert_eq!(paths(&release.removed), vec!["acme.v1.Foo"]);
+    assert!(
+        release.removed[0]
+            .breaking_rules
+            .contains(&"MESSAGE_NO_DELETE".to_string())
+    );
+    assert_eq!(
+        paths(&release.deprecated),
+        vec!["acme.v1.Bar.Foo", "acme.v1.Bar.Foo.id"]
+    );
+    for entry in &release.deprecated {
+        assert!(entry.breaking_rules.is_empty(), "{:?}", entry);
+    }
+
+    // A rule naming the field alone is scoped by the message it reports
+    let retyped = v1.replace(
+        "  string id = 1;\n}\nmessage Bar",
+        "  int64 id = 1;\n}\nmessage Bar",
+    );
+    let nested_deprecated = retyped.replace(
+        "    string id = 1;",
+        "    string id = 1 [deprecated = true];",
+    );
+    let log = changelog(&[("v1", v1), ("v2", &nested_deprecated)]);
+    let release = &log.releases[0];
+    assert_eq!(paths(&release.changed), vec!["acme.v1.Foo.id"]);
+    assert_eq!(release.changed[0].breaking_rules, vec!["FIELD_SAME_TYPE"]);
+    assert_eq!(paths(&release.deprecated), vec!["acme.v1.Bar.Foo.id"]);
+    assert!(release.deprecated[0].breaking_rules.is_empty());
+}
@@ -0,0 +1,134 @@
+use proto_sign::canonical_files_with_imports;
+use proto_sign::fingerprint::{ClosureFingerprint, closure_fingerprint};
+use std::fs;
+use std::path::Path;
+
+const COMMON: &str = r#"
+syntax = "proto2";
+
+package acme.common;
+
+message Money {
+  optional int64 units = 1;
+  extensions 100 to 199;
+}
+
+message Unrelated {
+  optional string note = 1;
+}
+"#;
+
+const ORDER: &str = r#"
+syntax = "proto2";
+
+package acme.v1;
+
+import "common.proto";
+import "google/protobuf/timestamp.proto";
+
+message Order {
+  optional string id = 1;
+  optional acme.common.Money total = 2;
+  optional google.protobuf.Timestamp created_at = 3;
+}
+
+message Ping {}
+
+extend acme.common.Money {
+  optional Currency currency = 100;
+}
+
+enum Currency {
+  CURRENCY_UNSPECIFIED = 0;
+}
+
+service OrderService {
+  rpc Get(Order) returns (Order);
+}
+
+service HealthService {
+  rpc Check(Ping) returns (Ping);
+}
+"#;
+
+fn closure(common: &str, order: &str, root: &str) -> ClosureFingerprint {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("common.proto"), common).unwrap();
+    let order_path = dir.path().join("order.proto");
+    let files = canonical_files_with_imports(order, Some(&order_path)).expect("Failed to parse");
+    closure_fingerprint(&files, root).unwrap()
+}
+
+#[test]
+fn test_closure_follows_references_across_files() {
+    let result = closure(COMMON, ORDER, "acme.v1.OrderService");
+
+    assert_eq!(result.root, "acme.v1.OrderService");
+    assert_eq!(
+        result.members,
+        vec![
+            "acme.common.Money",
+            "acme.v1.Currency",
+            "acme.v1.Order",
+            "acme.v1.OrderService",
+            "acme.v1.currency",
+            "google.protobuf.Timestamp",
+        ]
+    );
+    assert!(result.fingerprint.starts_with("ps1-sha256:"));
+}
+
+#[test]
+fn test_closure_ignores_definitions_outside_it() {
+    let base = closure(COMMON, ORDER, "acme.v1.OrderService");
+
+    let unrelated_changed = COMMON.replace("optional string note = 1;", "optional int32 note = 1;");
+    let health_changed = ORDER.replace("rpc Check(Ping) returns (Ping);", "");
+    assert_eq!(
+        closure(&unrelated_changed, ORDER, "acme.v1.OrderService"),
+        base
+    );
+    assert_eq!(
+        closure(COMMON, &health_changed, "acme.v1.OrderService"),
+        base
+    );
+}
+
+#[test]
+fn test_closure_changes_with_reachable_types() {
+    let base = closure(COMMON, ORDER, "acme.v1.OrderService").fingerprint;
+
+    let imported_changed = COMMON.replace("optional int64 units = 1;", "optional int32 units = 1;");
+    let extension_changed = ORDER.replace("CURRENCY_UNSPECIFIED = 0;", "CURRENCY_NONE = 0;");
+    assert_ne!(
+        closure(&imported_changed, ORDER, "acme.v1.OrderService").fingerprint,
+        base
+    );
+    assert_ne!(
+        closure(COMMON, &extension_changed, "acme.v1.OrderService").fingerprint,
+        base
+    );
+}
+
+#[test]
+fn test_closure_of_method_and_leading_dot() {
+    let method = closure(COMMON, ORDER, ".acme.v1.HealthService.Check");
+
+    assert_eq!(method.root, "acme.v1.HealthService.Check");
+    assert_eq!(
+        method.members,
+        vec!["acme.v1.HealthService.Check", "acme.v1.Ping"]
+    );
+}
+
+#[test]
+fn test_closure_of_unknown_definition_fails() {
+    let files = canonical_files_with_imports(
+        "syntax = \"proto3\";\npackage acme;\nmessage A {}\n",
+        Some(Path::new("a.proto")),
+    )
+    .unwrap();
+
+    let error = closure_fingerprint(&files, "acme.B").unwrap_err();
+    assert!(error.to_string().contains("acme.B"));
+}
@@ -0,0 +1,141 @@
+use proto_sign::ParseMode;
+use proto_sign::compat::BreakingConfig;
+use proto_sign::spec::{Compatibility, Spec};
+use std::path::Path;
+
+const BASE: &str = r#"
+syntax = "proto2";
+
+package acme.v1;
+
+message Order {
+  optional string id = 1;
+  optional Line line = 2;
+
+  message Line {
+    optional string sku = 1;
+    optional int32 quantity = 2;
+  }
+
+  extensions 100 to 199;
+}
+
+extend Order {
+  optional string note = 100;
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_OPEN = 1;
+}
+
+service OrderService {
+  rpc Watch(Order) returns (stream Order);
+}
+"#;
+
+fn compare(old: &str, new: &str, config: &BreakingConfig) -> Compatibility {
+    let old = Spec::try_from(old).unwrap();
+    let new = Spec::try_from(new).unwrap();
+    let compatibility = old.compare_with_config(&new, config);
+
+    let breaking = old.check_breaking_changes_with_config(&new, config);
+    assert_eq!(
+        compatibility == Compatibility::Red,
+        breaking.has_breaking_changes,
+        "compare and breaking disagree"
+    );
+    assert_eq!(
+        Compatibility::from_breaking_result(old.fingerprint == new.fingerprint, &breaking),
+        compatibility
+    );
+    compatibility
+}
+
+#[test]
+fn test_changes_missed_by_the_compatibility_model_are_red() {
+    let config = BreakingConfig::default();
+    for (from, to) in [
+        ("STATUS_OPEN = 1;", ""),
+        ("optional int32 quantity = 2;", ""),
+        ("optional string note = 100;", ""),
+        ("returns (stream Order)", "returns (Order)"),
+    ] {
+        let changed = BASE.replace(from, to);
+        assert_eq!(
+            compare(BASE, &changed, &config),
+            Compatibility::Red,
+            "{from}"
+        );
+    }
+}
+
+#[test]
+fn test_additions_are_yellow() {
+    let config = BreakingConfig::default();
+    let changed = BASE
+        .replace("STATUS_OPEN = 1;", "STATUS_OPEN = 1;\n  STATUS_CLOSED = 2;")
+        .replace(
+            "optional int32 quantity = 2;",
+            "optional int32 quantity = 2;\n    optional string unit = 3;",
+        );
+    assert_eq!(compare(BASE, &changed, &config), Compatibility::Yellow);
+    assert_eq!(compare(BASE, BASE, &config), Compatibility::Green);
+}
+
+#[test]
+fn test_classification_follows_configured_categories() {
+    let renamed = BASE.replace("optional string sku = 1;", "optional string item_sku = 1;");
+    assert_eq!(
+        compare(BASE, &renamed, &BreakingConfig::default()),
+        Compatibility::Red
+    );
+
+    let wire = BreakingConfig {
+        use_categories: vec!["WIRE".to_string()],
+        ..BreakingConfig::default()
+    };
+    assert_eq!(compare(BASE, &renamed, &wire), Compatibility::Yellow);
+
+    let except = BreakingConfig {
+        except_rules: vec!["FIELD_SAME_NAME".to_string()],
+        ..BreakingConfig::default()
+    };
+    assert_eq!(compare(BASE, &renamed, &except), Compatibility::Yellow);
+}
+
+#[test]
+fn test_compare_with_uses_default_categories() {
+    let old = Spec::try_from(BASE).unwrap();
+    let changed = BASE.replace("STATUS_OPEN = 1;", "");
+    let new = Spec::try_from(changed.as_str()).unwrap();
+
+    assert_eq!(old.compare_with(&new), Compatibility::Red);
+    assert_eq!(
+        old.compare_with(&new),
+        old.compare_with_config(&new, &BreakingConfig::default())
+    );
+}
+
+#[test]
+fn test_degraded_files_of_equal_length_are_not_green() {
+    let proto = "syntax = \"proto3\";\nmessage A {\n  Missing m = 1;\n  string z = 2;\n}\n";
+    let retyped = proto.replace("string z", "uint64 z");
+    assert_eq!(proto.len(), retyped.len());
+
+    let spec = |content| {
+        Spec::try_from_file_with_mode(Path::new("a.proto"), content, ParseMode::BestEffort).unwrap()
+    };
+    let (old, new) = (spec(proto), spec(&retyped));
+    assert!(old.is_degraded() && new.is_degraded());
+
+    let config = BreakingConfig {
+        use_categories: vec!["FILE".to_string(), "WIRE".to_string()],
+        ..BreakingConfig::default()
+    };
+    let compatibility = old.compare_with_config(&new, &config);
+    let breaking = old.check_breaking_changes_with_config(&new, &config);
+    assert_eq!(compatibility, Compatibility::Red);
+    assert!(breaking.has_breaking_changes);
+    assert_eq!(old.compare_with_config(&old, &config), Compatibility::Green);
+}
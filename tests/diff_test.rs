@@ -0,0 +1,282 @@
+use proto_sign::CanonicalDiff;
+use proto_sign::diff::{ChangeClass, ChangeKind, DiffElement, DiffEntry};
+use proto_sign::spec::Spec;
+
+const BASE: &str = r#"
+syntax = "proto2";
+
+package acme.v1;
+
+message Order {
+  optional string id = 1;
+  optional string note = 2;
+  reserved 8 to 9;
+
+  oneof payment {
+    string card = 10;
+  }
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_OPEN = 1;
+}
+
+service OrderService {
+  rpc Get(Order) returns (Order);
+}
+"#;
+
+fn diff(old: &str, new: &str) -> CanonicalDiff {
+    let old = Spec::try_from(old).unwrap();
+    let new = Spec::try_from(new).unwrap();
+    CanonicalDiff::between(&old.canonical_file, &new.canonical_file)
+}
+
+fn changed(from: &str, to: &str) -> CanonicalDiff {
+    let changed = BASE.replace(from, to);
+    assert_ne!(BASE, changed, "{from} not found");
+    diff(BASE, &changed)
+}
+
+fn summary(diff: &CanonicalDiff) -> Vec<(DiffElement, &str, ChangeKind, ChangeClass)> {
+    diff.entries
+        .iter()
+        .map(|e| (e.element, e.path.as_str(), e.change, e.class))
+        .collect()
+}
+
+#[test]
+fn test_formatting_changes_produce_an_empty_diff() {
+    let reformatted = BASE
+        .replace("  optional string id = 1;\n", "")
+        .replace(
+            "optional string note = 2;",
+            "optional string note = 2;\n  optional   string id=1; // id",
+        )
+        .replace("package acme.v1;", "// Orders\npackage acme.v1;");
+
+    let diff = diff(BASE, &reformatted);
+    assert!(diff.is_empty(), "{diff}");
+    assert!(!diff.has_breaking_changes());
+    assert_eq!(diff.to_string(), "No semantic changes.\n");
+}
+
+#[test]
+fn test_field_changes_are_classified() {
+    use ChangeClass::{Breaking, Cosmetic, NonBreaking};
+    use ChangeKind::{Added, Modified, Removed};
+    use DiffElement::Field;
+
+    let cases = [
+        ("optional string note = 2;", "", Removed, Breaking),
+        (
+            "optional string note = 2;",
+            "optional string note = 2;\n  optional int32 total = 3;",
+            Added,
+            NonBreaking,
+        ),
+        (
+            "optional string note = 2;",
+            "optional string note = 2;\n  required int32 total = 3;",
+            Added,
+            Breaking,
+        ),
+        (
+            "optional string note = 2;",
+            "optional string note = 2 [deprecated = true];",
+            Modified,
+            Cosmetic,
+        ),
+    ];
+    for (from, to, change, class) in cases {
+        let diff = changed(from, to);
+        let entries: Vec<_> = summary(&diff)
+            .into_iter()
+            .filter(|entry| entry.0 == Field)
+            .collect();
+        assert_eq!(entries.len(), 1, "{from} -> {to}: {diff}");
+        assert_eq!(
+            (entries[0].2, entries[0].3),
+            (change, class),
+            "{from} -> {to}"
+        );
+    }
+}
+
+#[test]
+fn test_modified_fields_report_attribute_values() {
+    let diff = changed("optional string id = 1;", "optional int64 order_id = 1;");
+
+    let attribute = |name: &str| -> &DiffEntry {
+        diff.entries
+            .iter()
+            .find(|e| e.attribute.as_deref() == Some(name))
+            .unwrap_or_else(|| panic!("no {name} change in {diff}"))
+    };
+    let name = attribute("name");
+    assert_eq!(name.path, "acme.v1.Order.order_id");
+    assert_eq!(
+        (name.old.as_deref(), name.new.as_deref()),
+        (Some("id"), Some("order_id"))
+    );
+    let type_change = attribute("type");
+    assert_eq!(
+        (type_change.old.as_deref(), type_change.new.as_deref()),
+        (Some("string"), Some("int64"))
+    );
+    assert!(
+        diff.entries
+            .iter()
+            .all(|e| e.class == ChangeClass::Breaking)
+    );
+}
+
+#[test]
+fn test_enum_service_and_reservation_changes() {
+    use ChangeClass::{Breaking, NonBreaking};
+    use ChangeKind::{Added, Modified, Removed};
+
+    let diff = changed("STATUS_OPEN = 1;", "STATUS_OPEN = 1;\n  STATUS_DONE = 2;");
+    assert_eq!(
+        summary(&diff),
+        vec![(
+            DiffElement::EnumValue,
+            "acme.v1.Status.STATUS_DONE",
+            Added,
+            NonBreaking
+        )]
+    );
+
+    let diff = changed(
+        "rpc Get(Order) returns (Order);",
+        "rpc Get(Order) returns (stream Order);\n  rpc Put(Order) returns (Order);",
+    );
+    assert_eq!(
+        summary(&diff),
+        vec![
+            (
+                DiffElement::Method,
+                "acme.v1.OrderService.Get",
+                Modified,
+                Breaking
+            ),
+            (
+                DiffElement::Method,
+                "acme.v1.OrderService.Put",
+                Added,
+                NonBreaking
+            ),
+        ]
+    );
+
+    let diff = changed(
+        "string card = 10;",
+        "string card = 10;\n    string cash = 11;",
+    );
+    assert_eq!(
+        summary(&diff),
+        vec![(DiffElement::Field, "acme.v1.Order.cash", Added, NonBreaking)]
+    );
+
+    let lifted = changed("reserved 8 to 9;", "reserved 9;");
+    assert_eq!(lifted.entries[0].class, Breaking);
+    assert_eq!(lifted.entries[0].old.as_deref(), Some("8 to 9"));
+    let extended = changed("reserved 8 to 9;", "reserved 8 to 9, 20;");
+    assert_eq!(extended.entries[0].class, NonBreaking);
+    for widened in [
+        "reserved 8 to 20;",
+        "reserved 8, 9 to 12;",
+        "reserved 5 to max;",
+    ] {
+        let diff = changed("reserved 8 to 9;", widened);
+        assert_eq!(diff.entries[0].class, NonBreaking, "{widened}");
+    }
+    let narrowed = changed("reserved 8 to 9;", "reserved 8, 10;");
+    assert_eq!(narrowed.entries[0].class, Breaking);
+
+    let removed = changed("rpc Get(Order) returns (Order);", "");
+    assert_eq!(
+        summary(&removed),
+        vec![(
+            DiffElement::Method,
+            "acme.v1.OrderService.Get",
+            Removed,
+            Breaking
+        )]
+    );
+}
+
+#[test]
+fn test_diff_text_and_json_output() {
+    let diff = changed(
+        "optional string note = 2;",
+        "optional string memo = 2 [deprecated = true];",
+    );
+    assert!(diff.has_breaking_changes());
+
+    let text = diff.to_string();
+    assert!(text.starts_with("Breaking (2):\n"), "{text}");
+    assert!(
+        text.contains("  ~ field acme.v1.Order.memo: name note -> memo\n"),
+        "{text}"
+    );
+    assert!(
+        text.contains("Cosmetic (1):\n  ~ field acme.v1.Order.memo: deprecated (unset) -> true\n"),
+        "{text}"
+    );
+    assert!(!text.contains("Non-breaking"), "{text}");
+
+    let json = serde_json::to_value(&diff).unwrap();
+    let first = &json["entries"][0];
+    assert_eq!(first["element"], "field");
+    assert_eq!(first["change"], "modified");
+    assert_eq!(first["class"], "breaking");
+    assert_eq!(first["attribute"], "name");
+    let cosmetic = json["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["class"] == "cosmetic")
+        .unwrap();
+    assert!(cosmetic.get("old").is_none());
+    assert_eq!(cosmetic["new"], "true");
+}
+
+#[test]
+fn test_enum_values_are_matched_by_name() {
+    use ChangeClass::{Breaking, NonBreaking};
+    use ChangeKind::{Added, Modified};
+
+    let aliased = BASE.replace(
+        "enum Status {",
+        "enum Status {\n  option allow_alias = true;",
+    );
+    let alias = aliased.replace(
+        "STATUS_OPEN = 1;",
+        "STATUS_OPEN = 1;\n  STATUS_STARTED = 1;",
+    );
+    assert_eq!(
+        summary(&diff(&aliased, &alias)),
+        vec![(
+            DiffElement::EnumValue,
+            "acme.v1.Status.STATUS_STARTED",
+            Added,
+            NonBreaking
+        )]
+    );
+
+    let renumbered = changed("STATUS_OPEN = 1;", "STATUS_OPEN = 2;");
+    assert_eq!(
+        summary(&renumbered),
+        vec![(
+            DiffElement::EnumValue,
+            "acme.v1.Status.STATUS_OPEN",
+            Modified,
+            Breaking
+        )]
+    );
+    assert_eq!(renumbered.entries[0].attribute.as_deref(), Some("number"));
+    assert_eq!(renumbered.entries[0].old.as_deref(), Some("1"));
+    assert_eq!(renumbered.entries[0].new.as_deref(), Some("2"));
+}
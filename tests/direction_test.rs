@@ -0,0 +1,167 @@
+use proto_sign::compat::{BreakingConfig, Direction};
+use proto_sign::spec::{Compatibility, Spec};
+
+const PROTO2: &str = r#"
+syntax = "proto2";
+
+package acme.v1;
+
+message Order {
+  optional string id = 1;
+  optional string note = 2;
+  optional Status status = 3;
+
+  oneof payment {
+    string card = 10;
+    string voucher = 11;
+  }
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_OPEN = 1;
+}
+"#;
+
+fn rules(old: &str, new: &str, direction: Direction) -> Vec<String> {
+    rules_with(
+        old,
+        new,
+        BreakingConfig {
+            direction,
+            ..BreakingConfig::default()
+        },
+    )
+}
+
+fn rules_with(old: &str, new: &str, config: BreakingConfig) -> Vec<String> {
+    let old = Spec::try_from(old).unwrap();
+    let new = Spec::try_from(new).unwrap();
+    let mut rules: Vec<String> = old
+        .check_breaking_changes_with_config(&new, &config)
+        .changes
+        .into_iter()
+        .map(|change| change.rule_id)
+        .collect();
+    rules.dedup();
+    rules
+}
+
+#[test]
+fn test_direction_parses_and_defaults_to_backward() {
+    assert_eq!(BreakingConfig::default().direction, Direction::Backward);
+    for direction in [Direction::Backward, Direction::Forward, Direction::Full] {
+        assert_eq!(
+            direction.to_string().parse::<Direction>().unwrap(),
+            direction
+        );
+    }
+    assert!("sideways".parse::<Direction>().is_err());
+
+    let config = BreakingConfig::from_yaml_str("version: v1\nbreaking:\n  direction: forward\n");
+    assert_eq!(config.unwrap().direction, Direction::Forward);
+}
+
+#[test]
+fn test_value_added_to_closed_enum_breaks_forward_only() {
+    let added = PROTO2.replace("STATUS_OPEN = 1;", "STATUS_OPEN = 1;\n  STATUS_CLOSED = 2;");
+    assert!(rules(PROTO2, &added, Direction::Backward).is_empty());
+    assert_eq!(
+        rules(PROTO2, &added, Direction::Forward),
+        vec!["ENUM_VALUE_NO_ADD_TO_CLOSED"]
+    );
+
+    // Proto3 enums are open: old readers keep the unknown value
+    let proto3 = "syntax = \"proto3\";\nenum Status {\n  STATUS_UNSPECIFIED = 0;\n}\n";
+    let proto3_added = proto3.replace("= 0;", "= 0;\n  STATUS_OPEN = 1;");
+    assert!(rules(proto3, &proto3_added, Direction::Forward).is_empty());
+}
+
+#[test]
+fn test_field_added_to_oneof_breaks_forward_only() {
+    let added = PROTO2.replace(
+        "string voucher = 11;",
+        "string voucher = 11;\n    string wallet = 12;",
+    );
+    assert!(rules(PROTO2, &added, Direction::Backward).is_empty());
+    assert_eq!(
+        rules(PROTO2, &added, Direction::Forward),
+        vec!["ONEOF_NO_ADD_FIELD"]
+    );
+
+    // A new field outside any oneof is fine in both directions
+    let plain = PROTO2.replace(
+        "optional Status status = 3;",
+        "optional Status status = 3;\n  optional int64 total = 4;",
+    );
+    assert!(rules(PROTO2, &plain, Direction::Forward).is_empty());
+}
+
+#[test]
+fn test_deletions_and_required_fields_by_direction() {
+    let deleted = PROTO2.replace("optional string note = 2;", "");
+    assert!(rules(PROTO2, &deleted, Direction::Backward).contains(&"FIELD_NO_DELETE".to_string()));
+    assert!(rules(PROTO2, &deleted, Direction::Forward).is_empty());
+
+    let required = PROTO2.replace(
+        "optional Status status = 3;",
+        "optional Status status = 3;\n  required int64 total = 4;",
+    );
+    for direction in [Direction::Backward, Direction::Forward, Direction::Full] {
+        assert_eq!(
+            rules(PROTO2, &required, direction),
+            vec!["MESSAGE_SAME_REQUIRED_FIELDS"],
+            "{direction}"
+        );
+    }
+}
+
+#[test]
+fn test_wire_category_reports_required_fields_forward() {
+    let wire = |direction| BreakingConfig {
+        use_categories: vec!["WIRE".to_string()],
+        direction,
+        ..BreakingConfig::default()
+    };
+    let required = PROTO2.replace(
+        "optional Status status = 3;",
+        "optional Status status = 3;\n  required int64 total = 4;",
+    );
+    assert_eq!(
+        rules_with(PROTO2, &required, wire(Direction::Forward)),
+        vec!["MESSAGE_SAME_REQUIRED_FIELDS"]
+    );
+    assert_eq!(
+        rules_with(&required, PROTO2, wire(Direction::Forward)),
+        vec!["MESSAGE_SAME_REQUIRED_FIELDS"]
+    );
+
+    let wire_json = BreakingConfig {
+        use_categories: vec!["WIRE_JSON".to_string()],
+        ..wire(Direction::Full)
+    };
+    assert_eq!(
+        rules_with(PROTO2, &required, wire_json),
+        vec!["MESSAGE_SAME_REQUIRED_FIELDS"]
+    );
+}
+
+#[test]
+fn test_full_direction_reports_both_and_drives_compare() {
+    let changed = PROTO2
+        .replace("optional string note = 2;", "")
+        .replace("STATUS_OPEN = 1;", "STATUS_OPEN = 1;\n  STATUS_CLOSED = 2;");
+    let full = rules(PROTO2, &changed, Direction::Full);
+    assert!(full.contains(&"FIELD_NO_DELETE".to_string()));
+    assert!(full.contains(&"ENUM_VALUE_NO_ADD_TO_CLOSED".to_string()));
+
+    let old = Spec::try_from(PROTO2).unwrap();
+    let added = PROTO2.replace("STATUS_OPEN = 1;", "STATUS_OPEN = 1;\n  STATUS_CLOSED = 2;");
+    let new = Spec::try_from(added.as_str()).unwrap();
+    let forward = BreakingConfig {
+        direction: Direction::Forward,
+        ..BreakingConfig::default()
+    };
+    assert_eq!(old.compare_with(&new), Compatibility::Yellow);
+    assert_eq!(old.compare_with_config(&new, &forward), Compatibility::Red);
+}
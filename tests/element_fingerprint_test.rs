@@ -0,0 +1,120 @@
+use proto_sign::fingerprint::{ElementKind, element_fingerprints};
+use proto_sign::spec::Spec;
+use std::collections::BTreeMap;
+
+const BASE: &str = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+message Order {
+  string id = 1;
+  Item item = 2;
+
+  message Item {
+    string sku = 1;
+  }
+}
+
+message Ping {}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+}
+
+service OrderService {
+  rpc Get(Order) returns (Order);
+  rpc Check(Ping) returns (Ping);
+}
+"#;
+
+fn fingerprints(proto: &str) -> BTreeMap<String, String> {
+    let spec = Spec::try_from(proto).expect("Failed to parse proto");
+    element_fingerprints(&spec.canonical_file)
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.name, e.fingerprint))
+        .collect()
+}
+
+#[test]
+fn test_every_definition_gets_a_fingerprint() {
+    let spec = Spec::try_from(BASE).unwrap();
+    let elements = element_fingerprints(&spec.canonical_file).unwrap();
+
+    let listed: Vec<(&str, ElementKind)> =
+        elements.iter().map(|e| (e.name.as_str(), e.kind)).collect();
+    assert_eq!(
+        listed,
+        vec![
+            ("acme.v1.Order", ElementKind::Message),
+            ("acme.v1.Order.Item", ElementKind::Message),
+            ("acme.v1.OrderService", ElementKind::Service),
+            ("acme.v1.OrderService.Check", ElementKind::Method),
+            ("acme.v1.OrderService.Get", ElementKind::Method),
+            ("acme.v1.Ping", ElementKind::Message),
+            ("acme.v1.Status", ElementKind::Enum),
+        ]
+    );
+    assert!(
+        elements
+            .iter()
+            .all(|e| e.fingerprint.starts_with("ps1-sha256:"))
+    );
+}
+
+#[test]
+fn test_field_change_only_affects_its_message() {
+    let before = fingerprints(BASE);
+    let after = fingerprints(&BASE.replace("string id = 1;", "int64 id = 1;"));
+
+    assert_ne!(before["acme.v1.Order"], after["acme.v1.Order"]);
+    for unchanged in [
+        "acme.v1.Order.Item",
+        "acme.v1.Ping",
+        "acme.v1.Status",
+        "acme.v1.OrderService",
+        "acme.v1.OrderService.Check",
+    ] {
+        assert_eq!(before[unchanged], after[unchanged], "{unchanged}");
+    }
+}
+
+#[test]
+fn test_nested_change_does_not_affect_parent() {
+    let before = fingerprints(BASE);
+    let after =
+        fingerprints(&BASE.replace("string sku = 1;", "string sku = 1;\n    int32 qty = 2;"));
+
+    assert_ne!(before["acme.v1.Order.Item"], after["acme.v1.Order.Item"]);
+    assert_eq!(before["acme.v1.Order"], after["acme.v1.Order"]);
+}
+
+#[test]
+fn test_method_change_affects_method_and_service() {
+    let before = fingerprints(BASE);
+    let after = fingerprints(&BASE.replace(
+        "rpc Check(Ping) returns (Ping);",
+        "rpc Check(Ping) returns (stream Ping);",
+    ));
+
+    assert_ne!(
+        before["acme.v1.OrderService.Check"],
+        after["acme.v1.OrderService.Check"]
+    );
+    assert_ne!(
+        before["acme.v1.OrderService"],
+        after["acme.v1.OrderService"]
+    );
+    assert_eq!(
+        before["acme.v1.OrderService.Get"],
+        after["acme.v1.OrderService.Get"]
+    );
+}
+
+#[test]
+fn test_element_fingerprints_ignore_definition_order() {
+    let reordered = BASE.replace("message Ping {}\n", "") + "\nmessage Ping {}\n";
+
+    assert_eq!(fingerprints(BASE), fingerprints(&reordered));
+}
@@ -0,0 +1,259 @@
+use proto_sign::ParseMode;
+use proto_sign::generate_fingerprint;
+use proto_sign::spec::Spec;
+
+const STATUS_V1: &str = r#"
+syntax = "proto3";
+
+package test.v1;
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_ACTIVE = 1;
+  STATUS_LEGACY = 2 [deprecated = true];
+}
+"#;
+
+const STATUS_V2: &str = r#"
+syntax = "proto3";
+
+package test.v1;
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_ACTIVE = 1 [deprecated = true];
+  STATUS_LEGACY = 2;
+}
+"#;
+
+#[test]
+fn test_enum_value_deprecation_is_captured() {
+    let spec = Spec::try_from(STATUS_V1).expect("Failed to parse proto");
+
+    let status = spec
+        .canonical_file
+        .enums
+        .iter()
+        .find(|e| e.name == "Status")
+        .expect("Status enum should be present");
+    let legacy = status
+        .values
+        .iter()
+        .find(|v| v.name == "STATUS_LEGACY")
+        .expect("STATUS_LEGACY should be present");
+    let active = status
+        .values
+        .iter()
+        .find(|v| v.name == "STATUS_ACTIVE")
+        .expect("STATUS_ACTIVE should be present");
+
+    assert_eq!(legacy.deprecated, Some(true));
+    assert_eq!(active.deprecated, None);
+}
+
+#[test]
+fn test_enum_value_custom_options_are_captured() {
+    let proto = r#"
+syntax = "proto3";
+
+import "google/protobuf/descriptor.proto";
+
+extend google.protobuf.EnumValueOptions {
+  string display_name = 50001;
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_ACTIVE = 1 [(display_name) = "Active"];
+}
+"#;
+
+    let spec = Spec::try_from(proto).expect("Failed to parse proto");
+    let status = spec.canonical_file.enums.iter().next().unwrap();
+    let active = status
+        .values
+        .iter()
+        .find(|v| v.name == "STATUS_ACTIVE")
+        .unwrap();
+
+    assert_eq!(
+        active.options.get("50001").map(String::as_str),
+        Some("Active")
+    );
+}
+
+#[test]
+fn test_fingerprint_detects_enum_value_option_change() {
+    let plain = STATUS_V1.replace(" [deprecated = true]", "");
+
+    let hash_deprecated = generate_fingerprint(STATUS_V1).unwrap();
+    let hash_plain = generate_fingerprint(&plain).unwrap();
+
+    assert_ne!(hash_deprecated, hash_plain);
+}
+
+#[test]
+fn test_enum_value_deprecation_changes_are_informational() {
+    let old_spec = Spec::try_from(STATUS_V1).expect("Failed to parse old proto");
+    let new_spec = Spec::try_from(STATUS_V2).expect("Failed to parse new proto");
+
+    let result = old_spec.check_breaking_changes(&new_spec);
+
+    assert!(
+        !result.has_breaking_changes,
+        "Deprecation changes should not be breaking: {:?}",
+        result.changes
+    );
+
+    let deprecated: Vec<_> = result
+        .informational
+        .iter()
+        .filter(|c| c.rule_id == "ENUM_VALUE_DEPRECATED")
+        .collect();
+    let undeprecated: Vec<_> = result
+        .informational
+        .iter()
+        .filter(|c| c.rule_id == "ENUM_VALUE_UNDEPRECATED")
+        .collect();
+
+    assert_eq!(deprecated.len(), 1);
+    assert!(deprecated[0].message.contains("STATUS_ACTIVE"));
+    assert_eq!(undeprecated.len(), 1);
+    assert!(undeprecated[0].message.contains("STATUS_LEGACY"));
+}
+
+#[test]
+fn test_enum_value_deprecation_rules_respect_except_rules() {
+    let old_spec = Spec::try_from(STATUS_V1).expect("Failed to parse old proto");
+    let new_spec = Spec::try_from(STATUS_V2).expect("Failed to parse new proto");
+
+    let mut config = proto_sign::compat::BreakingConfig::default();
+    config
+        .except_rules
+        .push("ENUM_VALUE_DEPRECATED".to_string());
+
+    let result = old_spec.check_breaking_changes_with_config(&new_spec, &config);
+
+    assert!(
+        result
+            .informational
+            .iter()
+            .all(|c| c.rule_id != "ENUM_VALUE_DEPRECATED")
+    );
+    assert!(
+        result
+            .informational
+            .iter()
+            .any(|c| c.rule_id == "ENUM_VALUE_UNDEPRECATED")
+    );
+}
+
+#[test]
+fn test_fallback_parser_captures_enum_value_options() {
+    // `debug_redact` is unknown to the bundled parser, forcing the text fallback.
+    let proto = r#"
+syntax = "proto3";
+
+enum Secret {
+  SECRET_UNSPECIFIED = 0;
+  SECRET_TOKEN = 1 [debug_redact = true, deprecated = true];
+}
+"#;
+
+    let spec = Spec::try_from_file_with_mode(
+        std::path::Path::new("secret.proto"),
+        proto,
+        ParseMode::BestEffort,
+    )
+    .expect("Fallback parsing should succeed");
+    assert!(spec.is_degraded());
+    let secret = spec
+        .canonical_file
+        .enums
+        .iter()
+        .find(|e| e.name == "Secret")
+        .expect("Secret enum should be present");
+    let token = secret
+        .values
+        .iter()
+        .find(|v| v.number == 1)
+        .expect("SECRET_TOKEN should be present");
+
+    assert_eq!(token.name, "SECRET_TOKEN");
+    assert_eq!(token.debug_redact, Some(true));
+    assert_eq!(token.deprecated, Some(true));
+}
+
+#[test]
+fn test_enum_value_deprecation_with_aliases_is_matched_by_name() {
+    let old_proto = r#"
+syntax = "proto3";
+
+enum Switch {
+  option allow_alias = true;
+  SWITCH_UNSPECIFIED = 0;
+  SWITCH_ON = 1;
+  SWITCH_ENABLED = 1 [deprecated = true];
+}
+"#;
+    let new_proto = old_proto.replace("SWITCH_ON = 1;", "SWITCH_ON = 1 [deprecated = true];");
+
+    let old_spec = Spec::try_from(old_proto).expect("Failed to parse old proto");
+    let new_spec = Spec::try_from(new_proto.as_str()).expect("Failed to parse new proto");
+    let result = old_spec.check_breaking_changes(&new_spec);
+
+    let reported: Vec<_> = result
+        .informational
+        .iter()
+        .map(|c| (c.rule_id.as_str(), c.message.as_str()))
+        .collect();
+    assert_eq!(reported.len(), 1, "{reported:?}");
+    assert_eq!(reported[0].0, "ENUM_VALUE_DEPRECATED");
+    assert!(reported[0].1.contains("SWITCH_ON"));
+}
+
+#[test]
+fn test_fallback_parser_keys_custom_options_like_strict_parse() {
+    let proto = r#"
+syntax = "proto3";
+
+import "google/protobuf/descriptor.proto";
+
+extend google.protobuf.EnumValueOptions {
+  string display_name = 50001;
+  bool hidden = 50002;
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_ACTIVE = 1 [(display_name) = "Active", (hidden) = true];
+}
+"#;
+    // `debug_redact` on another value forces the text fallback.
+    let degraded_proto = proto.replace(
+        "STATUS_UNSPECIFIED = 0;",
+        "STATUS_UNSPECIFIED = 0 [debug_redact = true];",
+    );
+
+    let strict = Spec::try_from(proto).expect("Failed to parse proto");
+    let degraded = Spec::try_from_file_with_mode(
+        std::path::Path::new("status.proto"),
+        &degraded_proto,
+        ParseMode::BestEffort,
+    )
+    .expect("Fallback parsing should succeed");
+    assert!(degraded.is_degraded());
+
+    let options = |spec: &Spec| {
+        spec.canonical_file
+            .enums
+            .iter()
+            .flat_map(|e| e.values.iter())
+            .find(|v| v.name == "STATUS_ACTIVE")
+            .expect("STATUS_ACTIVE should be present")
+            .options
+            .clone()
+    };
+    assert_eq!(options(&strict), options(&degraded));
+    assert_eq!(options(&strict).get("50002").map(String::as_str), Some("1"));
+}
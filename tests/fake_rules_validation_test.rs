@@ -32,6 +32,7 @@ fn test_extension_no_delete_rule_works() {
         number: 1000,
         extendee: ".google.protobuf.DescriptorProto".to_string(),
         type_name: "string".to_string(),
+        group: false,
         label: None,
         default: None,
         deprecated: None,
@@ -67,6 +68,7 @@ fn test_package_extension_no_delete_rule_works() {
         number: 2000,
         extendee: ".com.example.Message".to_string(),
         type_name: "int32".to_string(),
+        group: false,
         label: Some("optional".to_string()),
         default: Some("42".to_string()),
         deprecated: None,
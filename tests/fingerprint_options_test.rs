@@ -0,0 +1,121 @@
+use proto_sign::fingerprint::{fingerprint_with_options, matches_fingerprint};
+use proto_sign::spec::Spec;
+use proto_sign::{
+    DigestEncoding, FingerprintOptions, FingerprintVersion, HashAlgorithm, generate_fingerprint,
+    generate_fingerprint_with_options,
+};
+
+const PROTO: &str = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+message Order {
+  string id = 1;
+  int64 total = 2;
+}
+"#;
+
+fn fingerprint(algorithm: HashAlgorithm, encoding: DigestEncoding) -> String {
+    generate_fingerprint_with_options(PROTO, &FingerprintOptions::new(algorithm, encoding))
+        .expect("Failed to fingerprint proto")
+}
+
+#[test]
+fn test_default_options_match_generate_fingerprint() {
+    assert_eq!(
+        generate_fingerprint_with_options(PROTO, &FingerprintOptions::default()).unwrap(),
+        generate_fingerprint(PROTO).unwrap()
+    );
+}
+
+#[test]
+fn test_algorithm_and_encoding_are_named_in_prefix() {
+    let cases = [
+        (
+            HashAlgorithm::Sha512,
+            DigestEncoding::Hex,
+            "ps1-sha512:",
+            128,
+        ),
+        (
+            HashAlgorithm::Blake3,
+            DigestEncoding::Hex,
+            "ps1-blake3:",
+            64,
+        ),
+        (HashAlgorithm::Xxh3, DigestEncoding::Hex, "ps1-xxh3:", 32),
+        (
+            HashAlgorithm::Sha256,
+            DigestEncoding::Base64Url,
+            "ps1-sha256-b64u:",
+            43,
+        ),
+        (
+            HashAlgorithm::Blake3,
+            DigestEncoding::Multihash,
+            "ps1-blake3-mh:",
+            68,
+        ),
+    ];
+    for (algorithm, encoding, prefix, digest_len) in cases {
+        let fp = fingerprint(algorithm, encoding);
+        let digest = fp.strip_prefix(prefix).unwrap_or_else(|| panic!("{fp}"));
+        assert_eq!(digest.len(), digest_len, "{fp}");
+    }
+}
+
+#[test]
+fn test_multihash_starts_with_code_and_length() {
+    let sha256 = fingerprint(HashAlgorithm::Sha256, DigestEncoding::Multihash);
+    let hex = generate_fingerprint(PROTO).unwrap();
+    assert_eq!(
+        sha256,
+        format!(
+            "ps1-sha256-mh:1220{}",
+            hex.strip_prefix("ps1-sha256:").unwrap()
+        )
+    );
+    assert!(
+        fingerprint(HashAlgorithm::Xxh3, DigestEncoding::Multihash)
+            .starts_with("ps1-xxh3-mh:e4e70210")
+    );
+}
+
+#[test]
+fn test_different_kinds_never_compare_equal() {
+    let mut all = Vec::new();
+    for algorithm in HashAlgorithm::ALL {
+        for encoding in DigestEncoding::ALL {
+            all.push(fingerprint(algorithm, encoding));
+        }
+    }
+    let count = all.len();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), count);
+}
+
+#[test]
+fn test_stored_fingerprints_are_detected_and_matched() {
+    let spec = Spec::try_from(PROTO).unwrap();
+    let changed = PROTO.replace("int64 total", "int32 total");
+    let changed = Spec::try_from(changed.as_str()).unwrap();
+    for algorithm in HashAlgorithm::ALL {
+        for encoding in DigestEncoding::ALL {
+            let options = FingerprintOptions::new(algorithm, encoding);
+            let stored = fingerprint_with_options(&spec.canonical_file, &options).unwrap();
+
+            assert_eq!(FingerprintOptions::detect(&stored), Some(options));
+            assert_eq!(
+                FingerprintVersion::detect(&stored),
+                Some(FingerprintVersion::Ps1)
+            );
+            assert!(matches_fingerprint(&spec.canonical_file, &stored).unwrap());
+            assert!(!matches_fingerprint(&changed.canonical_file, &stored).unwrap());
+        }
+    }
+    assert_eq!(FingerprintOptions::detect("ps1-sha512:abcd"), None);
+    assert_eq!(FingerprintOptions::detect("ps1-md5:abcd"), None);
+    assert_eq!(FingerprintOptions::detect("ps1-sha256-mh:1320abcd"), None);
+}
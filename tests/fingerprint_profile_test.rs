@@ -0,0 +1,120 @@
+use proto_sign::fingerprint::{fingerprint_with_options, matches_fingerprint};
+use proto_sign::spec::Spec;
+use proto_sign::{FingerprintOptions, FingerprintProfile, generate_fingerprint};
+
+const BASE: &str = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+option go_package = "example.com/acme/v1";
+
+message Order {
+  string id = 1;
+  repeated int64 quantities = 2;
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_OPEN = 1;
+}
+
+service OrderService {
+  rpc Get(Order) returns (Order);
+}
+"#;
+
+fn fingerprint(proto: &str, profile: FingerprintProfile) -> String {
+    let spec = Spec::try_from(proto).unwrap();
+    let options = FingerprintOptions::default().with_profile(profile);
+    fingerprint_with_options(&spec.canonical_file, &options).unwrap()
+}
+
+fn profiles_unchanged(from: &str, to: &str) -> Vec<FingerprintProfile> {
+    let changed = BASE.replace(from, to);
+    assert_ne!(BASE, changed, "{from} not found");
+    FingerprintProfile::ALL
+        .into_iter()
+        .filter(|&profile| fingerprint(BASE, profile) == fingerprint(&changed, profile))
+        .collect()
+}
+
+#[test]
+fn test_exact_profile_is_the_default_fingerprint() {
+    assert_eq!(
+        fingerprint(BASE, FingerprintProfile::Exact),
+        generate_fingerprint(BASE).unwrap()
+    );
+    assert!(fingerprint(BASE, FingerprintProfile::Api).starts_with("ps1.api-sha256:"));
+    assert!(fingerprint(BASE, FingerprintProfile::Wire).starts_with("ps1.wire-sha256:"));
+}
+
+#[test]
+fn test_api_profile_ignores_deprecation_and_language_options() {
+    use FingerprintProfile::{Api, Wire};
+    for (from, to) in [
+        ("string id = 1;", "string id = 1 [deprecated = true];"),
+        ("STATUS_OPEN = 1;", "STATUS_OPEN = 1 [deprecated = true];"),
+        (
+            "message Order {",
+            "message Order {\n  option deprecated = true;",
+        ),
+        ("example.com/acme/v1", "example.com/acme/v1;acmev1"),
+        (
+            "option go_package",
+            "option java_outer_classname = \"Orders\";\noption go_package",
+        ),
+    ] {
+        assert_eq!(
+            profiles_unchanged(from, to),
+            vec![Api, Wire],
+            "{from} -> {to}"
+        );
+    }
+}
+
+#[test]
+fn test_wire_profile_also_ignores_json_names() {
+    assert_eq!(
+        profiles_unchanged("string id = 1;", "string id = 1 [json_name = \"orderId\"];"),
+        vec![FingerprintProfile::Wire]
+    );
+}
+
+#[test]
+fn test_encoding_changes_change_every_profile() {
+    for (from, to) in [
+        ("string id = 1;", "string id = 3;"),
+        ("repeated int64", "repeated sint64"),
+        ("quantities = 2;", "quantities = 2 [packed = false];"),
+        ("STATUS_OPEN = 1;", "STATUS_OPEN = 2;"),
+    ] {
+        assert!(profiles_unchanged(from, to).is_empty(), "{from} -> {to}");
+    }
+}
+
+#[test]
+fn test_profiled_fingerprints_are_detected_and_matched() {
+    let spec = Spec::try_from(BASE).unwrap();
+    let deprecated = BASE.replace("string id = 1;", "string id = 1 [deprecated = true];");
+    let deprecated = Spec::try_from(deprecated.as_str()).unwrap();
+
+    let stored = fingerprint(BASE, FingerprintProfile::Api);
+    assert_eq!(
+        FingerprintOptions::detect(&stored),
+        Some(FingerprintOptions::default().with_profile(FingerprintProfile::Api))
+    );
+    assert!(matches_fingerprint(&deprecated.canonical_file, &stored).unwrap());
+    assert!(
+        !matches_fingerprint(
+            &deprecated.canonical_file,
+            &generate_fingerprint(BASE).unwrap()
+        )
+        .unwrap()
+    );
+    assert!(matches_fingerprint(&spec.canonical_file, &stored).unwrap());
+    assert_eq!(
+        FingerprintOptions::detect(&stored.replace("ps1.api", "ps1.exact")),
+        None
+    );
+}
@@ -157,3 +157,42 @@ fn test_spec_api() {
         proto_sign::spec::Compatibility::Red
     );
 }
+
+#[test]
+fn test_spec_models_match_standalone_functions() {
+    let content = read_proto("complex_self_contained.proto");
+
+    let spec = proto_sign::spec::Spec::try_from(&content).unwrap();
+
+    assert_eq!(spec.fingerprint, generate_fingerprint(&content).unwrap());
+    assert_eq!(
+        spec.compatibility_model,
+        get_compatibility_model(&content).unwrap()
+    );
+    assert_eq!(
+        spec.fingerprint,
+        proto_sign::fingerprint_canonical_file(&spec.canonical_file).unwrap()
+    );
+}
+
+#[test]
+fn test_spec_from_file_resolves_imported_types() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("common.proto"),
+        "syntax = \"proto3\";\npackage acme;\nmessage Money { int64 units = 1; }\n",
+    )
+    .unwrap();
+    let order_path = dir.path().join("order.proto");
+    let order = "syntax = \"proto3\";\npackage acme;\nimport \"common.proto\";\nmessage Order { Money total = 1; }\n";
+
+    let spec = proto_sign::spec::Spec::try_from_file(&order_path, order)
+        .expect("imports next to the file should resolve");
+
+    assert!(!spec.is_degraded());
+    let message = spec.compatibility_model.messages.iter().next().unwrap();
+    assert_eq!(
+        message.fields.iter().next().unwrap().type_name,
+        ".acme.Money"
+    );
+}
@@ -0,0 +1,117 @@
+use proto_sign::canonical::{CanonicalFile, CanonicalMessage};
+use proto_sign::fingerprint::{
+    FingerprintVersion, encode_canonical, fingerprint_with_version, matches_fingerprint,
+};
+use proto_sign::generate_fingerprint;
+use proto_sign::spec::Spec;
+use sha2::{Digest, Sha256};
+
+fn ping_file() -> CanonicalFile {
+    let mut file = CanonicalFile {
+        package: Some("acme".to_string()),
+        syntax: "proto3".to_string(),
+        ..Default::default()
+    };
+    file.messages.insert(CanonicalMessage {
+        name: "Ping".to_string(),
+        ..Default::default()
+    });
+    file
+}
+
+#[test]
+fn test_generated_fingerprints_are_versioned() {
+    let fingerprint = generate_fingerprint("syntax = \"proto3\";\nmessage A {}\n").unwrap();
+
+    let digest = fingerprint
+        .strip_prefix("ps1-sha256:")
+        .expect("fingerprint should carry its version");
+    assert_eq!(digest.len(), 64);
+    assert_eq!(
+        FingerprintVersion::detect(&fingerprint),
+        Some(FingerprintVersion::Ps1)
+    );
+}
+
+#[test]
+fn test_ps1_encoding_matches_documented_example() {
+    assert_eq!(
+        encode_canonical(&ping_file()).unwrap(),
+        r#"{"messages":[{"name":"Ping"}],"package":"acme","syntax":"proto3"}"#
+    );
+}
+
+#[test]
+fn test_ps1_encoding_escapes_only_required_characters() {
+    let mut file = ping_file();
+    file.package = Some("a\"b\\c\u{1}é".to_string());
+
+    let encoded = encode_canonical(&file).unwrap();
+
+    assert!(
+        encoded.contains(r#""package":"a\"b\\c\u0001é""#),
+        "{encoded}"
+    );
+}
+
+#[test]
+fn test_legacy_version_reproduces_unversioned_fingerprint() {
+    let file = ping_file();
+    let pretty = serde_json::to_string_pretty(&file).unwrap();
+    let expected = format!("{:x}", Sha256::digest(pretty.as_bytes()));
+
+    let legacy = fingerprint_with_version(&file, FingerprintVersion::Legacy).unwrap();
+
+    assert_eq!(legacy, expected);
+    assert_eq!(
+        FingerprintVersion::detect(&legacy),
+        Some(FingerprintVersion::Legacy)
+    );
+}
+
+#[test]
+fn test_stored_fingerprints_match_in_their_own_version() {
+    let spec = Spec::try_from("syntax = \"proto3\";\npackage acme;\nmessage Ping {}\n").unwrap();
+    let legacy =
+        fingerprint_with_version(&spec.canonical_file, FingerprintVersion::Legacy).unwrap();
+
+    assert!(matches_fingerprint(&spec.canonical_file, &legacy).unwrap());
+    assert!(matches_fingerprint(&spec.canonical_file, &spec.fingerprint).unwrap());
+    assert!(!matches_fingerprint(&CanonicalFile::default(), &spec.fingerprint).unwrap());
+    assert!(matches_fingerprint(&spec.canonical_file, "not-a-fingerprint").is_err());
+}
+
+#[test]
+fn test_legacy_fingerprint_ignores_members_added_since() {
+    // Enum value options and groups were added to the model after legacy
+    // fingerprints were stored; the hash below was computed before that.
+    let proto = r#"
+syntax = "proto2";
+
+package legacy.v1;
+
+enum Status {
+  STATUS_UNKNOWN = 0;
+  STATUS_OLD = 1 [deprecated = true];
+}
+
+message Order {
+  optional string id = 1;
+  optional Status status = 2;
+  repeated group Line = 3 {
+    optional string sku = 4;
+    optional int32 quantity = 5;
+  }
+}
+"#;
+    let spec = Spec::try_from(proto).unwrap();
+
+    let legacy =
+        fingerprint_with_version(&spec.canonical_file, FingerprintVersion::Legacy).unwrap();
+
+    assert_eq!(
+        legacy,
+        "48b67f434e6f1d5e3a56c71d6bedcfd4dbab7aa67c8366f8cf55cabf28c6afd9"
+    );
+    assert!(matches_fingerprint(&spec.canonical_file, &legacy).unwrap());
+}
@@ -0,0 +1,200 @@
+use proto_sign::canonical_files_with_imports;
+use proto_sign::handshake::HandshakeManifest;
+use proto_sign::spec::{Compatibility, Spec};
+
+const BASE: &str = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+message Order {
+  string id = 1;
+  Item item = 2;
+
+  message Item {
+    string sku = 1;
+  }
+}
+
+message Ping {}
+
+service OrderService {
+  rpc Get(Order) returns (Order);
+}
+
+service HealthService {
+  rpc Check(Ping) returns (Ping);
+}
+"#;
+
+fn manifest(proto: &str) -> HandshakeManifest {
+    HandshakeManifest::from_spec(&Spec::try_from(proto).unwrap()).unwrap()
+}
+
+fn handshake(local: &str, peer: &str) -> Vec<(String, Compatibility)> {
+    manifest(local)
+        .compare_with(&manifest(peer))
+        .unwrap()
+        .services
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn test_identical_schemas_are_green() {
+    let reformatted = BASE.replace("message Ping {}", "// Liveness probe.\nmessage   Ping { }");
+    let result = manifest(BASE)
+        .compare_with(&manifest(&reformatted))
+        .unwrap();
+
+    assert_eq!(
+        result.service("acme.v1.OrderService"),
+        Some(Compatibility::Green)
+    );
+    assert_eq!(
+        result.service(".acme.v1.HealthService"),
+        Some(Compatibility::Green)
+    );
+    assert_eq!(result.overall(), Compatibility::Green);
+}
+
+#[test]
+fn test_changes_only_affect_services_that_reach_them() {
+    let added = BASE.replace(
+        "string sku = 1;",
+        "string sku = 1;\n    int32 quantity = 2;",
+    );
+    assert_eq!(
+        handshake(BASE, &added),
+        vec![
+            ("acme.v1.HealthService".to_string(), Compatibility::Green),
+            ("acme.v1.OrderService".to_string(), Compatibility::Yellow),
+        ]
+    );
+    // As in `compare`, the peer is the new version: a peer without the
+    // field has deleted it.
+    assert_eq!(
+        manifest(&added)
+            .compare_with(&manifest(BASE))
+            .unwrap()
+            .overall(),
+        Compatibility::Red
+    );
+
+    let retyped = BASE.replace("string sku = 1;", "int64 sku = 1;");
+    let result = manifest(BASE).compare_with(&manifest(&retyped)).unwrap();
+    assert_eq!(
+        result.service("acme.v1.OrderService"),
+        Some(Compatibility::Red)
+    );
+    assert_eq!(
+        result.service("acme.v1.HealthService"),
+        Some(Compatibility::Green)
+    );
+    assert_eq!(result.overall(), Compatibility::Red);
+}
+
+#[test]
+fn test_services_missing_on_either_side_are_red() {
+    let without_health = BASE
+        .replace("rpc Check(Ping) returns (Ping);", "")
+        .replace("service HealthService {\n  \n}", "");
+    assert_eq!(
+        handshake(&without_health, BASE),
+        vec![
+            ("acme.v1.HealthService".to_string(), Compatibility::Red),
+            ("acme.v1.OrderService".to_string(), Compatibility::Green),
+        ]
+    );
+}
+
+#[test]
+fn test_fingerprint_only_manifests_compare_by_equality() {
+    let local = manifest(BASE);
+    let fingerprints: Vec<(&str, &str)> = local
+        .services
+        .iter()
+        .map(|(name, service)| (name.as_str(), service.fingerprint.as_str()))
+        .collect();
+    let peer = HandshakeManifest::from_fingerprints(fingerprints);
+    assert_eq!(
+        local.compare_with(&peer).unwrap().overall(),
+        Compatibility::Green
+    );
+
+    let added = manifest(&BASE.replace("string id = 1;", "string id = 1;\n  string note = 3;"));
+    let result = added.compare_with(&peer).unwrap();
+    assert_eq!(
+        result.service("acme.v1.OrderService"),
+        Some(Compatibility::Red)
+    );
+}
+
+#[test]
+fn test_manifest_round_trips_through_json_and_follows_imports() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("money.proto"),
+        "syntax = \"proto3\";\npackage acme.common;\nmessage Money {\n  int64 units = 1;\n}\n",
+    )
+    .unwrap();
+    let proto = "syntax = \"proto3\";\npackage acme.v1;\nimport \"money.proto\";\nmessage Quote {\n  acme.common.Money price = 1;\n}\nservice QuoteService {\n  rpc Get(Quote) returns (Quote);\n}\n";
+    let path = dir.path().join("quote.proto");
+    std::fs::write(&path, proto).unwrap();
+
+    let files = canonical_files_with_imports(proto, Some(&path)).unwrap();
+    let local = HandshakeManifest::from_files(&files).unwrap();
+    let shape = local.services["acme.v1.QuoteService"]
+        .shape
+        .as_ref()
+        .unwrap();
+    let names: Vec<String> = shape
+        .iter()
+        .flat_map(|file| {
+            let package = file.package.clone().unwrap_or_default();
+            file.messages
+                .iter()
+                .map(move |m| format!("{package}.{}", m.name))
+        })
+        .collect();
+    assert_eq!(names, vec!["acme.common.Money", "acme.v1.Quote"]);
+
+    let json = serde_json::to_string(&local).unwrap();
+    let peer: HandshakeManifest = serde_json::from_str(&json).unwrap();
+    assert_eq!(peer, local);
+    assert_eq!(
+        local.compare_with(&peer).unwrap().overall(),
+        Compatibility::Green
+    );
+}
+
+#[test]
+fn test_handshake_agrees_with_compare() {
+    let with_status = BASE.replace(
+        "message Ping {}",
+        "message Ping {\n  Status status = 1;\n\n  enum Status {\n    STATUS_UNSPECIFIED = 0;\n    STATUS_UP = 1;\n  }\n}",
+    );
+    let changes = [
+        (
+            BASE,
+            BASE.replace(
+                "rpc Get(Order) returns (Order);",
+                "rpc Get(Order) returns (stream Order);",
+            ),
+        ),
+        (BASE, BASE.replace("string sku = 1;", "")),
+        (
+            with_status.as_str(),
+            with_status.replace("STATUS_UP = 1;", ""),
+        ),
+    ];
+
+    for (old, new) in &changes {
+        let compare = Spec::try_from(old)
+            .unwrap()
+            .compare_with(&Spec::try_from(new.as_str()).unwrap());
+        let handshake = manifest(old).compare_with(&manifest(new)).unwrap();
+        assert_eq!(compare, Compatibility::Red);
+        assert_eq!(handshake.overall(), compare, "{new}");
+    }
+}
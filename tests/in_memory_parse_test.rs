@@ -0,0 +1,30 @@
+use proto_sign::VirtualFileMap;
+use proto_sign::spec::Spec;
+
+#[test]
+fn test_sources_parse_without_a_temporary_directory() {
+    // SAFETY: this binary has a single test, so nothing reads the
+    // environment concurrently.
+    unsafe { std::env::set_var("TMPDIR", "/nonexistent/proto-sign") };
+    assert!(tempfile::tempdir().is_err());
+
+    let sources: VirtualFileMap = [
+        (
+            "acme/common/money.proto",
+            "syntax = \"proto3\";\npackage acme.common;\nmessage Money {\n  int64 units = 1;\n}\n",
+        ),
+        (
+            "acme/v1/order.proto",
+            "syntax = \"proto3\";\npackage acme.v1;\nimport \"acme/common/money.proto\";\nmessage Order {\n  acme.common.Money total = 1;\n}\n",
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    let spec = Spec::try_from_sources(&sources, "acme/v1/order.proto").unwrap();
+    let order = spec.canonical_file.messages.iter().next().unwrap();
+    assert_eq!(
+        order.fields.iter().next().unwrap().type_name,
+        ".acme.common.Money"
+    );
+}
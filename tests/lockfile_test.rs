@@ -0,0 +1,147 @@
+use proto_sign::VirtualFileMap;
+use proto_sign::fingerprint::ElementKind;
+use proto_sign::lock::{LockChangeKind, Lockfile};
+
+const MONEY: &str =
+    "syntax = \"proto3\";\npackage acme.common;\nmessage Money {\n  int64 units = 1;\n}\n";
+const ORDER: &str = r#"syntax = "proto3";
+package acme.v1;
+import "acme/common/money.proto";
+
+message Order {
+  string id = 1;
+  acme.common.Money total = 2;
+}
+
+service OrderService {
+  rpc Get(Order) returns (Order);
+}
+"#;
+
+fn tree(money: &str, order: &str) -> VirtualFileMap {
+    [
+        ("acme/common/money.proto", money),
+        ("acme/v1/order.proto", order),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn test_unchanged_tree_matches_lock() {
+    let lock = Lockfile::from_sources(&tree(MONEY, ORDER), true).unwrap();
+    let reformatted = ORDER.replace("message Order {", "// Orders.\nmessage   Order {");
+
+    let current = Lockfile::from_sources(&tree(MONEY, &reformatted), true).unwrap();
+    assert!(lock.check(&current).unwrap().is_empty());
+    assert!(lock.has_wire_fingerprints());
+}
+
+#[test]
+fn test_lock_reports_changed_elements() {
+    let lock = Lockfile::from_sources(&tree(MONEY, ORDER), false).unwrap();
+    let changed = ORDER
+        .replace("string id = 1;", "int64 id = 1;")
+        .replace("rpc Get(Order) returns (Order);", "")
+        .replace("message Order {", "message Ping {}\n\nmessage Order {");
+
+    let current = Lockfile::from_sources(&tree(MONEY, &changed), false).unwrap();
+    let changes = lock.check(&current).unwrap();
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, "acme/v1/order.proto");
+    assert_eq!(changes[0].change, LockChangeKind::Modified);
+    assert!(!changes[0].wire_changed);
+    let elements: Vec<(&str, ElementKind, LockChangeKind)> = changes[0]
+        .elements
+        .iter()
+        .map(|e| (e.name.as_str(), e.kind, e.change))
+        .collect();
+    assert_eq!(
+        elements,
+        vec![
+            (
+                "acme.v1.Order",
+                ElementKind::Message,
+                LockChangeKind::Modified
+            ),
+            (
+                "acme.v1.OrderService",
+                ElementKind::Service,
+                LockChangeKind::Modified
+            ),
+            (
+                "acme.v1.OrderService.Get",
+                ElementKind::Method,
+                LockChangeKind::Removed
+            ),
+            ("acme.v1.Ping", ElementKind::Message, LockChangeKind::Added),
+        ]
+    );
+}
+
+#[test]
+fn test_wire_change_in_import_is_reported_for_importers() {
+    let lock = Lockfile::from_sources(&tree(MONEY, ORDER), true).unwrap();
+    let money = MONEY.replace("int64 units = 1;", "sint64 units = 1;");
+
+    let current = Lockfile::from_sources(&tree(&money, ORDER), true).unwrap();
+    let changes = lock.check(&current).unwrap();
+
+    let paths: Vec<(&str, bool)> = changes
+        .iter()
+        .map(|c| (c.path.as_str(), c.wire_changed))
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            ("acme/common/money.proto", true),
+            ("acme/v1/order.proto", true)
+        ]
+    );
+    assert!(changes[1].elements.is_empty());
+}
+
+#[test]
+fn test_added_and_removed_files_and_json_round_trip() {
+    let lock = Lockfile::from_sources(&tree(MONEY, ORDER), false).unwrap();
+    let json = serde_json::to_string_pretty(&lock).unwrap();
+    let stored: Lockfile = serde_json::from_str(&json).unwrap();
+    assert_eq!(stored, lock);
+
+    let mut sources: VirtualFileMap = [("acme/common/money.proto", MONEY)].into_iter().collect();
+    sources.insert("top.proto", "syntax = \"proto3\";\nmessage Top {}\n");
+    let current = Lockfile::from_sources(&sources, false).unwrap();
+
+    let changes = stored.check(&current).unwrap();
+    let summary: Vec<(&str, LockChangeKind)> = changes
+        .iter()
+        .map(|c| (c.path.as_str(), c.change))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("acme/v1/order.proto", LockChangeKind::Removed),
+            ("top.proto", LockChangeKind::Added),
+        ]
+    );
+}
+
+#[test]
+fn test_check_rejects_non_cryptographic_fingerprints() {
+    use proto_sign::fingerprint::{
+        DigestEncoding, FingerprintOptions, HashAlgorithm, fingerprint_with_options,
+    };
+
+    let mut lock = Lockfile::from_sources(&tree(MONEY, ORDER), false).unwrap();
+    let current = lock.clone();
+    let entry = lock.files.get_mut("acme/v1/order.proto").unwrap();
+    entry.fingerprint = fingerprint_with_options(
+        &entry.canonical,
+        &FingerprintOptions::new(HashAlgorithm::Xxh3, DigestEncoding::Hex),
+    )
+    .unwrap();
+
+    let error = lock.check(&current).unwrap_err();
+    assert!(error.to_string().contains("xxh3"), "{error}");
+}
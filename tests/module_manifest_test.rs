@@ -0,0 +1,100 @@
+use proto_sign::module::ModuleManifest;
+use proto_sign::{VirtualFileMap, generate_fingerprint};
+use std::fs;
+
+const MONEY: &str =
+    "syntax = \"proto3\";\npackage acme.common;\nmessage Money { int64 units = 1; }\n";
+const ORDER: &str = "syntax = \"proto3\";\npackage acme.v1;\nimport \"acme/common/money.proto\";\nmessage Order { acme.common.Money total = 1; }\n";
+const TOP: &str = "syntax = \"proto3\";\nmessage Top {}\n";
+
+fn module(money: &str) -> VirtualFileMap {
+    [
+        ("acme/common/money.proto", money),
+        ("acme/v1/order.proto", ORDER),
+        ("top.proto", TOP),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn test_manifest_lists_files_and_directories() {
+    let manifest = ModuleManifest::from_sources(&module(MONEY)).unwrap();
+
+    assert_eq!(
+        manifest.files.keys().collect::<Vec<_>>(),
+        vec![
+            "acme/common/money.proto",
+            "acme/v1/order.proto",
+            "top.proto"
+        ]
+    );
+    assert_eq!(
+        manifest.directories.keys().collect::<Vec<_>>(),
+        vec!["", "acme", "acme/common", "acme/v1"]
+    );
+    assert_eq!(manifest.root, manifest.directories[""]);
+    assert_eq!(
+        manifest.files["top.proto"],
+        generate_fingerprint(TOP).unwrap()
+    );
+}
+
+#[test]
+fn test_change_propagates_only_along_its_path() {
+    let before = ModuleManifest::from_sources(&module(MONEY)).unwrap();
+    let after = ModuleManifest::from_sources(&module(&MONEY.replace("int64", "int32"))).unwrap();
+
+    assert_ne!(before.root, after.root);
+    assert_ne!(before.directories["acme"], after.directories["acme"]);
+    assert_ne!(
+        before.directories["acme/common"],
+        after.directories["acme/common"]
+    );
+    assert_eq!(before.directories["acme/v1"], after.directories["acme/v1"]);
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.changed, vec!["acme/common/money.proto"]);
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+}
+
+#[test]
+fn test_diff_reports_added_and_removed_files() {
+    let before = ModuleManifest::from_sources(&module(MONEY)).unwrap();
+    let mut sources = module(MONEY);
+    sources.insert("acme/new.proto", TOP);
+    let mut files = ModuleManifest::from_sources(&sources).unwrap().files;
+    files.remove("top.proto");
+    let after = ModuleManifest::from_file_fingerprints(files).unwrap();
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.added, vec!["acme/new.proto"]);
+    assert_eq!(diff.removed, vec!["top.proto"]);
+    assert!(diff.changed.is_empty());
+    assert!(before.diff(&before).is_empty());
+}
+
+#[test]
+fn test_manifest_from_dir_round_trips_through_json() {
+    let dir = tempfile::tempdir().unwrap();
+    for (path, content) in [
+        ("acme/common/money.proto", MONEY),
+        ("acme/v1/order.proto", ORDER),
+        ("top.proto", TOP),
+        ("README.md", "not a proto"),
+    ] {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    let manifest = ModuleManifest::from_dir(dir.path()).unwrap();
+    assert_eq!(
+        manifest,
+        ModuleManifest::from_sources(&module(MONEY)).unwrap()
+    );
+
+    let json = serde_json::to_string(&manifest).unwrap();
+    let stored: ModuleManifest = serde_json::from_str(&json).unwrap();
+    assert!(stored.diff(&manifest).is_empty());
+}
@@ -0,0 +1,176 @@
+use proto_sign::spec::Spec;
+use proto_sign::{ParseError, ParseMode, generate_fingerprint};
+use std::path::Path;
+
+#[test]
+fn test_syntax_error_reports_position() {
+    let proto = "syntax = \"proto3\";\nmessage A {\n  string a = 1\n}\n";
+
+    let error = Spec::try_from_file(Path::new("a.proto"), proto).unwrap_err();
+
+    match &error {
+        ParseError::Syntax {
+            file, line, column, ..
+        } => {
+            assert_eq!(file, "a.proto");
+            assert_eq!((*line, *column), (4, 1));
+        }
+        other => panic!("expected a syntax error, got {other:?}"),
+    }
+    assert!(error.to_string().starts_with("a.proto:4:1: syntax error"));
+}
+
+#[test]
+fn test_unresolved_type_is_reported() {
+    let proto = "syntax = \"proto3\";\nmessage A {\n  Missing m = 1;\n}\n";
+
+    let error = Spec::try_from(proto).unwrap_err();
+
+    assert_eq!(
+        error,
+        ParseError::UnresolvedType {
+            file: "<input>".to_string(),
+            type_name: "Missing".to_string(),
+            scope: ".A".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_duplicate_definitions_are_reported() {
+    let duplicate_enum = "syntax = \"proto3\";\nenum E { A = 0; }\nenum E { B = 0; }\n";
+    let duplicate_nested =
+        "syntax = \"proto3\";\nmessage Outer {\n  message Inner {}\n  message Inner {}\n}\n";
+    let duplicate_value = "syntax = \"proto3\";\nenum E {\n  A = 0;\n  A = 1;\n}\n";
+
+    for (proto, symbol) in [
+        (duplicate_enum, "E"),
+        (duplicate_nested, "Outer.Inner"),
+        (duplicate_value, "E.A"),
+    ] {
+        let error = Spec::try_from(proto).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::DuplicateSymbol {
+                file: "<input>".to_string(),
+                symbol: symbol.to_string(),
+            }
+        );
+    }
+}
+
+#[test]
+fn test_same_name_in_different_scopes_is_allowed() {
+    let proto = r#"
+syntax = "proto3";
+
+message A {
+  message Inner {}
+}
+
+message B {
+  message Inner {}
+  // message Inner {}
+  string note = 1 [json_name = "message Inner {"];
+}
+"#;
+
+    assert!(Spec::try_from(proto).is_ok());
+}
+
+#[test]
+fn test_unsupported_option_is_reported() {
+    let proto = "syntax = \"proto3\";\nenum E {\n  A = 0 [debug_redact = true];\n}\n";
+
+    let error = Spec::try_from(proto).unwrap_err();
+
+    assert!(
+        matches!(&error, ParseError::UnsupportedOption { option, .. } if option == "debug_redact"),
+        "unexpected error: {error:?}"
+    );
+}
+
+#[test]
+fn test_fingerprint_error_downcasts_to_parse_error() {
+    let error = generate_fingerprint("syntax = \"proto3\";\nmessage A { B b = 1; }\n").unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<ParseError>(),
+        Some(ParseError::UnresolvedType { .. })
+    ));
+}
+
+#[test]
+fn test_best_effort_marks_spec_as_degraded() {
+    let proto = "syntax = \"proto3\";\nmessage A {\n  Missing m = 1;\n  string name = 2;\n}\n";
+    let path = Path::new("a.proto");
+
+    assert!(Spec::try_from_file_with_mode(path, proto, ParseMode::Strict).is_err());
+
+    let spec = Spec::try_from_file_with_mode(path, proto, ParseMode::BestEffort)
+        .expect("best effort parsing should not fail");
+    assert!(spec.is_degraded());
+    assert!(matches!(
+        spec.degraded,
+        Some(ParseError::UnresolvedType { .. })
+    ));
+    assert_eq!(spec.canonical_file.messages.len(), 1);
+}
+
+#[test]
+fn test_degraded_fingerprints_hash_the_approximated_model() {
+    let proto = "syntax = \"proto3\";\nmessage A {\n  Missing m = 1;\n  string z = 2;\n}\n";
+    let retyped = proto.replace("string z", "uint64 z");
+    let reformatted = proto.replace("string z = 2;", "string   z = 2; // z");
+    let spec = |content| {
+        Spec::try_from_file_with_mode(Path::new("a.proto"), content, ParseMode::BestEffort).unwrap()
+    };
+
+    let (old, new) = (spec(proto), spec(&retyped));
+    assert!(old.is_degraded() && new.is_degraded());
+    assert_eq!(proto.len(), retyped.len());
+    assert_ne!(old.fingerprint, new.fingerprint);
+    assert!(old.fingerprint.starts_with("ps1-"), "{}", old.fingerprint);
+    assert_eq!(old.fingerprint, spec(&reformatted).fingerprint);
+}
+
+#[test]
+fn test_valid_file_is_not_degraded() {
+    let proto = "syntax = \"proto3\";\nmessage A {\n  string name = 1;\n}\n";
+
+    let spec = Spec::try_from_file_with_mode(Path::new("a.proto"), proto, ParseMode::BestEffort)
+        .expect("Failed to parse proto");
+
+    assert!(!spec.is_degraded());
+}
+
+#[test]
+fn test_content_without_path_does_not_import_from_working_directory() {
+    // tests/data/a.proto exists relative to the working directory of tests,
+    // but content without a path must not see it.
+    let proto = r#"
+syntax = "proto3";
+
+import "tests/data/a.proto";
+
+message Wrapper {
+  test.package.TestMessage message = 1;
+}
+"#;
+
+    let error = Spec::try_from(proto).unwrap_err();
+    assert!(
+        matches!(error, ParseError::UnresolvedType { .. }),
+        "expected the import to be stubbed, got {error:?}"
+    );
+    assert!(generate_fingerprint(proto).is_err());
+
+    // Imports resolve from the file's own directory, which here is the
+    // working directory
+    let with_path = Spec::try_from_file(Path::new("wrapper.proto"), proto);
+    assert!(with_path.is_ok(), "{:?}", with_path.err());
+
+    // A file elsewhere does not fall back to the working directory
+    let nested = Spec::try_from_file(Path::new("tests/wrapper.proto"), proto);
+    assert!(nested.is_err());
+}
@@ -0,0 +1,184 @@
+use proto_sign::ParseMode;
+use proto_sign::compat::BreakingConfig;
+use proto_sign::generate_fingerprint;
+use proto_sign::spec::Spec;
+
+const WITH_GROUP: &str = r#"
+syntax = "proto2";
+
+package legacy;
+
+message SearchResponse {
+  repeated group Result = 1 {
+    required string url = 2;
+    optional string title = 3;
+  }
+}
+"#;
+
+const WITH_MESSAGE: &str = r#"
+syntax = "proto2";
+
+package legacy;
+
+message SearchResponse {
+  message Result {
+    required string url = 2;
+    optional string title = 3;
+  }
+  repeated Result result = 1;
+}
+"#;
+
+const WITH_RENAMED_GROUP: &str = r#"
+syntax = "proto2";
+
+package legacy;
+
+message SearchResponse {
+  repeated group Hit = 1 {
+    required string url = 2;
+    optional string title = 3;
+  }
+}
+"#;
+
+fn wire_config() -> BreakingConfig {
+    BreakingConfig {
+        use_categories: vec!["WIRE".to_string()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_group_field_is_normalized_distinctly() {
+    let spec = Spec::try_from(WITH_GROUP).expect("Failed to parse proto");
+    let message = spec.canonical_file.messages.iter().next().unwrap();
+    let field = message.fields.iter().next().unwrap();
+
+    assert!(field.group);
+    assert_eq!(field.name, "result");
+    assert_eq!(field.number, 1);
+    assert_eq!(field.type_name, ".legacy.SearchResponse.Result");
+
+    let message_spec = Spec::try_from(WITH_MESSAGE).expect("Failed to parse proto");
+    let message_field = message_spec
+        .canonical_file
+        .messages
+        .iter()
+        .next()
+        .unwrap()
+        .fields
+        .iter()
+        .next()
+        .unwrap();
+
+    assert!(!message_field.group);
+    assert_eq!(message_field.type_name, field.type_name);
+}
+
+#[test]
+fn test_fingerprint_distinguishes_group_from_message() {
+    let hash_group = generate_fingerprint(WITH_GROUP).unwrap();
+    let hash_message = generate_fingerprint(WITH_MESSAGE).unwrap();
+
+    assert_ne!(hash_group, hash_message);
+}
+
+#[test]
+fn test_group_to_message_is_wire_breaking() {
+    let old_spec = Spec::try_from(WITH_GROUP).expect("Failed to parse old proto");
+    let new_spec = Spec::try_from(WITH_MESSAGE).expect("Failed to parse new proto");
+
+    let result = old_spec.check_breaking_changes_with_config(&new_spec, &wire_config());
+
+    let wire_changes: Vec<_> = result
+        .changes
+        .iter()
+        .filter(|c| c.rule_id == "FIELD_WIRE_COMPATIBLE_TYPE")
+        .collect();
+    assert_eq!(wire_changes.len(), 1, "changes: {:?}", result.changes);
+    assert!(wire_changes[0].message.contains("group"));
+}
+
+#[test]
+fn test_message_to_group_is_wire_breaking() {
+    let old_spec = Spec::try_from(WITH_MESSAGE).expect("Failed to parse old proto");
+    let new_spec = Spec::try_from(WITH_GROUP).expect("Failed to parse new proto");
+
+    let result = old_spec.check_breaking_changes_with_config(&new_spec, &wire_config());
+
+    assert!(
+        result
+            .changes
+            .iter()
+            .any(|c| c.rule_id == "FIELD_WIRE_COMPATIBLE_TYPE")
+    );
+}
+
+#[test]
+fn test_group_to_message_changes_field_type() {
+    let old_spec = Spec::try_from(WITH_GROUP).expect("Failed to parse old proto");
+    let new_spec = Spec::try_from(WITH_MESSAGE).expect("Failed to parse new proto");
+
+    let config = BreakingConfig {
+        use_rules: vec!["FIELD_SAME_TYPE".to_string()],
+        ..Default::default()
+    };
+    let result = old_spec.check_breaking_changes_with_config(&new_spec, &config);
+
+    assert_eq!(result.changes.len(), 1, "changes: {:?}", result.changes);
+}
+
+#[test]
+fn test_group_rename_is_wire_breaking() {
+    let old_spec = Spec::try_from(WITH_GROUP).expect("Failed to parse old proto");
+    let new_spec = Spec::try_from(WITH_RENAMED_GROUP).expect("Failed to parse new proto");
+
+    let result = old_spec.check_breaking_changes_with_config(&new_spec, &wire_config());
+
+    let wire_changes: Vec<_> = result
+        .changes
+        .iter()
+        .filter(|c| c.rule_id == "FIELD_WIRE_COMPATIBLE_TYPE")
+        .collect();
+    assert_eq!(wire_changes.len(), 1, "changes: {:?}", result.changes);
+    assert!(wire_changes[0].message.contains("SearchResponse.hit"));
+}
+
+#[test]
+fn test_fallback_parser_understands_groups() {
+    // The unresolved `Unknown` type makes the real parser fail.
+    let proto = r#"
+syntax = "proto2";
+
+message SearchResponse {
+  repeated group Result = 1 {
+    required string url = 2;
+  }
+  optional Unknown extra = 4;
+}
+"#;
+
+    let spec = Spec::try_from_file_with_mode(
+        std::path::Path::new("search.proto"),
+        proto,
+        ParseMode::BestEffort,
+    )
+    .expect("Fallback parsing should succeed");
+    assert!(spec.is_degraded());
+    let message = spec
+        .canonical_file
+        .messages
+        .iter()
+        .find(|m| m.name == "SearchResponse")
+        .expect("SearchResponse should be present");
+
+    let numbers: Vec<i32> = message.fields.iter().map(|f| f.number).collect();
+    assert_eq!(numbers, vec![1, 4], "group body fields must not leak");
+
+    let group = message.fields.iter().find(|f| f.number == 1).unwrap();
+    assert!(group.group);
+    assert_eq!(group.name, "result");
+    assert_eq!(group.type_name, ".SearchResponse.Result");
+}
@@ -0,0 +1,152 @@
+use proto_sign::compat::{BreakingConfig, BreakingResult};
+use proto_sign::spec::Spec;
+
+const BASE: &str = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+message Order {
+  string id = 1;
+  int64 total = 2;
+}
+
+message Empty {}
+
+service OrderService {
+  rpc Get(Order) returns (Order);
+  rpc Ping(Empty) returns (Empty);
+}
+"#;
+
+fn check(new: &str, detect_renames: bool) -> BreakingResult {
+    let old = Spec::try_from(BASE).unwrap();
+    let new = Spec::try_from(new).unwrap();
+    let config = BreakingConfig {
+        use_categories: vec!["FILE".to_string()],
+        detect_renames,
+        ..BreakingConfig::default()
+    };
+    old.check_breaking_changes_with_config(&new, &config)
+}
+
+fn hints(result: &BreakingResult) -> Vec<(&str, &str)> {
+    result
+        .changes
+        .iter()
+        .filter_map(|c| Some((c.rule_id.as_str(), c.hint.as_deref()?)))
+        .collect()
+}
+
+fn renames(result: &BreakingResult) -> Vec<(&str, &str)> {
+    result
+        .informational
+        .iter()
+        .map(|c| (c.rule_id.as_str(), c.message.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_renamed_message_is_hinted_on_its_deletion() {
+    let renamed = BASE
+        .replace("message Order {", "message Purchase {")
+        .replace("(Order)", "(Purchase)");
+    let result = check(&renamed, true);
+    assert!(result.has_breaking_changes);
+    assert!(
+        hints(&result).contains(&(
+            "MESSAGE_NO_DELETE",
+            "probably renamed from Order to Purchase"
+        )),
+        "{:?}",
+        result.changes
+    );
+    assert!(renames(&result).contains(&(
+        "MESSAGE_PROBABLY_RENAMED",
+        "Message \"Order\" was probably renamed to \"Purchase\"."
+    )));
+}
+
+#[test]
+fn test_renamed_field_keeps_number_and_type() {
+    let renamed = BASE.replace("string id = 1;", "string order_id = 1;");
+    let result = check(&renamed, true);
+    assert_eq!(
+        hints(&result),
+        vec![(
+            "FIELD_SAME_NAME",
+            "probably renamed from Order.id to Order.order_id"
+        )]
+    );
+    assert_eq!(
+        renames(&result),
+        vec![(
+            "FIELD_PROBABLY_RENAMED",
+            "Field \"id\" was probably renamed to \"order_id\" in message \"Order\"."
+        )]
+    );
+
+    // A new type under the same number is a replacement, not a rename
+    let replaced = BASE.replace("string id = 1;", "int64 order_id = 1;");
+    let result = check(&replaced, true);
+    assert!(hints(&result).is_empty());
+    assert!(renames(&result).is_empty());
+}
+
+#[test]
+fn test_renamed_rpc_follows_renamed_messages() {
+    let renamed = BASE
+        .replace("message Order {", "message Purchase {")
+        .replace(
+            "rpc Get(Order) returns (Order);",
+            "rpc Fetch(Purchase) returns (Purchase);",
+        );
+    let result = check(&renamed, true);
+    assert!(hints(&result).contains(&(
+        "RPC_NO_DELETE",
+        "probably renamed from OrderService.Get to OrderService.Fetch"
+    )));
+
+    // A changed signature is not paired
+    let changed = BASE.replace(
+        "rpc Ping(Empty) returns (Empty);",
+        "rpc Health(Order) returns (Empty);",
+    );
+    let result = check(&changed, true);
+    assert!(result.changes.iter().any(|c| c.rule_id == "RPC_NO_DELETE"));
+    assert!(hints(&result).is_empty());
+}
+
+#[test]
+fn test_ambiguous_candidates_are_not_paired() {
+    let ambiguous = BASE.replace(
+        "message Order {\n  string id = 1;\n  int64 total = 2;\n}",
+        "message Purchase {\n  string id = 1;\n  int64 total = 2;\n}\n\nmessage Sale {\n  string id = 1;\n  int64 total = 2;\n}",
+    );
+    let result = check(&ambiguous.replace("(Order)", "(Purchase)"), true);
+    assert!(
+        result
+            .changes
+            .iter()
+            .any(|c| c.rule_id == "MESSAGE_NO_DELETE")
+    );
+    assert!(hints(&result).is_empty());
+    assert!(renames(&result).is_empty());
+}
+
+#[test]
+fn test_detection_is_off_by_default() {
+    assert!(!BreakingConfig::default().detect_renames);
+    let renamed = BASE
+        .replace("message Order {", "message Purchase {")
+        .replace("(Order)", "(Purchase)");
+    let result = check(&renamed, false);
+    assert!(result.has_breaking_changes);
+    assert!(hints(&result).is_empty());
+    assert!(renames(&result).is_empty());
+
+    let json = serde_json::to_value(&result.changes[0]).unwrap();
+    assert!(json.get("hint").is_none());
+    let config = BreakingConfig::from_yaml_str("version: v1\nbreaking:\n  detect_renames: true\n");
+    assert!(config.unwrap().detect_renames);
+}
@@ -0,0 +1,148 @@
+use proto_sign::roundtrip::{RoundTripOptions, RoundTripReport, verify_round_trip};
+
+const BASE: &str = r#"
+syntax = "proto2";
+
+package acme.v1;
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_OPEN = 1;
+}
+
+message Item {
+  optional string sku = 1;
+}
+
+message Order {
+  required string id = 1;
+  optional int32 count = 2;
+  optional string note = 3;
+  optional bytes blob = 4;
+  optional sint32 delta = 5;
+  optional Item item = 6;
+  optional Status status = 7;
+  map<string, Item> items = 8;
+}
+"#;
+
+fn round_trip(from: &str, to: &str) -> RoundTripReport {
+    let changed = BASE.replace(from, to);
+    assert_ne!(BASE, changed, "{from} not found");
+    verify_round_trip(BASE, None, &changed, None, &RoundTripOptions::default()).unwrap()
+}
+
+fn mismatch(report: &RoundTripReport) -> (&str, &str, &str) {
+    assert_eq!(report.mismatches.len(), 1, "{report}");
+    let mismatch = &report.mismatches[0];
+    (
+        mismatch.field.as_str(),
+        mismatch.category.as_str(),
+        mismatch.problem.as_str(),
+    )
+}
+
+#[test]
+fn test_identical_schemas_round_trip_unchanged() {
+    let report = verify_round_trip(BASE, None, BASE, None, &RoundTripOptions::default()).unwrap();
+    assert_eq!(report.fields_checked, 9);
+    assert!(report.mismatches.is_empty(), "{report}");
+    assert!(report.is_wire_compatible() && report.is_wire_json_compatible());
+    assert!(
+        report
+            .to_string()
+            .ends_with("All samples read back unchanged.\n")
+    );
+}
+
+#[test]
+fn test_wire_compatible_changes_only_break_json() {
+    for (from, to, field) in [
+        (
+            "optional int32 count = 2;",
+            "optional int64 count = 2;",
+            "count",
+        ),
+        (
+            "optional string note = 3;",
+            "optional bytes note = 3;",
+            "note",
+        ),
+        (
+            "optional int32 count = 2;",
+            "repeated int32 count = 2;",
+            "count",
+        ),
+        (
+            "optional Item item = 6;",
+            "optional bytes item = 6;",
+            "item",
+        ),
+    ] {
+        let report = round_trip(from, to);
+        assert_eq!(mismatch(&report).0, field, "{to}");
+        assert_eq!(mismatch(&report).1, "WIRE_JSON", "{to}: {report}");
+        assert!(report.is_wire_compatible());
+        assert!(!report.is_wire_json_compatible());
+    }
+}
+
+#[test]
+fn test_wire_incompatible_changes_are_reported_per_field() {
+    let report = round_trip("optional bytes blob = 4;", "optional string blob = 4;");
+    assert_eq!(mismatch(&report).1, "WIRE");
+    assert!(
+        mismatch(&report).2.starts_with("decoding failed: "),
+        "{report}"
+    );
+
+    let report = round_trip("optional sint32 delta = 5;", "optional int32 delta = 5;");
+    assert_eq!(mismatch(&report).0, "delta");
+    assert!(mismatch(&report).2.contains(" read back as "), "{report}");
+    assert!(!report.is_wire_compatible());
+
+    // Proto2 enums are closed: numbers they do not define are dropped
+    let report = round_trip("optional int32 count = 2;", "optional Status count = 2;");
+    assert_eq!(mismatch(&report).1, "WIRE");
+    assert!(
+        mismatch(&report).2.contains("unknown enum value"),
+        "{report}"
+    );
+}
+
+#[test]
+fn test_json_names_and_enum_representation() {
+    let report = round_trip("optional string note = 3;", "optional string memo = 3;");
+    assert_eq!(
+        mismatch(&report),
+        ("note", "WIRE_JSON", "JSON name note changed to memo")
+    );
+
+    let report = round_trip("optional Status status = 7;", "optional int32 status = 7;");
+    assert_eq!(mismatch(&report).1, "WIRE_JSON");
+    assert!(
+        mismatch(&report).2.starts_with("JSON value \"STATUS_"),
+        "{report}"
+    );
+}
+
+#[test]
+fn test_reports_are_seeded_and_skip_deleted_fields() {
+    let changed = BASE
+        .replace("optional sint32 delta = 5;", "optional int32 delta = 5;")
+        .replace("optional int32 count = 2;", "");
+    let options = RoundTripOptions {
+        samples: 8,
+        seed: 42,
+    };
+    let first = verify_round_trip(BASE, None, &changed, None, &options).unwrap();
+    let second = verify_round_trip(BASE, None, &changed, None, &options).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(first.fields_checked, 8);
+    assert_eq!(first.samples, 8);
+
+    let json = serde_json::to_value(&first).unwrap();
+    assert_eq!(json["seed"], 42);
+    assert_eq!(json["mismatches"][0]["message"], "acme.v1.Order");
+    assert_eq!(json["mismatches"][0]["number"], 5);
+}
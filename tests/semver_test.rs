@@ -0,0 +1,152 @@
+use proto_sign::compat::{BreakingConfig, Direction};
+use proto_sign::spec::{Compatibility, Spec, VersionBump, VersionRecommendation};
+
+const BASE: &str = r#"
+syntax = "proto2";
+
+package acme.v1;
+
+message Order {
+  optional string id = 1;
+  optional string note = 2;
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_OPEN = 1;
+}
+
+service OrderService {
+  rpc Get(Order) returns (Order);
+}
+"#;
+
+fn recommend(new: &str, config: &BreakingConfig) -> VersionRecommendation {
+    let old = Spec::try_from(BASE).unwrap();
+    let new = Spec::try_from(new).unwrap();
+    old.recommend_version_bump(&new, config)
+}
+
+fn changed(from: &str, to: &str) -> VersionRecommendation {
+    let changed = BASE.replace(from, to);
+    assert_ne!(BASE, changed, "{from} not found");
+    recommend(&changed, &BreakingConfig::default())
+}
+
+#[test]
+fn test_breaking_changes_recommend_major() {
+    let removed = changed("optional string note = 2;", "");
+    assert_eq!(removed.bump, VersionBump::Major);
+    assert!(
+        removed
+            .reasons
+            .iter()
+            .any(|reason| reason.starts_with("FIELD_NO_DELETE: ")),
+        "{:?}",
+        removed.reasons
+    );
+}
+
+#[test]
+fn test_additions_recommend_minor() {
+    let cases = [
+        (
+            "optional string note = 2;",
+            "optional string note = 2;\n  optional int64 total = 3;",
+            "+ field acme.v1.Order.total",
+        ),
+        (
+            "STATUS_OPEN = 1;",
+            "STATUS_OPEN = 1;\n  STATUS_DONE = 2;",
+            "+ enum value acme.v1.Status.STATUS_DONE",
+        ),
+        (
+            "rpc Get(Order) returns (Order);",
+            "rpc Get(Order) returns (Order);\n  rpc Put(Order) returns (Order);",
+            "+ method acme.v1.OrderService.Put",
+        ),
+        (
+            "enum Status {",
+            "message Receipt {}\n\nenum Status {",
+            "+ message acme.v1.Receipt",
+        ),
+    ];
+    for (from, to, reason) in cases {
+        let recommendation = changed(from, to);
+        assert_eq!(recommendation.bump, VersionBump::Minor, "{to}");
+        assert_eq!(recommendation.reasons, vec![reason.to_string()]);
+    }
+}
+
+#[test]
+fn test_cosmetic_changes_recommend_patch() {
+    let reordered = BASE.replace("  optional string id = 1;\n", "").replace(
+        "optional string note = 2;",
+        "optional string note = 2;\n  // The order id\n  optional string id = 1;",
+    );
+    let recommendation = recommend(&reordered, &BreakingConfig::default());
+    assert_eq!(recommendation.bump, VersionBump::Patch);
+    assert!(recommendation.reasons.is_empty());
+
+    // Deprecation is outside the api profile
+    let options = BASE
+        .replace(
+            "rpc Get(Order) returns (Order);",
+            "rpc Get(Order) returns (Order) {\n    option deprecated = true;\n  }",
+        )
+        .replace(
+            "optional string note = 2;",
+            "optional string note = 2 [deprecated = true];",
+        );
+    let recommendation = recommend(&options, &BreakingConfig::default());
+    assert_eq!(recommendation.bump, VersionBump::Patch);
+    assert!(!recommendation.reasons.is_empty());
+}
+
+#[test]
+fn test_bump_follows_compare_and_config() {
+    let old = Spec::try_from(BASE).unwrap();
+    let added = BASE.replace("STATUS_OPEN = 1;", "STATUS_OPEN = 1;\n  STATUS_DONE = 2;");
+    let new = Spec::try_from(added.as_str()).unwrap();
+    assert_eq!(old.compare_with(&new), Compatibility::Yellow);
+    assert_eq!(
+        old.recommend_version_bump(&new, &BreakingConfig::default())
+            .bump,
+        VersionBump::Minor
+    );
+
+    // A value added to a closed enum breaks readers still on the old schema
+    let forward = BreakingConfig {
+        direction: Direction::Forward,
+        ..BreakingConfig::default()
+    };
+    assert_eq!(old.compare_with_config(&new, &forward), Compatibility::Red);
+    assert_eq!(
+        old.recommend_version_bump(&new, &forward).bump,
+        VersionBump::Major
+    );
+
+    assert_eq!(old.compare_with(&old), Compatibility::Green);
+    assert_eq!(
+        old.recommend_version_bump(&old, &BreakingConfig::default()),
+        VersionRecommendation {
+            bump: VersionBump::Patch,
+            reasons: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn test_bump_ordering_and_json() {
+    assert!(VersionBump::Patch < VersionBump::Minor);
+    assert!(VersionBump::Minor < VersionBump::Major);
+    assert_eq!(VersionBump::Major.to_string(), "major");
+
+    let recommendation = changed(
+        "optional string note = 2;",
+        "optional string note = 2;\n  optional int64 total = 3;",
+    );
+    let json = serde_json::to_value(&recommendation).unwrap();
+    assert_eq!(json["bump"], "minor");
+    assert_eq!(json["reasons"][0], "+ field acme.v1.Order.total");
+}
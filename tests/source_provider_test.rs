@@ -0,0 +1,109 @@
+use proto_sign::spec::Spec;
+use proto_sign::{ParseError, VirtualFileMap};
+
+const COMMON: &str = r#"
+syntax = "proto3";
+
+package acme.common;
+
+message Money {
+  int64 units = 1;
+  string currency = 2;
+}
+"#;
+
+const ORDER: &str = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+import "acme/common/money.proto";
+
+message Order {
+  acme.common.Money total = 1;
+}
+"#;
+
+fn sources() -> VirtualFileMap {
+    [
+        ("acme/common/money.proto", COMMON),
+        ("acme/v1/order.proto", ORDER),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn test_imports_resolve_from_the_map() {
+    let sources = sources();
+
+    let spec = Spec::try_from_sources(&sources, "acme/v1/order.proto")
+        .expect("imports in the map should resolve");
+
+    let order = spec.canonical_file.messages.iter().next().unwrap();
+    let total = order.fields.iter().next().unwrap();
+    assert_eq!(total.type_name, ".acme.common.Money");
+    assert!(!spec.is_degraded());
+}
+
+#[test]
+fn test_matches_string_parsing_for_self_contained_files() {
+    let sources = sources();
+
+    let from_map = Spec::try_from_sources(&sources, "acme/common/money.proto").unwrap();
+    let from_str = Spec::try_from(COMMON).unwrap();
+
+    assert_eq!(from_map.fingerprint, from_str.fingerprint);
+    assert_eq!(from_map.canonical_file, from_str.canonical_file);
+}
+
+#[test]
+fn test_missing_import_is_stubbed_but_its_types_are_not() {
+    let mut sources = VirtualFileMap::new();
+    sources.insert("acme/v1/order.proto", ORDER);
+
+    let error = Spec::try_from_sources(&sources, "acme/v1/order.proto").unwrap_err();
+
+    assert!(
+        matches!(&error, ParseError::UnresolvedType { file, .. } if file == "acme/v1/order.proto"),
+        "unexpected error: {error:?}"
+    );
+}
+
+#[test]
+fn test_errors_in_imports_name_the_import() {
+    let mut sources = sources();
+    sources.insert(
+        "acme/common/money.proto",
+        "syntax = \"proto3\";\nmessage Money {\n",
+    );
+
+    let error = Spec::try_from_sources(&sources, "acme/v1/order.proto").unwrap_err();
+
+    assert_eq!(error.file(), "acme/common/money.proto");
+}
+
+#[test]
+fn test_byte_buffers_must_be_utf8() {
+    let mut sources = VirtualFileMap::new();
+
+    sources
+        .insert_bytes("money.proto", COMMON.as_bytes())
+        .expect("valid UTF-8 should be accepted");
+    let error = sources
+        .insert_bytes("broken.proto", vec![0xff, 0xfe])
+        .unwrap_err();
+
+    assert!(matches!(error, ParseError::Io { .. }));
+    assert_eq!(sources.len(), 1);
+    assert!(Spec::try_from_sources(&sources, "money.proto").is_ok());
+}
+
+#[test]
+fn test_unknown_path_is_an_error() {
+    let sources = sources();
+
+    let error = Spec::try_from_sources(&sources, "acme/v1/missing.proto").unwrap_err();
+
+    assert_eq!(error.file(), "acme/v1/missing.proto");
+}
@@ -0,0 +1,193 @@
+use proto_sign::ParseMode;
+use proto_sign::canonical::{
+    CanonicalEnum, CanonicalField, CanonicalFile, CanonicalMessage, CanonicalMethod,
+    CanonicalService,
+};
+use proto_sign::resolve::resolve_type_references;
+use proto_sign::spec::Spec;
+
+fn field(name: &str, number: i32, type_name: &str) -> CanonicalField {
+    CanonicalField {
+        name: name.to_string(),
+        number,
+        type_name: type_name.to_string(),
+        ..Default::default()
+    }
+}
+
+fn enumeration(name: &str) -> CanonicalEnum {
+    CanonicalEnum {
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+fn build_file() -> CanonicalFile {
+    let mut outer = CanonicalMessage {
+        name: "Outer".to_string(),
+        ..Default::default()
+    };
+    outer.nested_messages.insert(CanonicalMessage {
+        name: "Inner".to_string(),
+        ..Default::default()
+    });
+    outer.nested_enums.insert(enumeration("Status"));
+    outer.fields.insert(field("inner", 1, "Inner"));
+    outer
+        .fields
+        .insert(field("qualified_inner", 2, "Outer.Inner"));
+    outer.fields.insert(field("status", 3, "Status"));
+    outer
+        .fields
+        .insert(field("package_relative", 4, "v1.Other"));
+    outer
+        .fields
+        .insert(field("imported", 5, "google.protobuf.Timestamp"));
+    outer.fields.insert(field("count", 6, "int32"));
+    outer.fields.insert(field("absolute", 7, ".acme.v1.Other"));
+
+    let mut other = CanonicalMessage {
+        name: "Other".to_string(),
+        ..Default::default()
+    };
+    other.fields.insert(field("status", 1, "Status"));
+
+    let mut service = CanonicalService {
+        name: "OrderService".to_string(),
+        ..Default::default()
+    };
+    service.methods.insert(CanonicalMethod {
+        name: "Get".to_string(),
+        input_type: "Outer".to_string(),
+        output_type: "Outer.Inner".to_string(),
+        ..Default::default()
+    });
+
+    let mut file = CanonicalFile {
+        package: Some("acme.v1".to_string()),
+        syntax: "proto3".to_string(),
+        ..Default::default()
+    };
+    file.messages.insert(outer);
+    file.messages.insert(other);
+    file.enums.insert(enumeration("Status"));
+    file.services.insert(service);
+    file
+}
+
+fn field_type<'a>(file: &'a CanonicalFile, message: &str, number: i32) -> &'a str {
+    file.messages
+        .iter()
+        .find(|m| m.name == message)
+        .and_then(|m| m.fields.iter().find(|f| f.number == number))
+        .map(|f| f.type_name.as_str())
+        .expect("field should exist")
+}
+
+#[test]
+fn test_relative_references_resolve_to_fully_qualified_names() {
+    let mut file = build_file();
+    resolve_type_references(&mut file);
+
+    assert_eq!(field_type(&file, "Outer", 1), ".acme.v1.Outer.Inner");
+    assert_eq!(field_type(&file, "Outer", 2), ".acme.v1.Outer.Inner");
+    assert_eq!(field_type(&file, "Outer", 4), ".acme.v1.Other");
+    assert_eq!(field_type(&file, "Outer", 7), ".acme.v1.Other");
+}
+
+#[test]
+fn test_innermost_scope_wins() {
+    let mut file = build_file();
+    resolve_type_references(&mut file);
+
+    assert_eq!(field_type(&file, "Outer", 3), ".acme.v1.Outer.Status");
+    assert_eq!(field_type(&file, "Other", 1), ".acme.v1.Status");
+}
+
+#[test]
+fn test_scalars_and_unknown_references() {
+    let mut file = build_file();
+    resolve_type_references(&mut file);
+
+    assert_eq!(field_type(&file, "Outer", 5), ".google.protobuf.Timestamp");
+    assert_eq!(field_type(&file, "Outer", 6), "int32");
+}
+
+#[test]
+fn test_method_types_are_resolved() {
+    let mut file = build_file();
+    resolve_type_references(&mut file);
+
+    let method = file
+        .services
+        .iter()
+        .next()
+        .unwrap()
+        .methods
+        .iter()
+        .next()
+        .unwrap();
+    assert_eq!(method.input_type, ".acme.v1.Outer");
+    assert_eq!(method.output_type, ".acme.v1.Outer.Inner");
+}
+
+#[test]
+fn test_resolution_is_idempotent() {
+    let mut once = build_file();
+    resolve_type_references(&mut once);
+    let mut twice = build_file();
+    resolve_type_references(&mut twice);
+    resolve_type_references(&mut twice);
+
+    assert_eq!(once, twice);
+}
+
+#[test]
+fn test_fallback_and_parser_agree_on_type_names() {
+    let parsed = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+message Order {
+  Item item = 1;
+}
+
+message Item {
+  string sku = 1;
+}
+"#;
+    // The unresolved `Missing` type forces the text fallback.
+    let fallback = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+message Order {
+  Item item = 1;
+  Missing missing = 2;
+}
+
+message Item {
+  string sku = 1;
+}
+"#;
+
+    let parsed_spec = Spec::try_from(parsed).expect("Failed to parse proto");
+    let fallback_spec = Spec::try_from_file_with_mode(
+        std::path::Path::new("order.proto"),
+        fallback,
+        ParseMode::BestEffort,
+    )
+    .expect("Fallback parsing should succeed");
+    assert!(fallback_spec.is_degraded());
+
+    assert_eq!(
+        field_type(&parsed_spec.canonical_file, "Order", 1),
+        ".acme.v1.Item"
+    );
+    assert_eq!(
+        field_type(&fallback_spec.canonical_file, "Order", 1),
+        field_type(&parsed_spec.canonical_file, "Order", 1)
+    );
+}
@@ -0,0 +1,87 @@
+use proto_sign::{generate_fingerprint, generate_wire_fingerprint};
+
+const BASE: &str = r#"
+syntax = "proto3";
+
+package acme.v1;
+
+message Order {
+  string id = 1;
+  repeated int32 quantities = 2;
+  Item item = 3;
+  Status status = 4;
+}
+
+message Item {
+  string sku = 1;
+  int64 price = 2;
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_OPEN = 1;
+}
+
+service OrderService {
+  rpc Get(Order) returns (Order);
+}
+"#;
+
+fn wire(proto: &str) -> String {
+    generate_wire_fingerprint(proto).expect("Failed to fingerprint proto")
+}
+
+#[test]
+fn test_wire_fingerprint_has_its_own_prefix() {
+    assert!(wire(BASE).starts_with("wire1-sha256:"));
+}
+
+#[test]
+fn test_renames_keep_wire_fingerprint() {
+    let renamed = BASE
+        .replace("string id = 1;", "string order_id = 1;")
+        .replace("Item", "LineItem")
+        .replace("STATUS_OPEN", "STATUS_ACTIVE");
+
+    assert_ne!(
+        generate_fingerprint(BASE).unwrap(),
+        generate_fingerprint(&renamed).unwrap()
+    );
+    assert_eq!(wire(BASE), wire(&renamed));
+}
+
+#[test]
+fn test_encoding_changes_change_wire_fingerprint() {
+    for (from, to) in [
+        ("string id = 1;", "string id = 5;"),
+        ("int64 price = 2;", "sint64 price = 2;"),
+        ("int64 price = 2;", "fixed64 price = 2;"),
+        (
+            "repeated int32 quantities = 2;",
+            "repeated int32 quantities = 2 [packed = false];",
+        ),
+        ("STATUS_OPEN = 1;", "STATUS_OPEN = 2;"),
+        ("rpc Get(Order)", "rpc Fetch(Order)"),
+    ] {
+        assert_ne!(wire(BASE), wire(&BASE.replace(from, to)), "{from} -> {to}");
+    }
+}
+
+#[test]
+fn test_compatible_scalar_changes_keep_wire_fingerprint() {
+    let changed = BASE
+        .replace("int64 price = 2;", "uint64 price = 2;")
+        .replace("string sku = 1;", "bytes sku = 1;");
+
+    assert_eq!(wire(BASE), wire(&changed));
+}
+
+#[test]
+fn test_recursive_messages_are_fingerprinted() {
+    let tree = "syntax = \"proto3\";\nmessage Node {\n  repeated Node children = 1;\n  Leaf leaf = 2;\n}\nmessage Leaf {\n  Node parent = 1;\n}\n";
+    let renamed = tree.replace("Node", "Tree").replace("children", "kids");
+    let changed = tree.replace("Node parent = 1;", "Leaf parent = 1;");
+
+    assert_eq!(wire(tree), wire(&renamed));
+    assert_ne!(wire(tree), wire(&changed));
+}